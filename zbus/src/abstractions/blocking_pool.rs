@@ -0,0 +1,59 @@
+use std::{fmt, future::Future, io::Result};
+
+use super::{async_lock::Semaphore, Task};
+
+/// A pool of threads for running blocking code.
+///
+/// The threads themselves are provided by the runtime (the `blocking` crate's thread pool or
+/// tokio's blocking thread pool). This type only limits the number of jobs that are allowed to run
+/// concurrently, so that a flood of blocking calls can't exhaust the threads of the underlying
+/// pool.
+pub(crate) struct BlockingPool {
+    semaphore: Semaphore,
+    size: usize,
+}
+
+impl BlockingPool {
+    /// Create a new pool, running at most `size` jobs concurrently.
+    pub fn new(size: usize) -> Self {
+        Self {
+            semaphore: Semaphore::new(size),
+            size,
+        }
+    }
+
+    /// The maximum number of jobs that can run concurrently.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Run `future` to completion on a thread of the pool.
+    ///
+    /// This waits for a slot in the pool to become available first. Under tokio, the future is run
+    /// in the context of the current runtime so it can make use of tokio's APIs.
+    pub async fn block_on<F>(&self, future: F, name: &str) -> Result<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let _permit = self.semaphore.acquire().await;
+
+        #[cfg(not(feature = "tokio"))]
+        let job = move || async_io::block_on(future);
+        #[cfg(feature = "tokio")]
+        let job = {
+            let handle = tokio::runtime::Handle::current();
+            move || handle.block_on(future)
+        };
+
+        Task::spawn_blocking(job, name).await
+    }
+}
+
+impl fmt::Debug for BlockingPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BlockingPool")
+            .field("size", &self.size)
+            .finish_non_exhaustive()
+    }
+}
@@ -6,6 +6,7 @@ mod executor;
 pub use executor::*;
 mod async_drop;
 pub(crate) mod async_lock;
+pub(crate) mod blocking_pool;
 pub use async_drop::*;
 pub(crate) mod timeout;
 
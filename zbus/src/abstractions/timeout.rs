@@ -33,3 +33,11 @@ where
     })
     .await
 }
+
+/// Sleeps for the provided duration.
+pub(crate) async fn sleep(duration: Duration) {
+    #[cfg(feature = "tokio")]
+    tokio::time::sleep(duration).await;
+    #[cfg(not(feature = "tokio"))]
+    async_io::Timer::after(duration).await;
+}
@@ -103,6 +103,37 @@ impl Address {
         }
     }
 
+    /// Get the address of the bus that started the current process through D-Bus activation.
+    ///
+    /// This respects the `DBUS_STARTER_ADDRESS` environment variable, and falls back to the
+    /// address of the bus named by the `DBUS_STARTER_BUS_TYPE` environment variable (see
+    /// [`Address::session`] and [`Address::system`]). Fails if neither is set, which means the
+    /// process wasn't activated by a bus.
+    pub fn starter() -> Result<Self> {
+        Self::starter_from(
+            env::var("DBUS_STARTER_ADDRESS").ok(),
+            env::var("DBUS_STARTER_BUS_TYPE").ok(),
+        )
+    }
+
+    /// [`Address::starter`], given the values of the environment variables.
+    fn starter_from(address: Option<String>, bus_type: Option<String>) -> Result<Self> {
+        if let Some(address) = address {
+            return Self::from_str(&address);
+        }
+
+        match bus_type.as_deref() {
+            Some("session") | Some("user") => Self::session(),
+            Some("system") => Self::system(),
+            Some(bus_type) => Err(Error::Address(format!(
+                "unknown bus type `{bus_type}` in `DBUS_STARTER_BUS_TYPE`"
+            ))),
+            None => Err(Error::Address(
+                "neither `DBUS_STARTER_ADDRESS` nor `DBUS_STARTER_BUS_TYPE` is set".into(),
+            )),
+        }
+    }
+
     /// The GUID for this address, if known.
     pub fn guid(&self) -> Option<&Guid<'_>> {
         self.guid.as_ref().map(|guid| guid.inner())
@@ -404,6 +435,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn starter_address() {
+        let unix = "unix:path=/tmp/dbus-starter";
+        // The address takes precedence over the bus type.
+        assert_eq!(
+            Address::starter_from(Some(unix.into()), Some("system".into())).unwrap(),
+            Address::from_str(unix).unwrap()
+        );
+        assert_eq!(
+            Address::starter_from(None, Some("system".into())).unwrap(),
+            Address::system().unwrap()
+        );
+        assert!(Address::starter_from(None, Some("starter".into())).is_err());
+        assert!(Address::starter_from(None, None).is_err());
+        assert!(Address::starter_from(Some("foo".into()), None).is_err());
+    }
+
     #[test]
     fn connect_tcp() {
         let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
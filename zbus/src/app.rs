@@ -0,0 +1,759 @@
+//! Single-instance applications.
+//!
+//! This module provides [`SingleInstance`], a helper for applications that should only run once
+//! at a time, with subsequent invocations forwarding their request to the running instance.
+//!
+//! The first instance to start becomes the [`Primary`] instance. It owns the well-known name of
+//! the application on the session bus, requested with [`RequestNameFlags::DoNotQueue`], and
+//! exports a small activation interface. Any instance started afterwards becomes a [`Secondary`]
+//! instance, which forwards its arguments, environment, working directory, and its standard input
+//! and output file descriptors to the primary instance, through the activation interface. The
+//! primary instance handles these as [`Activation`]s and replies with an exit code, that the
+//! secondary instance is expected to exit with.
+//!
+//! When the session bus is unavailable, a peer-to-peer socket can be used as a fallback, through
+//! `SingleInstance::p2p_fallback`. This requires the `p2p` feature.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use futures_util::StreamExt;
+//! use std::io::Write;
+//! use zbus::app::{Instance, SingleInstance};
+//!
+//! # zbus::block_on(async {
+//! match SingleInstance::new("org.zbus.MyApp")?.start().await? {
+//!     Instance::Primary(mut primary) => {
+//!         while let Some(activation) = primary.next().await {
+//!             println!("Activated with {:?}", activation.args());
+//!             if let Some(stdout) = activation.stdout() {
+//!                 let mut stdout = std::fs::File::from(stdout.try_clone_to_owned()?);
+//!                 writeln!(stdout, "Handled by the running instance")?;
+//!             }
+//!             activation.reply(0);
+//!         }
+//!     }
+//!     Instance::Secondary(secondary) => {
+//!         let exit_code = secondary.forward().await?;
+//!         std::process::exit(exit_code);
+//!     }
+//! }
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! # }).unwrap();
+//! ```
+
+use event_listener::{Event, EventListener};
+use futures_core::{ready, stream};
+use std::{
+    collections::{HashMap, VecDeque},
+    ffi::OsString,
+    future::Future,
+    os::{
+        fd::{AsFd, BorrowedFd},
+        unix::ffi::{OsStrExt, OsStringExt},
+    },
+    path::{Path, PathBuf},
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, OnceLock,
+    },
+    task::{Context, Poll},
+};
+use zbus_names::{BusName, WellKnownName};
+use zvariant::{Fd, OwnedFd};
+
+use crate::{fdo, fdo::RequestNameFlags, interface, Connection, Error, Result};
+
+const PATH: &str = "/org/zbus/SingleInstance";
+const INTERFACE: &str = "org.zbus.SingleInstance1";
+
+/// A single-instance application.
+///
+/// See the [module documentation](self) for details.
+#[derive(Debug, Clone)]
+pub struct SingleInstance {
+    name: WellKnownName<'static>,
+    #[cfg(feature = "p2p")]
+    p2p_fallback: Option<PathBuf>,
+}
+
+impl SingleInstance {
+    /// Create a new `SingleInstance` for the application with the well-known name `name`.
+    pub fn new<'n, N>(name: N) -> Result<Self>
+    where
+        N: TryInto<WellKnownName<'n>>,
+        N::Error: Into<Error>,
+    {
+        Ok(Self {
+            name: name.try_into().map_err(Into::into)?.into_owned(),
+            #[cfg(feature = "p2p")]
+            p2p_fallback: None,
+        })
+    }
+
+    /// Use a peer-to-peer socket at `path` if the session bus is unavailable.
+    ///
+    /// The primary instance listens on the socket, and secondary instances connect to it. A stale
+    /// socket, left behind by a primary instance that didn't exit cleanly, is replaced. A lock file
+    /// next to the socket, with the `lock` extension, serializes the instances starting at the
+    /// same time.
+    #[cfg(feature = "p2p")]
+    pub fn p2p_fallback<P>(mut self, path: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        self.p2p_fallback = Some(path.into());
+
+        self
+    }
+
+    /// The well-known name of the application.
+    pub fn name(&self) -> &WellKnownName<'static> {
+        &self.name
+    }
+
+    /// Determine whether this is the primary or a secondary instance.
+    pub async fn start(self) -> Result<Instance> {
+        match Connection::session().await {
+            Ok(conn) => self.start_on_bus(conn).await,
+            #[cfg(feature = "p2p")]
+            Err(e) => match self.p2p_fallback {
+                Some(path) => {
+                    tracing::debug!("Session bus unavailable ({e}), using the p2p fallback");
+
+                    p2p::start(path).await
+                }
+                None => Err(e),
+            },
+            #[cfg(not(feature = "p2p"))]
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn start_on_bus(self, conn: Connection) -> Result<Instance> {
+        let queue = Arc::new(Queue::default());
+        // Export the interface first so that no activation can come in before it's there.
+        conn.object_server()
+            .at(
+                PATH,
+                ActivationInterface {
+                    queue: queue.clone(),
+                },
+            )
+            .await?;
+
+        match conn
+            .request_name_with_flags(&self.name, RequestNameFlags::DoNotQueue.into())
+            .await
+        {
+            Ok(_) => Ok(Instance::Primary(Primary::new(Some(conn), queue))),
+            Err(Error::NameTaken) => {
+                conn.object_server()
+                    .remove::<ActivationInterface, _>(PATH)
+                    .await?;
+
+                Ok(Instance::Secondary(Secondary {
+                    connection: conn,
+                    destination: Some(self.name.into()),
+                }))
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// The role of an instance of a [`SingleInstance`] application.
+#[derive(Debug)]
+pub enum Instance {
+    /// The instance is the running instance of the application.
+    Primary(Primary),
+    /// Another instance of the application is running.
+    Secondary(Secondary),
+}
+
+/// The primary instance of a [`SingleInstance`] application.
+///
+/// This is a [`stream::Stream`] of the [`Activation`]s forwarded by the secondary instances.
+/// Dropping it makes the pending and future activations fail.
+#[derive(Debug)]
+pub struct Primary {
+    connection: Option<Connection>,
+    queue: Arc<Queue>,
+    listener: Option<EventListener>,
+    #[cfg(feature = "p2p")]
+    p2p: Option<p2p::Server>,
+}
+
+impl Primary {
+    fn new(connection: Option<Connection>, queue: Arc<Queue>) -> Self {
+        Self {
+            connection,
+            queue,
+            listener: None,
+            #[cfg(feature = "p2p")]
+            p2p: None,
+        }
+    }
+
+    /// The connection to the session bus, owning the name of the application.
+    ///
+    /// This is `None` when the peer-to-peer fallback is used.
+    pub fn connection(&self) -> Option<&Connection> {
+        self.connection.as_ref()
+    }
+}
+
+impl stream::Stream for Primary {
+    type Item = Activation;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            if let Some(activation) = this.queue.pop() {
+                this.listener = None;
+
+                return Poll::Ready(Some(activation));
+            }
+
+            match &mut this.listener {
+                Some(listener) => {
+                    ready!(Pin::new(listener).poll(cx));
+                    this.listener = None;
+                }
+                // Listen before checking again, so we don't miss a notification in between.
+                None => this.listener = Some(this.queue.event.listen()),
+            }
+        }
+    }
+}
+
+impl Drop for Primary {
+    fn drop(&mut self) {
+        self.queue.close();
+    }
+}
+
+/// A secondary instance of a [`SingleInstance`] application.
+#[derive(Debug)]
+pub struct Secondary {
+    connection: Connection,
+    // `None` on peer-to-peer connections.
+    destination: Option<BusName<'static>>,
+}
+
+impl Secondary {
+    /// The connection to the primary instance, directly or through the bus.
+    pub fn connection(&self) -> &Connection {
+        &self.connection
+    }
+
+    /// Forward the request of the current process to the primary instance.
+    ///
+    /// Same as [`Secondary::forward_args`], with the command line arguments of the process,
+    /// without the program name.
+    pub async fn forward(&self) -> Result<i32> {
+        self.forward_args(std::env::args().skip(1)).await
+    }
+
+    /// Forward a request with the given arguments to the primary instance.
+    ///
+    /// Along with `args`, the environment and the working directory of the current process are
+    /// forwarded, as well as its standard input and output file descriptors. Environment
+    /// variables that are not valid UTF-8 are skipped.
+    ///
+    /// Returns the exit code the primary instance replied with.
+    pub async fn forward_args<I, S>(&self, args: I) -> Result<i32>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let args: Vec<String> = args.into_iter().map(Into::into).collect();
+        let environment: HashMap<String, String> = std::env::vars_os()
+            .filter_map(|(key, value)| Some((key.into_string().ok()?, value.into_string().ok()?)))
+            .collect();
+        let working_directory = std::env::current_dir()?;
+        let (stdin, stdout) = (std::io::stdin(), std::io::stdout());
+        let fds: HashMap<&str, Fd<'_>> = [("stdin", stdin.as_fd()), ("stdout", stdout.as_fd())]
+            .into_iter()
+            // Closed descriptors can't be sent.
+            .filter(|(_, fd)| rustix::io::fcntl_getfd(fd).is_ok())
+            .map(|(name, fd)| (name, Fd::from(fd)))
+            .collect();
+
+        self.connection
+            .call_method(
+                self.destination.as_ref(),
+                PATH,
+                Some(INTERFACE),
+                "Activate",
+                &(
+                    args,
+                    environment,
+                    working_directory.as_os_str().as_bytes(),
+                    fds,
+                ),
+            )
+            .await?
+            .body()
+            .deserialize()
+    }
+}
+
+/// A request forwarded by a secondary instance of a [`SingleInstance`] application.
+///
+/// Reply with [`Activation::reply`]. Dropping an activation without replying makes the request
+/// fail in the secondary instance.
+#[derive(Debug)]
+pub struct Activation {
+    args: Vec<String>,
+    environment: HashMap<String, String>,
+    working_directory: PathBuf,
+    stdin: Option<OwnedFd>,
+    stdout: Option<OwnedFd>,
+    reply: Arc<Reply>,
+}
+
+impl Activation {
+    /// The arguments of the secondary instance, without the program name.
+    pub fn args(&self) -> &[String] {
+        &self.args
+    }
+
+    /// The environment of the secondary instance.
+    pub fn environment(&self) -> &HashMap<String, String> {
+        &self.environment
+    }
+
+    /// The working directory of the secondary instance.
+    pub fn working_directory(&self) -> &Path {
+        &self.working_directory
+    }
+
+    /// The standard input of the secondary instance, if it was open.
+    pub fn stdin(&self) -> Option<BorrowedFd<'_>> {
+        self.stdin.as_ref().map(|fd| fd.as_fd())
+    }
+
+    /// The standard output of the secondary instance, if it was open.
+    pub fn stdout(&self) -> Option<BorrowedFd<'_>> {
+        self.stdout.as_ref().map(|fd| fd.as_fd())
+    }
+
+    /// Reply to the secondary instance, with the code it should exit with.
+    pub fn reply(self, exit_code: i32) {
+        self.reply.set(Some(exit_code));
+    }
+}
+
+impl Drop for Activation {
+    fn drop(&mut self) {
+        // No-op if already replied to.
+        self.reply.set(None);
+    }
+}
+
+/// The activations waiting to be handled by the primary instance.
+#[derive(Debug, Default)]
+struct Queue {
+    activations: Mutex<VecDeque<Activation>>,
+    closed: AtomicBool,
+    event: Event,
+}
+
+impl Queue {
+    /// Queue `activation`, or give it back if the primary instance is gone.
+    fn push(&self, activation: Activation) -> std::result::Result<(), Activation> {
+        let mut activations = self.activations.lock().expect("poisoned lock");
+        if self.closed.load(Ordering::SeqCst) {
+            return Err(activation);
+        }
+        activations.push_back(activation);
+        self.event.notify(usize::MAX);
+
+        Ok(())
+    }
+
+    fn pop(&self) -> Option<Activation> {
+        self.activations.lock().expect("poisoned lock").pop_front()
+    }
+
+    fn close(&self) {
+        let pending = {
+            let mut activations = self.activations.lock().expect("poisoned lock");
+            self.closed.store(true, Ordering::SeqCst);
+
+            std::mem::take(&mut *activations)
+        };
+        // Fail the pending activations, outside of the lock.
+        drop(pending);
+    }
+}
+
+/// The reply to an activation. `None` if the activation was dropped without a reply.
+#[derive(Debug, Default)]
+struct Reply {
+    exit_code: OnceLock<Option<i32>>,
+    event: Event,
+}
+
+impl Reply {
+    fn set(&self, exit_code: Option<i32>) {
+        if self.exit_code.set(exit_code).is_ok() {
+            self.event.notify(usize::MAX);
+        }
+    }
+
+    async fn wait(&self) -> Option<i32> {
+        loop {
+            let listener = self.event.listen();
+            if let Some(exit_code) = self.exit_code.get() {
+                return *exit_code;
+            }
+            listener.await;
+        }
+    }
+}
+
+/// The interface exported by the primary instance.
+struct ActivationInterface {
+    queue: Arc<Queue>,
+}
+
+#[interface(name = "org.zbus.SingleInstance1")]
+impl ActivationInterface {
+    /// Forward the request of a secondary instance, and return the code it should exit with.
+    async fn activate(
+        &self,
+        args: Vec<String>,
+        environment: HashMap<String, String>,
+        working_directory: Vec<u8>,
+        mut fds: HashMap<String, OwnedFd>,
+    ) -> fdo::Result<i32> {
+        let reply = Arc::new(Reply::default());
+        let activation = Activation {
+            args,
+            environment,
+            working_directory: OsString::from_vec(working_directory).into(),
+            stdin: fds.remove("stdin"),
+            stdout: fds.remove("stdout"),
+            reply: reply.clone(),
+        };
+        if let Err(activation) = self.queue.push(activation) {
+            // Don't hold the reply until the end of the function.
+            drop(activation);
+
+            return Err(fdo::Error::Failed(
+                "The application is shutting down".into(),
+            ));
+        }
+
+        reply
+            .wait()
+            .await
+            .ok_or_else(|| fdo::Error::Failed("The activation was dropped without a reply".into()))
+    }
+}
+
+#[cfg(feature = "p2p")]
+mod p2p {
+    use std::{
+        fs::{File, OpenOptions},
+        io,
+        path::{Path, PathBuf},
+        sync::Arc,
+        time::Duration,
+    };
+    #[cfg(feature = "tokio")]
+    use tokio::net::UnixStream;
+    #[cfg(not(feature = "tokio"))]
+    use {async_io::Async, std::os::unix::net::UnixStream};
+
+    use tracing::{debug, warn};
+
+    use super::{ActivationInterface, Instance, Primary, Queue, Secondary, PATH};
+    use crate::{connection::Builder, timeout::sleep, Executor, Guid, Result, Task};
+
+    #[cfg(not(feature = "tokio"))]
+    type UnixListener = Async<std::os::unix::net::UnixListener>;
+    #[cfg(feature = "tokio")]
+    type UnixListener = tokio::net::UnixListener;
+
+    /// The listening side of the fallback.
+    #[derive(Debug)]
+    pub(super) struct Server {
+        path: PathBuf,
+        _task: Task<()>,
+    }
+
+    impl Drop for Server {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+
+    pub(super) async fn start(path: PathBuf) -> Result<Instance> {
+        // Checking for a live instance and replacing a stale socket must be atomic, or an instance
+        // could unlink the socket another one just bound.
+        let lock = lock(&path).await?;
+        let stream = match connect(&path).await {
+            Ok(stream) => Some(stream),
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    io::ErrorKind::ConnectionRefused | io::ErrorKind::NotFound
+                ) =>
+            {
+                None
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let listener = match stream {
+            Some(stream) => {
+                drop(lock);
+                let conn = Builder::unix_stream(stream).p2p().build().await?;
+
+                return Ok(Instance::Secondary(Secondary {
+                    connection: conn,
+                    destination: None,
+                }));
+            }
+            None => {
+                // Nobody is listening, so the socket (if any) is stale.
+                match std::fs::remove_file(&path) {
+                    Ok(()) => (),
+                    Err(e) if e.kind() == io::ErrorKind::NotFound => (),
+                    Err(e) => return Err(e.into()),
+                }
+                bind(&path)?
+            }
+        };
+        drop(lock);
+
+        let queue = Arc::new(Queue::default());
+        let executor = Executor::new();
+        let task = executor.spawn(
+            serve(listener, queue.clone(), executor.clone()),
+            "single instance server",
+        );
+        #[cfg(not(feature = "tokio"))]
+        std::thread::Builder::new()
+            .name("zbus::app::SingleInstance server".into())
+            .spawn(move || {
+                crate::utils::block_on(async move {
+                    // Run as long as there is a task to run.
+                    while !executor.is_empty() {
+                        executor.tick().await;
+                    }
+                })
+            })?;
+
+        let mut primary = Primary::new(None, queue);
+        primary.p2p = Some(Server { path, _task: task });
+
+        Ok(Instance::Primary(primary))
+    }
+
+    async fn serve(listener: UnixListener, queue: Arc<Queue>, executor: Executor<'static>) {
+        loop {
+            let stream = match accept(&listener).await {
+                Ok(stream) => stream,
+                Err(e) => {
+                    warn!("Failed to accept a connection: {e}");
+                    // Errors such as running out of file descriptors don't go away right away.
+                    sleep(Duration::from_millis(100)).await;
+
+                    continue;
+                }
+            };
+            let iface = ActivationInterface {
+                queue: queue.clone(),
+            };
+            // Don't let a peer stall the handshake of the others.
+            executor
+                .spawn(
+                    async move {
+                        let conn = match Builder::unix_stream(stream)
+                            .server(Guid::generate())
+                            .and_then(|b| b.p2p().serve_at(PATH, iface))
+                        {
+                            Ok(builder) => builder.build().await,
+                            Err(e) => Err(e),
+                        };
+                        match conn {
+                            // Keep the connection around until the peer is done with it.
+                            Ok(conn) => {
+                                conn.closed().await;
+                            }
+                            Err(e) => debug!("Failed to establish a connection: {e}"),
+                        }
+                    },
+                    "single instance connection",
+                )
+                .detach();
+        }
+    }
+
+    /// Take an exclusive lock on the lock file next to the socket at `path`.
+    ///
+    /// The lock is released when the returned file is dropped.
+    async fn lock(path: &Path) -> io::Result<File> {
+        use rustix::{
+            fs::{flock, FlockOperation},
+            io::Errno,
+        };
+
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path.with_extension("lock"))?;
+        loop {
+            match flock(&file, FlockOperation::NonBlockingLockExclusive) {
+                Ok(()) => return Ok(file),
+                // Only held briefly, while the other instance checks the socket.
+                Err(Errno::WOULDBLOCK) => sleep(Duration::from_millis(10)).await,
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    async fn connect(path: &PathBuf) -> io::Result<UnixStream> {
+        #[cfg(not(feature = "tokio"))]
+        {
+            Async::<UnixStream>::connect(path).await?.into_inner()
+        }
+
+        #[cfg(feature = "tokio")]
+        {
+            UnixStream::connect(path).await
+        }
+    }
+
+    fn bind(path: &PathBuf) -> io::Result<UnixListener> {
+        UnixListener::bind(path)
+    }
+
+    async fn accept(listener: &UnixListener) -> io::Result<UnixStream> {
+        #[cfg(not(feature = "tokio"))]
+        {
+            listener.accept().await?.0.into_inner()
+        }
+
+        #[cfg(feature = "tokio")]
+        {
+            listener.accept().await.map(|(stream, _)| stream)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::StreamExt;
+    use ntest::timeout;
+    use std::time::Duration;
+    use test_log::test;
+
+    use super::*;
+
+    fn primary(instance: Instance) -> Primary {
+        match instance {
+            Instance::Primary(primary) => primary,
+            Instance::Secondary(_) => panic!("expected a primary instance"),
+        }
+    }
+
+    fn secondary(instance: Instance) -> Secondary {
+        match instance {
+            Instance::Secondary(secondary) => secondary,
+            Instance::Primary(_) => panic!("expected a secondary instance"),
+        }
+    }
+
+    async fn activate(primary: &mut Primary, secondary: &Secondary) {
+        let (exit_code, ()) =
+            futures_util::join!(secondary.forward_args(["--open", "file.txt"]), async {
+                let activation = primary.next().await.unwrap();
+                assert_eq!(activation.args(), ["--open", "file.txt"]);
+                assert_eq!(
+                    activation.working_directory(),
+                    std::env::current_dir().unwrap()
+                );
+                let environment: HashMap<String, String> = std::env::vars().collect();
+                assert_eq!(activation.environment(), &environment);
+                assert!(activation.stdout().is_some());
+                activation.reply(42);
+            });
+        assert_eq!(exit_code.unwrap(), 42);
+
+        // Dropping the activation fails the request.
+        let (res, ()) = futures_util::join!(secondary.forward_args(["--fail"]), async {
+            drop(primary.next().await.unwrap());
+        });
+        match res {
+            Err(Error::MethodError(name, _, _)) => {
+                assert_eq!(name, "org.freedesktop.DBus.Error.Failed")
+            }
+            res => panic!("unexpected result: {res:?}"),
+        }
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn single_instance() {
+        crate::block_on(single_instance_async());
+    }
+
+    async fn single_instance_async() {
+        let app = SingleInstance::new("org.zbus.SingleInstanceTest").unwrap();
+        let mut first = primary(app.clone().start().await.unwrap());
+        let second = secondary(app.clone().start().await.unwrap());
+        activate(&mut first, &second).await;
+
+        // Dropping the primary instance releases the name.
+        drop(first);
+        let mut first = loop {
+            match app.clone().start().await.unwrap() {
+                Instance::Primary(primary) => break primary,
+                Instance::Secondary(_) => crate::timeout::sleep(Duration::from_millis(10)).await,
+            }
+        };
+        let second = secondary(app.start().await.unwrap());
+        activate(&mut first, &second).await;
+    }
+
+    #[cfg(feature = "p2p")]
+    #[test]
+    #[timeout(15000)]
+    fn single_instance_p2p() {
+        crate::block_on(single_instance_p2p_async());
+    }
+
+    #[cfg(feature = "p2p")]
+    async fn single_instance_p2p_async() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.socket");
+        // A socket left behind by a primary instance that's gone.
+        drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+
+        let mut first = primary(p2p::start(path.clone()).await.unwrap());
+        assert!(first.connection().is_none());
+        let second = secondary(p2p::start(path.clone()).await.unwrap());
+        activate(&mut first, &second).await;
+
+        // The socket goes away with the primary instance.
+        drop(first);
+        assert!(!path.exists());
+
+        // Of the instances starting at the same time, only one becomes the primary.
+        let (first, second) =
+            futures_util::join!(p2p::start(path.clone()), p2p::start(path.clone()));
+        let (mut first, second) = match (first.unwrap(), second.unwrap()) {
+            (Instance::Primary(first), Instance::Secondary(second))
+            | (Instance::Secondary(second), Instance::Primary(first)) => (first, second),
+            instances => panic!("unexpected instances: {instances:?}"),
+        };
+        activate(&mut first, &second).await;
+    }
+}
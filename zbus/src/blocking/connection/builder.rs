@@ -14,8 +14,14 @@ use zvariant::ObjectPath;
 #[cfg(feature = "p2p")]
 use crate::Guid;
 use crate::{
-    address::Address, blocking::Connection, conn::AuthMechanism, connection::socket::BoxedSplit,
-    names::WellKnownName, object_server::Interface, utils::block_on, Error, Result,
+    address::Address,
+    blocking::Connection,
+    conn::AuthMechanism,
+    connection::socket::BoxedSplit,
+    names::WellKnownName,
+    object_server::{DispatchPolicy, Interface},
+    utils::block_on,
+    Error, Result,
 };
 
 /// A builder for [`zbus::blocking::Connection`].
@@ -34,6 +40,13 @@ impl<'a> Builder<'a> {
         crate::connection::Builder::system().map(Self)
     }
 
+    /// Create a builder for a connection to the message bus that activated the current process.
+    ///
+    /// See [`crate::connection::Builder::starter`] for details.
+    pub fn starter() -> Result<Self> {
+        crate::connection::Builder::starter().map(Self)
+    }
+
     /// Create a builder for a connection that will use the given [D-Bus bus address].
     ///
     /// [D-Bus bus address]: https://dbus.freedesktop.org/doc/dbus-specification.html#addresses
@@ -211,6 +224,28 @@ impl<'a> Builder<'a> {
         Self(self.0.method_timeout(timeout))
     }
 
+    /// Set the maximum number of blocking interface methods that are run concurrently.
+    ///
+    /// Interface methods marked with `#[zbus(blocking)]` are run on a thread pool, so that they
+    /// don't stall the runtime while they run. This limits how many of them can be running at the
+    /// same time. Additional calls wait for a running method to finish. Defaults to 16.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is 0.
+    pub fn blocking_pool_size(self, size: usize) -> Self {
+        Self(self.0.blocking_pool_size(size))
+    }
+
+    /// Set the policy the [`ObjectServer`] dispatches method calls with.
+    ///
+    /// See [`DispatchPolicy`] for details.
+    ///
+    /// [`ObjectServer`]: crate::blocking::ObjectServer
+    pub fn dispatch_policy(self, policy: DispatchPolicy) -> Self {
+        Self(self.0.dispatch_policy(policy))
+    }
+
     /// Build the connection, consuming the builder.
     ///
     /// # Errors
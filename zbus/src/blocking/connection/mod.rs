@@ -1,17 +1,21 @@
 //! Blocking connection API.
 
 use enumflags2::BitFlags;
-use event_listener::EventListener;
+use event_listener::{Event, EventListener};
+use futures_core::Stream;
+use futures_lite::StreamExt;
 use std::{io, ops::Deref};
+use tracing::warn;
 use zbus_names::{BusName, ErrorName, InterfaceName, MemberName, OwnedUniqueName, WellKnownName};
 use zvariant::ObjectPath;
 
 use crate::{
     blocking::ObjectServer,
+    connection::{DisconnectReason, NameEvent, WatchNameFlags},
     fdo::{ConnectionCredentials, RequestNameFlags, RequestNameReply},
     message::Message,
     utils::block_on,
-    DBusError, Error, Result,
+    DBusError, Error, MessageStream, OwnedMatchRule, Result,
 };
 
 mod builder;
@@ -210,6 +214,59 @@ impl Connection {
         block_on(self.inner.release_name(well_known_name))
     }
 
+    /// Watch the ownership of a bus name.
+    ///
+    /// Blocking version of [`crate::Connection::watch_name`]. See docs there for more details.
+    pub fn watch_name<'n, N>(&self, name: N) -> Result<NameWatcher>
+    where
+        N: TryInto<BusName<'n>>,
+        N::Error: Into<Error>,
+    {
+        block_on(self.inner.watch_name(name)).map(NameWatcher)
+    }
+
+    /// Watch the ownership of a bus name, with flags.
+    ///
+    /// Blocking version of [`crate::Connection::watch_name_with_flags`]. See docs there for more
+    /// details.
+    pub fn watch_name_with_flags<'n, N>(
+        &self,
+        name: N,
+        flags: BitFlags<WatchNameFlags>,
+    ) -> Result<NameWatcher>
+    where
+        N: TryInto<BusName<'n>>,
+        N::Error: Into<Error>,
+    {
+        block_on(self.inner.watch_name_with_flags(name, flags)).map(NameWatcher)
+    }
+
+    /// Call `handler` for each signal matching `rule`.
+    ///
+    /// Blocking version of [`crate::Connection::on_signal`], except that `handler` is called from
+    /// a thread dedicated to it, so it's free to block or to make blocking calls. The thread exits
+    /// once the returned [`SignalHandler`] is dropped, removing the match rule.
+    pub fn on_signal<R, F>(&self, rule: R, handler: F) -> Result<SignalHandler>
+    where
+        R: TryInto<OwnedMatchRule>,
+        R::Error: Into<Error>,
+        F: FnMut(Message) + Send + 'static,
+    {
+        let stream =
+            block_on(MessageStream::for_match_rule(rule, &self.inner, None))?.filter_map(|msg| {
+                match msg {
+                    Ok(msg) => Some(msg),
+                    Err(e) => {
+                        warn!("Error receiving signal: {e}");
+
+                        None
+                    }
+                }
+            });
+
+        SignalHandler::spawn(stream, handler)
+    }
+
     /// Check if `self` is a connection to a message bus.
     ///
     /// This will return `false` for p2p connections.
@@ -269,6 +326,13 @@ impl Connection {
         block_on(self.inner.close())
     }
 
+    /// Wait for the connection to be disconnected.
+    ///
+    /// Blocking version of [`crate::Connection::closed`]. See docs there for more details.
+    pub fn closed(&self) -> DisconnectReason {
+        block_on(self.inner.closed())
+    }
+
     /// Gracefully close the connection, waiting for all other references to be dropped.
     ///
     /// Blocking version of [`crate::Connection::graceful_shutdown`]. See docs there for
@@ -281,6 +345,12 @@ impl Connection {
     pub fn method_timeout(&self) -> Option<std::time::Duration> {
         self.inner.method_timeout()
     }
+
+    /// The maximum number of blocking interface methods run concurrently. See
+    /// [Builder::blocking_pool_size] for details.
+    pub fn blocking_pool_size(&self) -> usize {
+        self.inner.blocking_pool_size()
+    }
 }
 
 impl From<crate::Connection> for Connection {
@@ -289,6 +359,82 @@ impl From<crate::Connection> for Connection {
     }
 }
 
+/// An [`Iterator`] of the ownership changes of a bus name.
+///
+/// Blocking version of [`crate::connection::NameWatcher`]. Use [`Connection::watch_name`] to
+/// create an instance of this type.
+#[derive(Debug)]
+pub struct NameWatcher(crate::connection::NameWatcher);
+
+impl NameWatcher {
+    /// The bus name being watched.
+    pub fn name(&self) -> &BusName<'static> {
+        self.0.name()
+    }
+
+    /// The current owner of the name, as far as the events yielded so far tell.
+    pub fn owner(&self) -> Option<&OwnedUniqueName> {
+        self.0.owner()
+    }
+}
+
+impl Iterator for NameWatcher {
+    type Item = NameEvent;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        block_on(self.0.next())
+    }
+}
+
+/// A signal handler registered with [`Connection::on_signal`] or
+/// [`crate::blocking::Proxy::on_signal`].
+///
+/// Blocking version of [`crate::connection::SignalHandler`]. The handler is called from a thread
+/// dedicated to it, for as long as this guard is alive.
+#[derive(Debug)]
+#[must_use = "the signal handler is removed when the guard is dropped"]
+pub struct SignalHandler {
+    stop: Event,
+}
+
+impl SignalHandler {
+    /// Spawn a thread calling `handler` for each message of `stream`.
+    pub(crate) fn spawn<S, F>(stream: S, mut handler: F) -> Result<Self>
+    where
+        S: Stream<Item = Message> + Send + 'static,
+        F: FnMut(Message) + Send + 'static,
+    {
+        let stop = Event::new();
+        let mut stopped = stop.listen();
+        std::thread::Builder::new()
+            .name("zbus::SignalHandler".into())
+            .spawn(move || {
+                block_on(async move {
+                    let mut stream = std::pin::pin!(stream);
+                    loop {
+                        let next = futures_lite::future::or(stream.next(), async {
+                            (&mut stopped).await;
+
+                            None
+                        });
+                        match next.await {
+                            Some(msg) => handler(msg),
+                            None => break,
+                        }
+                    }
+                })
+            })?;
+
+        Ok(Self { stop })
+    }
+}
+
+impl Drop for SignalHandler {
+    fn drop(&mut self) {
+        self.stop.notify(usize::MAX);
+    }
+}
+
 #[cfg(feature = "p2p")]
 #[cfg(all(test, unix))]
 mod tests {
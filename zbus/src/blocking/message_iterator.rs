@@ -1,7 +1,8 @@
 use futures_lite::StreamExt;
 
 use crate::{
-    blocking::Connection, message::Message, utils::block_on, MatchRule, OwnedMatchRule, Result,
+    blocking::Connection, message::Message, utils::block_on, MatchRule, OverflowPolicy,
+    OwnedMatchRule, Result,
 };
 
 /// A blocking wrapper of [`crate::MessageStream`].
@@ -104,6 +105,23 @@ impl MessageIterator {
             .expect("Inner stream is `None`")
             .match_rule()
     }
+
+    /// Set the policy to apply when the queue of this iterator is full.
+    ///
+    /// See [`crate::MessageStream::set_overflow_policy`] for details.
+    pub fn set_overflow_policy(&mut self, policy: OverflowPolicy) {
+        block_on(
+            self.azync
+                .as_mut()
+                .expect("Inner stream is `None`")
+                .set_overflow_policy(policy),
+        )
+    }
+
+    /// The number of messages this iterator missed so far because its queue was full.
+    pub fn lagged(&self) -> u64 {
+        self.inner().lagged()
+    }
 }
 
 impl Iterator for MessageIterator {
@@ -1,9 +1,17 @@
 //! The object server API.
 
-use zvariant::ObjectPath;
+use std::{collections::HashMap, time::Duration};
+
+use futures_lite::StreamExt;
+use zbus_names::{InterfaceName, UniqueName};
+use zvariant::{ObjectPath, OwnedObjectPath, OwnedValue};
 
 use crate::{
-    object_server::{Interface, InterfaceDeref, InterfaceDerefMut, SignalEmitter},
+    fdo,
+    object_server::{
+        DispatchPolicy, IdleTracker, Interface, InterfaceDeref, InterfaceDerefMut,
+        RegistrationChange, RegistrationChangeStream, SignalEmitter,
+    },
     utils::block_on,
     Error, Result,
 };
@@ -79,6 +87,90 @@ where
     }
 }
 
+/// A blocking wrapper of [`crate::object_server::InterfaceHandle`].
+///
+/// Use [`ObjectServer::interface_handle`] to get an instance of this type.
+#[derive(Debug, Clone)]
+pub struct InterfaceHandle {
+    azync: crate::object_server::InterfaceHandle,
+}
+
+impl InterfaceHandle {
+    /// The name of the interface.
+    pub fn name(&self) -> &InterfaceName<'static> {
+        self.azync.name()
+    }
+
+    /// The object path the interface is registered at.
+    pub fn path(&self) -> &ObjectPath<'static> {
+        self.azync.path()
+    }
+
+    /// The signal emitter for the object the interface is registered at.
+    pub fn signal_emitter(&self) -> &SignalEmitter<'static> {
+        self.azync.signal_emitter()
+    }
+
+    /// Whether the interface is of type `I`.
+    pub fn is<I>(&self) -> bool
+    where
+        I: Interface,
+    {
+        block_on(self.azync.is::<I>())
+    }
+
+    /// Get an [`InterfaceRef`] for the interface, if it is of type `I`.
+    pub fn downcast<I>(&self) -> Option<InterfaceRef<I>>
+    where
+        I: Interface,
+    {
+        block_on(self.azync.downcast()).map(|azync| InterfaceRef { azync })
+    }
+
+    /// Get the value of a property of the interface.
+    ///
+    /// Returns `None` if the property doesn't exist.
+    pub fn get_property(&self, property_name: &str) -> Option<fdo::Result<OwnedValue>> {
+        block_on(self.azync.get_property(property_name))
+    }
+
+    /// Get the values of all the properties of the interface.
+    pub fn get_all_properties(&self) -> fdo::Result<HashMap<String, OwnedValue>> {
+        block_on(self.azync.get_all_properties())
+    }
+
+    /// The introspection XML of the interface.
+    pub fn introspect(&self) -> String {
+        block_on(self.azync.introspect())
+    }
+
+    /// Get a reference to the underlying async `InterfaceHandle`.
+    pub fn inner(&self) -> &crate::object_server::InterfaceHandle {
+        &self.azync
+    }
+
+    /// Get the underlying async `InterfaceHandle`, consuming `self`.
+    pub fn into_inner(self) -> crate::object_server::InterfaceHandle {
+        self.azync
+    }
+}
+
+/// An [`Iterator`] of [`RegistrationChange`]s.
+///
+/// Use [`ObjectServer::receive_registration_changes`] to create an instance of this type.
+#[derive(Debug)]
+pub struct RegistrationChangeIterator {
+    azync: RegistrationChangeStream,
+}
+
+impl Iterator for RegistrationChangeIterator {
+    type Item = RegistrationChange;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        block_on(self.azync.next())
+    }
+}
+
 /// A blocking wrapper of [`crate::ObjectServer`].
 ///
 /// # Example
@@ -221,6 +313,105 @@ impl ObjectServer {
         })
     }
 
+    /// Register an already registered interface at an additional path.
+    ///
+    /// See [`crate::ObjectServer::alias`] for details.
+    pub fn alias<'p, P, I>(&self, path: P, iface: &InterfaceRef<I>) -> Result<bool>
+    where
+        I: Interface,
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+    {
+        block_on(self.azync.alias(path, &iface.azync))
+    }
+
+    /// Enable or disable the emission of the signals of the interface `I`, at the given path.
+    ///
+    /// See [`crate::ObjectServer::set_signal_emission`] for details.
+    pub fn set_signal_emission<'p, P, I>(&self, path: P, enabled: bool) -> Result<()>
+    where
+        I: Interface,
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+    {
+        block_on(self.azync.set_signal_emission::<P, I>(path, enabled))
+    }
+
+    /// Bind the lifetime of the object at the given path to a client.
+    ///
+    /// See [`crate::ObjectServer::bind_to_client`] for details.
+    pub fn bind_to_client<'p, 'c, P, C>(&self, path: P, client: C) -> Result<()>
+    where
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+        C: TryInto<UniqueName<'c>>,
+        C::Error: Into<Error>,
+    {
+        block_on(self.azync.bind_to_client(path, client))
+    }
+
+    /// Create an [`IdleTracker`], to find out when the service is no longer in use.
+    ///
+    /// See [`crate::ObjectServer::idle_tracker`] for details. Use [`crate::block_on`] to wait on
+    /// [`IdleTracker::idle`].
+    pub fn idle_tracker(&self, timeout: Duration) -> IdleTracker {
+        self.azync.idle_tracker(timeout)
+    }
+
+    /// Set the policy method calls are dispatched with.
+    ///
+    /// See [`crate::ObjectServer::set_dispatch_policy`] for details.
+    pub fn set_dispatch_policy(&self, policy: DispatchPolicy) {
+        self.azync.set_dispatch_policy(policy)
+    }
+
+    /// The policy method calls are dispatched with.
+    pub fn dispatch_policy(&self) -> DispatchPolicy {
+        self.azync.dispatch_policy()
+    }
+
+    /// The paths of all the objects served.
+    ///
+    /// See [`crate::ObjectServer::paths`] for details.
+    pub fn paths(&self) -> Vec<OwnedObjectPath> {
+        block_on(self.azync.paths())
+    }
+
+    /// The names of all the interfaces registered at the given path.
+    ///
+    /// See [`crate::ObjectServer::interface_names`] for details.
+    pub fn interface_names<'p, P>(&self, path: P) -> Result<Vec<InterfaceName<'static>>>
+    where
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+    {
+        block_on(self.azync.interface_names(path))
+    }
+
+    /// Get a handle to the interface with the given name, at the given path.
+    ///
+    /// See [`crate::ObjectServer::interface_handle`] for details.
+    pub fn interface_handle<'p, 'i, P, N>(&self, path: P, name: N) -> Result<InterfaceHandle>
+    where
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+        N: TryInto<InterfaceName<'i>>,
+        N::Error: Into<Error>,
+    {
+        Ok(InterfaceHandle {
+            azync: block_on(self.azync.interface_handle(path, name))?,
+        })
+    }
+
+    /// Receive the changes to the registered interfaces.
+    ///
+    /// See [`crate::ObjectServer::receive_registration_changes`] for details.
+    pub fn receive_registration_changes(&self) -> RegistrationChangeIterator {
+        RegistrationChangeIterator {
+            azync: self.azync.receive_registration_changes(),
+        }
+    }
+
     /// Get a reference to the underlying async ObjectServer.
     pub fn inner(&self) -> &crate::ObjectServer {
         &self.azync
@@ -1,7 +1,12 @@
 use zbus_names::{BusName, InterfaceName};
 use zvariant::ObjectPath;
 
-use crate::{blocking::Connection, proxy::CacheProperties, utils::block_on, Error, Result};
+use crate::{
+    blocking::Connection,
+    proxy::{CacheProperties, PropertyCachePolicy, RetryPolicy},
+    utils::block_on,
+    Error, Result,
+};
 
 pub use crate::proxy::Defaults;
 
@@ -49,6 +54,30 @@ impl<'a, T> Builder<'a, T> {
         Self(self.0.uncached_properties(properties))
     }
 
+    /// Set the cache policy of all the properties.
+    ///
+    /// See [`crate::proxy::Builder::cache_policy`] for details.
+    #[must_use]
+    pub fn cache_policy(self, policy: PropertyCachePolicy) -> Self {
+        Self(self.0.cache_policy(policy))
+    }
+
+    /// Set the cache policy of the `property` property.
+    ///
+    /// See [`crate::proxy::Builder::property_cache_policy`] for details.
+    #[must_use]
+    pub fn property_cache_policy(self, property: &str, policy: PropertyCachePolicy) -> Self {
+        Self(self.0.property_cache_policy(property, policy))
+    }
+
+    /// Set the policy for retrying method calls that fail with a transient error.
+    ///
+    /// By default, method calls are not retried. See [`RetryPolicy`] for details.
+    #[must_use]
+    pub fn retry_policy(self, policy: RetryPolicy) -> Self {
+        Self(self.0.retry_policy(policy))
+    }
+
     /// Build a proxy from the builder.
     ///
     /// # Panics
@@ -7,9 +7,9 @@ use zbus_names::{BusName, InterfaceName, MemberName, UniqueName};
 use zvariant::{ObjectPath, OwnedValue, Value};
 
 use crate::{
-    blocking::Connection,
+    blocking::{connection::SignalHandler, Connection},
     message::Message,
-    proxy::{Defaults, MethodFlags},
+    proxy::{Defaults, MethodFlags, PropertyCacheState, RetryPolicy},
     utils::block_on,
     Error, Result,
 };
@@ -153,6 +153,11 @@ impl<'a> Proxy<'a> {
         self.inner().interface()
     }
 
+    /// Get a reference to the policy for retrying failed method calls, if any.
+    pub fn retry_policy(&self) -> Option<&RetryPolicy> {
+        self.inner().retry_policy()
+    }
+
     /// Introspect the associated object, and return the XML description.
     ///
     /// See the [xml](https://docs.rs/zbus_xml) crate for parsing the result.
@@ -197,6 +202,30 @@ impl<'a> Proxy<'a> {
         block_on(self.inner().get_property(property_name))
     }
 
+    /// Get all the properties, as a [`crate::Properties`] implementation.
+    ///
+    /// See [`crate::Proxy::get_all_as`] for details.
+    pub fn get_all_as<T>(&self) -> Result<T>
+    where
+        T: crate::Properties,
+    {
+        block_on(self.inner().get_all_as())
+    }
+
+    /// Fetch all the properties from the peer and update the cache with them.
+    ///
+    /// See [`crate::Proxy::refresh_properties`] for details.
+    pub fn refresh_properties(&self) -> Result<()> {
+        block_on(self.inner().refresh_properties())
+    }
+
+    /// The state of the properties cache.
+    ///
+    /// See [`crate::Proxy::property_cache_state`] for details.
+    pub fn property_cache_state(&self) -> PropertyCacheState {
+        self.inner().property_cache_state()
+    }
+
     /// Set the property `property_name`.
     ///
     /// Effectively, call the `Set` method of the `org.freedesktop.DBus.Properties` interface.
@@ -262,6 +291,29 @@ impl<'a> Proxy<'a> {
         block_on(self.inner().call_with_flags(method_name, flags, body))
     }
 
+    /// Same as [`Proxy::call_with_flags`], but with `retry_policy` used instead of the policy the
+    /// proxy was built with.
+    ///
+    /// See [`crate::Proxy::call_with_retry_policy`] for details.
+    pub fn call_with_retry_policy<'m, M, B, R>(
+        &self,
+        method_name: M,
+        flags: BitFlags<MethodFlags>,
+        retry_policy: Option<&RetryPolicy>,
+        body: &B,
+    ) -> Result<Option<R>>
+    where
+        M: TryInto<MemberName<'m>>,
+        M::Error: Into<Error>,
+        B: serde::ser::Serialize + zvariant::DynamicType,
+        R: for<'d> zvariant::DynamicDeserialize<'d>,
+    {
+        block_on(
+            self.inner()
+                .call_with_retry_policy(method_name, flags, retry_policy, body),
+        )
+    }
+
     /// Call a method without expecting a reply.
     ///
     /// This sets the `NoReplyExpected` flag on the calling message and does not wait for a reply.
@@ -289,6 +341,22 @@ impl<'a> Proxy<'a> {
         self.receive_signal_with_args(signal_name, &[])
     }
 
+    /// Call `handler` for each signal named `signal_name`.
+    ///
+    /// Blocking version of [`crate::Proxy::on_signal`], except that `handler` is called from a
+    /// thread dedicated to it. See [`crate::blocking::Connection::on_signal`] for details.
+    pub fn on_signal<'m, M, F>(&self, signal_name: M, handler: F) -> Result<SignalHandler>
+    where
+        M: TryInto<MemberName<'m>>,
+        M::Error: Into<Error>,
+        F: FnMut(Message) + Send + 'static,
+    {
+        let signal_name = signal_name.try_into().map_err(Into::into)?.into_owned();
+        let stream = block_on(self.inner().receive_signal(signal_name))?;
+
+        SignalHandler::spawn(stream, handler)
+    }
+
     /// Same as [`Proxy::receive_signal`] but with a filter.
     ///
     /// The D-Bus specification allows you to filter signals by their arguments, which helps avoid
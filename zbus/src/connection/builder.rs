@@ -27,7 +27,7 @@ use crate::{
     address::{self, Address},
     fdo::RequestNameFlags,
     names::{InterfaceName, WellKnownName},
-    object_server::{ArcInterface, Interface},
+    object_server::{ArcInterface, DispatchPolicy, Interface},
     Connection, Error, Executor, Guid, OwnedGuid, Result,
 };
 
@@ -84,6 +84,8 @@ pub struct Builder<'a> {
     unique_name: Option<crate::names::UniqueName<'a>>,
     request_name_flags: BitFlags<RequestNameFlags>,
     method_timeout: Option<std::time::Duration>,
+    blocking_pool_size: Option<usize>,
+    dispatch_policy: Option<DispatchPolicy>,
 }
 
 impl<'a> Builder<'a> {
@@ -97,6 +99,14 @@ impl<'a> Builder<'a> {
         Ok(Self::new(Target::Address(Address::system()?)))
     }
 
+    /// Create a builder for a connection to the message bus that activated the current process.
+    ///
+    /// Services started through D-Bus activation should use this, to connect to the bus they were
+    /// started for. See [`Address::starter`] for how the bus is found.
+    pub fn starter() -> Result<Self> {
+        Ok(Self::new(Target::Address(Address::starter()?)))
+    }
+
     /// Create a builder for a connection that will use the given [D-Bus bus address].
     ///
     /// # Example
@@ -368,6 +378,35 @@ impl<'a> Builder<'a> {
         self
     }
 
+    /// Set the maximum number of blocking interface methods that are run concurrently.
+    ///
+    /// Interface methods marked with `#[zbus(blocking)]` are run on a thread pool, so that they
+    /// don't stall the runtime while they run. This limits how many of them can be running at the
+    /// same time. Additional calls wait for a running method to finish. Defaults to 16.
+    ///
+    /// See the [`macro@crate::interface`] documentation for details on blocking methods.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is 0.
+    pub fn blocking_pool_size(mut self, size: usize) -> Self {
+        assert!(size > 0, "blocking pool size must be greater than 0");
+        self.blocking_pool_size = Some(size);
+
+        self
+    }
+
+    /// Set the policy the [`ObjectServer`] dispatches method calls with.
+    ///
+    /// See [`DispatchPolicy`] for details.
+    ///
+    /// [`ObjectServer`]: crate::ObjectServer
+    pub fn dispatch_policy(mut self, policy: DispatchPolicy) -> Self {
+        self.dispatch_policy = Some(policy);
+
+        self
+    }
+
     /// Build the connection, consuming the builder.
     ///
     /// # Errors
@@ -401,7 +440,15 @@ impl<'a> Builder<'a> {
         #[cfg(unix)]
         let already_received_fds = auth.already_received_fds.drain(..).collect();
 
-        let mut conn = Connection::new(auth, is_bus_conn, executor, self.method_timeout).await?;
+        let mut conn = Connection::new(
+            auth,
+            is_bus_conn,
+            executor,
+            self.method_timeout,
+            self.blocking_pool_size
+                .unwrap_or(super::DEFAULT_BLOCKING_POOL_SIZE),
+        )
+        .await?;
         conn.set_max_queued(self.max_queued.unwrap_or(DEFAULT_MAX_QUEUED));
 
         if !self.interfaces.is_empty() {
@@ -424,6 +471,10 @@ impl<'a> Builder<'a> {
             listener.await;
         }
 
+        if let Some(policy) = self.dispatch_policy {
+            conn.object_server().set_dispatch_policy(policy);
+        }
+
         // Start the socket reader task.
         conn.init_socket_reader(
             socket_read,
@@ -455,6 +506,8 @@ impl<'a> Builder<'a> {
             unique_name: None,
             request_name_flags: BitFlags::default(),
             method_timeout: None,
+            blocking_pool_size: None,
+            dispatch_policy: None,
         }
     }
 
@@ -0,0 +1,256 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, OnceLock},
+};
+
+use tracing::trace;
+use zbus_names::{BusName, OwnedUniqueName, UniqueName};
+
+use crate::{
+    async_lock::Mutex,
+    fdo::{self, ConnectionCredentials, DBusProxy},
+    proxy::CacheProperties,
+    Connection,
+};
+
+#[derive(Debug)]
+enum Entry {
+    // The credentials are being queried.
+    Pending,
+    Cached(Arc<ConnectionCredentials>),
+}
+
+/// A cache of the credentials of the peers calling methods on a connection.
+///
+/// On a bus, entries are keyed by the unique name of the peer and removed when the peer leaves the
+/// bus. On peer-to-peer connections, the credentials of the (only) peer are queried once.
+#[derive(Debug, Default)]
+pub(crate) struct CredentialsCache {
+    entries: Mutex<HashMap<OwnedUniqueName, Entry>>,
+    peer: OnceLock<Arc<ConnectionCredentials>>,
+}
+
+impl CredentialsCache {
+    /// The credentials of `sender`, or of the peer on peer-to-peer connections.
+    pub(crate) async fn get(
+        &self,
+        conn: &Connection,
+        sender: Option<&UniqueName<'_>>,
+    ) -> fdo::Result<Arc<ConnectionCredentials>> {
+        if !conn.is_bus() {
+            if let Some(creds) = self.peer.get() {
+                return Ok(creds.clone());
+            }
+            let creds = conn
+                .peer_credentials()
+                .await
+                .map_err(|e| fdo::Error::IOError(e.to_string()))?;
+
+            return Ok(self.peer.get_or_init(|| Arc::new(creds)).clone());
+        }
+
+        let sender = sender
+            .map(|s| OwnedUniqueName::from(s.to_owned()))
+            .ok_or_else(|| fdo::Error::Failed("Method call without a sender".into()))?;
+        if let Some(Entry::Cached(creds)) = self.entries.lock().await.get(&sender) {
+            return Ok(creds.clone());
+        }
+
+        // Watch before marking the entry as pending, so that the mark is removed if the peer
+        // leaves while it's queried.
+        conn.watch_vanished_names().await?;
+        self.entries
+            .lock()
+            .await
+            .entry(sender.clone())
+            .or_insert(Entry::Pending);
+        let creds = match query(conn, &sender).await {
+            Ok(creds) => creds,
+            Err(e) => {
+                let mut entries = self.entries.lock().await;
+                if let Some(Entry::Pending) = entries.get(&sender) {
+                    entries.remove(&sender);
+                }
+
+                return Err(e);
+            }
+        };
+
+        Ok(self.insert(sender, creds).await)
+    }
+
+    /// Cache the credentials of `sender`, unless it left the bus since they were queried.
+    async fn insert(
+        &self,
+        sender: OwnedUniqueName,
+        creds: ConnectionCredentials,
+    ) -> Arc<ConnectionCredentials> {
+        let mut entries = self.entries.lock().await;
+        match entries.get_mut(&sender) {
+            Some(Entry::Cached(cached)) => cached.clone(),
+            Some(entry) => {
+                trace!("Caching credentials of `{sender}`");
+                let creds = Arc::new(creds);
+                *entry = Entry::Cached(creds.clone());
+
+                creds
+            }
+            None => Arc::new(creds),
+        }
+    }
+
+    /// Forget about `name`, which left the bus.
+    pub(crate) async fn remove(&self, name: &UniqueName<'_>) {
+        if self.entries.lock().await.remove(name.as_str()).is_some() {
+            trace!("Removed cached credentials of `{name}`");
+        }
+    }
+}
+
+async fn query(conn: &Connection, sender: &OwnedUniqueName) -> fdo::Result<ConnectionCredentials> {
+    let dbus = DBusProxy::builder(conn)
+        .cache_properties(CacheProperties::No)
+        .build()
+        .await?;
+    let creds = dbus
+        .get_connection_credentials(BusName::from(sender.as_ref()))
+        .await?;
+    #[cfg(target_os = "linux")]
+    let creds = with_process_fd(&dbus, sender, creds).await;
+
+    Ok(creds)
+}
+
+/// Pin the process of `sender` with a pidfd, if the bus didn't provide one.
+///
+/// Unlike the process ID, a pidfd can't end up referring to another process once the caller exits.
+#[cfg(target_os = "linux")]
+async fn with_process_fd(
+    dbus: &DBusProxy<'_>,
+    sender: &OwnedUniqueName,
+    creds: ConnectionCredentials,
+) -> ConnectionCredentials {
+    use rustix::process::{pidfd_open, Pid, PidfdFlags};
+
+    let (None, Some(pid)) = (creds.process_fd(), creds.process_id()) else {
+        return creds;
+    };
+    let Some(raw_pid) = i32::try_from(pid).ok().and_then(Pid::from_raw) else {
+        return creds;
+    };
+    let fd = match pidfd_open(raw_pid, PidfdFlags::empty()) {
+        Ok(fd) => fd,
+        Err(e) => {
+            tracing::debug!("Failed to open a pidfd for process {pid}: {e}");
+
+            return creds;
+        }
+    };
+    // The process ID could have been reused before the pidfd was opened, unless the sender is
+    // still connected from the same process.
+    match dbus
+        .get_connection_unix_process_id(BusName::from(sender.as_ref()))
+        .await
+    {
+        Ok(current) if current == pid => creds.set_process_fd(fd.into()),
+        _ => creds,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use ntest::timeout;
+    use test_log::test;
+
+    use zbus_names::OwnedUniqueName;
+
+    use super::{CredentialsCache, Entry};
+    use crate::{
+        connection::Builder, fdo::ConnectionCredentials, interface, timeout::sleep, Connection,
+        Proxy,
+    };
+
+    struct Credentials;
+
+    #[interface(name = "org.zbus.Credentials")]
+    impl Credentials {
+        fn process_id(&self, #[zbus(credentials)] credentials: &ConnectionCredentials) -> u32 {
+            credentials.process_id().unwrap_or_default()
+        }
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn cached_credentials() {
+        crate::block_on(cached_credentials_async());
+    }
+
+    async fn cached_credentials_async() {
+        let service = Builder::session()
+            .unwrap()
+            .serve_at("/org/zbus/Credentials", Credentials)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+        let client = Connection::session().await.unwrap();
+        let client_name = client.unique_name().unwrap().to_owned();
+        let proxy = Proxy::new(
+            &client,
+            service.unique_name().unwrap().to_owned(),
+            "/org/zbus/Credentials",
+            "org.zbus.Credentials",
+        )
+        .await
+        .unwrap();
+
+        for _ in 0..2 {
+            let pid: u32 = proxy.call("ProcessId", &()).await.unwrap();
+            assert_eq!(pid, std::process::id());
+        }
+        let cache = &service.inner.credentials_cache;
+        match cache.entries.lock().await.get(&client_name) {
+            Some(Entry::Cached(cached)) => {
+                assert_eq!(cached.process_id(), Some(std::process::id()))
+            }
+            entry => panic!("unexpected entry: {entry:?}"),
+        }
+        // Credentials arguments aren't part of the method signature.
+        let xml = service
+            .object_server()
+            .interface_handle("/org/zbus/Credentials", "org.zbus.Credentials")
+            .await
+            .unwrap()
+            .introspect()
+            .await;
+        assert!(!xml.contains(r#"direction="in""#), "{xml}");
+
+        // The entry goes away with the client.
+        drop(proxy);
+        drop(client);
+        while cache.entries.lock().await.contains_key(&client_name) {
+            sleep(Duration::from_millis(10)).await;
+        }
+    }
+
+    #[test]
+    fn departure_while_querying() {
+        crate::block_on(async {
+            let cache = CredentialsCache::default();
+            let name = OwnedUniqueName::try_from(":1.42").unwrap();
+            cache
+                .entries
+                .lock()
+                .await
+                .insert(name.clone(), Entry::Pending);
+            // The peer leaves before its credentials are received.
+            cache.remove(&name).await;
+            let creds = ConnectionCredentials::default().set_process_id(42);
+            let creds = cache.insert(name.clone(), creds).await;
+            assert_eq!(creds.process_id(), Some(42));
+            assert!(cache.entries.lock().await.is_empty());
+        });
+    }
+}
@@ -0,0 +1,101 @@
+use std::{
+    fmt, io,
+    sync::{Arc, OnceLock},
+};
+
+use event_listener::Event;
+
+use crate::Error;
+
+/// The reason a [`Connection`] was disconnected.
+///
+/// Returned by [`Connection::closed`] and carried by [`Error::Disconnected`].
+///
+/// [`Connection`]: super::Connection
+/// [`Connection::closed`]: super::Connection::closed
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum DisconnectReason {
+    /// The connection was closed through [`Connection::close`](super::Connection::close).
+    Closed,
+    /// The peer closed its end of the socket.
+    PeerHangUp,
+    /// An I/O error occurred on the socket.
+    InputOutput(Arc<io::Error>),
+    /// The peer sent a message that could not be parsed.
+    InvalidMessage(Box<Error>),
+}
+
+impl DisconnectReason {
+    /// Classify an error returned while reading from the socket.
+    pub(crate) fn from_read_error(error: Error) -> Self {
+        match error {
+            Error::InputOutput(e) => match e.kind() {
+                io::ErrorKind::UnexpectedEof
+                | io::ErrorKind::BrokenPipe
+                | io::ErrorKind::ConnectionReset
+                | io::ErrorKind::ConnectionAborted => Self::PeerHangUp,
+                _ => Self::InputOutput(e),
+            },
+            Error::Disconnected(reason) => reason,
+            e => Self::InvalidMessage(Box::new(e)),
+        }
+    }
+}
+
+impl PartialEq for DisconnectReason {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Closed, Self::Closed) => true,
+            (Self::PeerHangUp, Self::PeerHangUp) => true,
+            (Self::InputOutput(_), Self::InputOutput(_)) => false,
+            (Self::InvalidMessage(s), Self::InvalidMessage(o)) => s == o,
+            (_, _) => false,
+        }
+    }
+}
+
+impl fmt::Display for DisconnectReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Closed => write!(f, "connection closed"),
+            Self::PeerHangUp => write!(f, "peer hung up"),
+            Self::InputOutput(e) => write!(f, "I/O error: {e}"),
+            Self::InvalidMessage(e) => write!(f, "invalid message received: {e}"),
+        }
+    }
+}
+
+/// The disconnection state, shared between a connection and its socket reader.
+#[derive(Debug, Default)]
+pub(crate) struct Disconnect {
+    reason: OnceLock<DisconnectReason>,
+    event: Event,
+}
+
+impl Disconnect {
+    /// The reason of the disconnection, if it happened already.
+    pub(crate) fn reason(&self) -> Option<&DisconnectReason> {
+        self.reason.get()
+    }
+
+    /// Record the disconnection, unless already recorded, and return the effective reason.
+    pub(crate) fn set(&self, reason: DisconnectReason) -> &DisconnectReason {
+        let reason = self.reason.get_or_init(|| reason);
+        self.event.notify(usize::MAX);
+
+        reason
+    }
+
+    /// Wait for the disconnection.
+    pub(crate) async fn wait(&self) -> DisconnectReason {
+        loop {
+            // Listen before checking, so we don't miss a notification in between.
+            let listener = self.event.listen();
+            if let Some(reason) = self.reason() {
+                return reason.clone();
+            }
+            listener.await;
+        }
+    }
+}
@@ -1,5 +1,5 @@
 //! Connection API.
-use async_broadcast::{broadcast, InactiveReceiver, Receiver, Sender as Broadcaster};
+use async_broadcast::{broadcast, InactiveReceiver, Receiver, Sender as Broadcaster, TryRecvError};
 use enumflags2::BitFlags;
 use event_listener::{Event, EventListener};
 use ordered_stream::{OrderedFuture, OrderedStream, PollResult};
@@ -13,7 +13,9 @@ use std::{
     time::Duration,
 };
 use tracing::{debug, info_span, instrument, trace, trace_span, warn, Instrument};
-use zbus_names::{BusName, ErrorName, InterfaceName, MemberName, OwnedUniqueName, WellKnownName};
+use zbus_names::{
+    BusName, ErrorName, InterfaceName, MemberName, OwnedUniqueName, UniqueName, WellKnownName,
+};
 use zvariant::ObjectPath;
 
 use futures_core::Future;
@@ -21,29 +23,52 @@ use futures_lite::StreamExt;
 
 use crate::{
     async_lock::{Mutex, Semaphore, SemaphorePermit},
+    blocking_pool::BlockingPool,
     fdo::{ConnectionCredentials, ReleaseNameReply, RequestNameFlags, RequestNameReply},
     is_flatpak,
     message::{Flags, Message, Type},
+    message_stream::Overflow,
+    object_server::IdleState,
     timeout::timeout,
-    DBusError, Error, Executor, MatchRule, MessageStream, ObjectServer, OwnedGuid, OwnedMatchRule,
-    Result, Task,
+    DBusError, Error, Executor, MatchRule, MessageStream, ObjectServer, OverflowPolicy, OwnedGuid,
+    OwnedMatchRule, Result, Task,
 };
 
 mod builder;
 pub use builder::Builder;
 
+mod credentials_cache;
+use credentials_cache::CredentialsCache;
+
+mod disconnect;
+use disconnect::Disconnect;
+pub use disconnect::DisconnectReason;
+
 pub mod socket;
 pub use socket::Socket;
 
+mod name_ownership;
+pub use name_ownership::{NameOwnership, NameOwnershipEvent};
+
+mod name_watcher;
+pub use name_watcher::{NameEvent, NameWatcher, WatchNameFlags};
+
+mod signal_handler;
+pub use signal_handler::SignalHandler;
+
 mod socket_reader;
 use socket_reader::SocketReader;
 
+mod vanished_names;
+use vanished_names::VanishedNames;
+
 pub(crate) mod handshake;
 pub use handshake::AuthMechanism;
 use handshake::Authenticated;
 
 const DEFAULT_MAX_QUEUED: usize = 64;
 const DEFAULT_MAX_METHOD_RETURN_QUEUED: usize = 8;
+pub(crate) const DEFAULT_BLOCKING_POOL_SIZE: usize = 16;
 
 /// Inner state shared by Connection and WeakConnection
 #[derive(Debug)]
@@ -57,6 +82,7 @@ pub(crate) struct ConnectionInner {
     registered_names: Mutex<HashMap<WellKnownName<'static>, NameStatus>>,
 
     activity_event: Arc<Event>,
+    disconnect: Arc<Disconnect>,
     socket_write: Mutex<Box<dyn socket::WriteHalf>>,
 
     // Our executor
@@ -67,7 +93,9 @@ pub(crate) struct ConnectionInner {
     socket_reader_task: OnceLock<Task<()>>,
 
     pub(crate) msg_receiver: InactiveReceiver<Result<Message>>,
+    pub(crate) msg_overflow: Arc<Overflow>,
     pub(crate) method_return_receiver: InactiveReceiver<Result<Message>>,
+    pub(crate) method_return_overflow: Arc<Overflow>,
     msg_senders: Arc<Mutex<HashMap<Option<OwnedMatchRule>, MsgBroadcaster>>>,
 
     subscriptions: Mutex<Subscriptions>,
@@ -77,7 +105,19 @@ pub(crate) struct ConnectionInner {
 
     drop_event: Event,
 
+    credentials_cache: CredentialsCache,
+
+    // Watches the peers leaving the bus, for `credentials_cache` and the client objects of
+    // `object_server`.
+    vanished_names: VanishedNames,
+
+    // The activity tracked by `IdleTracker`.
+    idle: Arc<IdleState>,
+
     method_timeout: Option<Duration>,
+
+    // The pool blocking interface methods are run on.
+    blocking_pool: BlockingPool,
 }
 
 impl Drop for ConnectionInner {
@@ -89,9 +129,27 @@ impl Drop for ConnectionInner {
     }
 }
 
-type Subscriptions = HashMap<OwnedMatchRule, (u64, InactiveReceiver<Result<Message>>)>;
+type Subscriptions =
+    HashMap<OwnedMatchRule, (u64, InactiveReceiver<Result<Message>>, Arc<Overflow>)>;
 
-pub(crate) type MsgBroadcaster = Broadcaster<Result<Message>>;
+/// The sending side of a message queue, along with its overflow state.
+#[derive(Debug, Clone)]
+pub(crate) struct MsgBroadcaster {
+    pub(crate) sender: Broadcaster<Result<Message>>,
+    pub(crate) overflow: Arc<Overflow>,
+    /// The queues of the streams that set an overflow policy, receiving the same messages.
+    pub(crate) private: Vec<MsgBroadcaster>,
+}
+
+impl MsgBroadcaster {
+    fn new(sender: Broadcaster<Result<Message>>, overflow: Arc<Overflow>) -> Self {
+        Self {
+            sender,
+            overflow,
+            private: Vec::new(),
+        }
+    }
+}
 
 /// A D-Bus connection.
 ///
@@ -221,17 +279,23 @@ pub struct Connection {
 pub(crate) struct PendingMethodCall {
     stream: Option<MessageStream>,
     serial: NonZeroU32,
+    disconnect: Arc<Disconnect>,
 }
 
 impl Future for PendingMethodCall {
     type Output = Result<Message>;
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let disconnect = self.disconnect.clone();
         self.poll_before(cx, None).map(|ret| {
             ret.map(|(_, r)| r).unwrap_or_else(|| {
-                Err(crate::Error::InputOutput(
-                    io::Error::new(ErrorKind::BrokenPipe, "socket closed").into(),
-                ))
+                let reason = disconnect.reason().cloned().unwrap_or_else(|| {
+                    DisconnectReason::InputOutput(
+                        io::Error::new(ErrorKind::BrokenPipe, "socket closed").into(),
+                    )
+                });
+
+                Err(Error::Disconnected(reason))
             })
         })
     }
@@ -391,19 +455,39 @@ impl Connection {
         }
         let msg = builder.build(body)?;
 
+        self.send_method_call(&msg).await
+    }
+
+    /// Send the given method call message.
+    ///
+    /// Unless the message has the `NoReplyExpected` flag, an object that allows the reply to be
+    /// retrieved is returned.
+    pub(crate) async fn send_method_call(
+        &self,
+        msg: &Message,
+    ) -> Result<Option<PendingMethodCall>> {
         let msg_receiver = self.inner.method_return_receiver.activate_cloned();
         let stream = Some(MessageStream::for_subscription_channel(
             msg_receiver,
+            self.inner.method_return_overflow.clone(),
             // This is a lie but we only use the stream internally so it's fine.
             None,
             self,
         ));
         let serial = msg.primary_header().serial_num();
-        self.send(&msg).await?;
-        if flags.contains(Flags::NoReplyExpected) {
+        self.send(msg).await?;
+        if msg
+            .primary_header()
+            .flags()
+            .contains(Flags::NoReplyExpected)
+        {
             Ok(None)
         } else {
-            Ok(Some(PendingMethodCall { stream, serial }))
+            Ok(Some(PendingMethodCall {
+                stream,
+                serial,
+                disconnect: self.inner.disconnect.clone(),
+            }))
         }
     }
 
@@ -805,6 +889,148 @@ impl Connection {
         .map(|r| r == ReleaseNameReply::Released)
     }
 
+    /// Request a well-known name on the bus, and keep track of its ownership.
+    ///
+    /// Unlike [`Connection::request_name_with_flags`], the returned [`NameOwnership`] reports the
+    /// changes of the ownership of the name over time, as a stream of [`NameOwnershipEvent`], and
+    /// releases the name when dropped. The name is not tracked by [`Connection::release_name`].
+    ///
+    /// While the guard is alive, the service is considered busy by the
+    /// [`IdleTracker`](crate::object_server::IdleTracker).
+    ///
+    /// # Errors
+    ///
+    /// Fails with `zbus::Error::NameTaken` if the name is owned by another peer and `flags`
+    /// contains [`RequestNameFlags::DoNotQueue`], and with `zbus::Error::Unsupported` if `self`
+    /// is not a connection to a message bus.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # zbus::block_on(async {
+    /// use futures_util::StreamExt;
+    /// use zbus::{connection::NameOwnershipEvent, fdo::RequestNameFlags, Connection};
+    ///
+    /// let conn = Connection::session().await?;
+    /// let mut ownership = conn
+    ///     .own_name("org.zbus.MyService", RequestNameFlags::AllowReplacement.into())
+    ///     .await?;
+    /// while let Some(event) = ownership.next().await {
+    ///     match event {
+    ///         NameOwnershipEvent::Acquired | NameOwnershipEvent::Reacquired => {
+    ///             // Start serving requests.
+    ///         }
+    ///         NameOwnershipEvent::Lost => {
+    ///             // Another instance took over.
+    ///         }
+    ///         NameOwnershipEvent::Queued => {
+    ///             let position = ownership.queue_position().await?;
+    ///             println!("Waiting for the name, at position {position:?}");
+    ///         }
+    ///     }
+    /// }
+    /// # Ok::<(), zbus::Error>(())
+    /// # }).unwrap();
+    /// ```
+    pub async fn own_name<'w, W>(
+        &self,
+        well_known_name: W,
+        flags: BitFlags<RequestNameFlags>,
+    ) -> Result<NameOwnership>
+    where
+        W: TryInto<WellKnownName<'w>>,
+        W::Error: Into<Error>,
+    {
+        let well_known_name = well_known_name.try_into().map_err(Into::into)?.to_owned();
+
+        NameOwnership::new(self, well_known_name, flags).await
+    }
+
+    /// Watch the ownership of a bus name.
+    ///
+    /// The returned stream first yields the current owner of `name`, if any, and then a
+    /// [`NameEvent`] each time the name appears or vanishes on the bus. This is the equivalent of
+    /// GDBus' `g_bus_watch_name`.
+    ///
+    /// Unlike [`crate::Proxy::receive_owner_changed`], this doesn't require a proxy, and the
+    /// initial state is reported without an extra call.
+    ///
+    /// # Errors
+    ///
+    /// Fails with `zbus::Error::Unsupported` if `self` is not a connection to a message bus.
+    pub async fn watch_name<'n, N>(&self, name: N) -> Result<NameWatcher>
+    where
+        N: TryInto<BusName<'n>>,
+        N::Error: Into<Error>,
+    {
+        self.watch_name_with_flags(name, BitFlags::default()).await
+    }
+
+    /// Watch the ownership of a bus name, with flags.
+    ///
+    /// Same as [`Connection::watch_name`], but with the given `flags`. Use
+    /// [`WatchNameFlags::AutoStart`] to have the bus activate the service owning the name before
+    /// the initial state is reported.
+    pub async fn watch_name_with_flags<'n, N>(
+        &self,
+        name: N,
+        flags: BitFlags<WatchNameFlags>,
+    ) -> Result<NameWatcher>
+    where
+        N: TryInto<BusName<'n>>,
+        N::Error: Into<Error>,
+    {
+        let name = name.try_into().map_err(Into::into)?.to_owned();
+
+        NameWatcher::new(self, name, flags).await
+    }
+
+    /// Call `handler` for each signal matching `rule`.
+    ///
+    /// This subscribes to the signals through a [`MessageStream`] and spawns a task on the executor
+    /// of the connection to drive it, so `handler` should not block. The subscription lasts for as
+    /// long as the returned [`SignalHandler`] is alive. Dropping it removes the match rule.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use zbus::{Connection, MatchRule, Result};
+    /// # async fn example() -> Result<()> {
+    /// let conn = Connection::session().await?;
+    /// let rule = MatchRule::builder()
+    ///     .msg_type(zbus::message::Type::Signal)
+    ///     .sender("org.freedesktop.DBus")?
+    ///     .interface("org.freedesktop.DBus")?
+    ///     .member("NameOwnerChanged")?
+    ///     .build();
+    /// let _handler = conn
+    ///     .on_signal(rule, |msg| println!("{:?}", msg.header().member()))
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn on_signal<R, F>(&self, rule: R, handler: F) -> Result<SignalHandler>
+    where
+        R: TryInto<OwnedMatchRule>,
+        R::Error: Into<Error>,
+        F: FnMut(Message) + Send + 'static,
+    {
+        let rule = rule.try_into().map_err(Into::into)?;
+        let name = rule.to_string();
+        let stream = MessageStream::for_match_rule(rule, self, None)
+            .await?
+            .filter_map(|msg| match msg {
+                Ok(msg) => Some(msg),
+                Err(e) => {
+                    warn!("Error receiving signal: {e}");
+
+                    None
+                }
+            });
+
+        Ok(SignalHandler::spawn(self, stream, handler, &name))
+    }
+
     /// Check if `self` is a connection to a message bus.
     ///
     /// This will return `false` for p2p connections. When the `p2p` feature is disabled, this will
@@ -919,6 +1145,11 @@ impl Connection {
         &self.inner.executor
     }
 
+    /// The activity state of the connection, as tracked by `IdleTracker`.
+    pub(crate) fn idle_state(&self) -> &Arc<IdleState> {
+        &self.inner.idle
+    }
+
     /// Get a reference to the associated [`ObjectServer`].
     ///
     /// The `ObjectServer` is created on-demand.
@@ -930,6 +1161,11 @@ impl Connection {
         self.ensure_object_server(true)
     }
 
+    /// The associated [`ObjectServer`], if it was already created.
+    pub(crate) fn existing_object_server(&self) -> Option<&ObjectServer> {
+        self.inner.object_server.get()
+    }
+
     pub(crate) fn ensure_object_server(&self, start: bool) -> &ObjectServer {
         self.inner
             .object_server
@@ -1036,14 +1272,28 @@ impl Connection {
         rule: OwnedMatchRule,
         max_queued: Option<usize>,
     ) -> Result<Receiver<Result<Message>>> {
+        self.add_match_with_overflow(rule, max_queued)
+            .await
+            .map(|(receiver, _)| receiver)
+    }
+
+    /// Same as `add_match` but also returns the overflow state of the queue.
+    pub(crate) async fn add_match_with_overflow(
+        &self,
+        rule: OwnedMatchRule,
+        max_queued: Option<usize>,
+    ) -> Result<(Receiver<Result<Message>>, Arc<Overflow>)> {
         use std::collections::hash_map::Entry;
 
         if self.inner.msg_senders.lock().await.is_empty() {
             // This only happens if socket reader task has errored out.
-            return Err(Error::InputOutput(Arc::new(io::Error::new(
-                io::ErrorKind::BrokenPipe,
-                "Socket reader task has errored out",
-            ))));
+            let reason = self.inner.disconnect.reason().cloned().unwrap_or_else(|| {
+                DisconnectReason::InputOutput(Arc::new(io::Error::new(
+                    io::ErrorKind::BrokenPipe,
+                    "Socket reader task has errored out",
+                )))
+            });
+            return Err(Error::Disconnected(reason));
         }
 
         let mut subscriptions = self.inner.subscriptions.lock().await;
@@ -1063,17 +1313,18 @@ impl Connection {
                     )
                     .await?;
                 }
-                e.insert((1, receiver.clone().deactivate()));
+                let overflow = Arc::new(Overflow::default());
+                e.insert((1, receiver.clone().deactivate(), overflow.clone()));
                 self.inner
                     .msg_senders
                     .lock()
                     .await
-                    .insert(Some(rule), sender);
+                    .insert(Some(rule), MsgBroadcaster::new(sender, overflow.clone()));
 
-                Ok(receiver)
+                Ok((receiver, overflow))
             }
             Entry::Occupied(mut e) => {
-                let (num_subscriptions, receiver) = e.get_mut();
+                let (num_subscriptions, receiver, overflow) = e.get_mut();
                 *num_subscriptions += 1;
                 if let Some(max_queued) = max_queued {
                     if max_queued > receiver.capacity() {
@@ -1081,11 +1332,52 @@ impl Connection {
                     }
                 }
 
-                Ok(receiver.activate_cloned())
+                Ok((receiver.activate_cloned(), overflow.clone()))
             }
         }
     }
 
+    /// Move a stream of the queue for `rule` to a queue of its own, with the given overflow policy.
+    ///
+    /// The messages pending in `receiver` are moved along, so the stream doesn't miss any. The
+    /// private queue is dropped with the stream, while its subscription to `rule` is kept as is.
+    pub(crate) async fn add_private_queue(
+        &self,
+        rule: Option<&OwnedMatchRule>,
+        receiver: &mut Receiver<Result<Message>>,
+        policy: OverflowPolicy,
+    ) -> (Receiver<Result<Message>>, Arc<Overflow>) {
+        // Hold the lock while moving the pending messages, so none is received in the meantime.
+        let mut senders = self.inner.msg_senders.lock().await;
+
+        let mut pending = Vec::new();
+        let mut lagged = 0;
+        loop {
+            match receiver.try_recv() {
+                Ok(msg) => pending.push(msg),
+                Err(TryRecvError::Overflowed(n)) => lagged += n,
+                Err(TryRecvError::Empty | TryRecvError::Closed) => break,
+            }
+        }
+        let (mut sender, mut private_receiver) = broadcast(receiver.capacity().max(pending.len()));
+        private_receiver.set_await_active(false);
+        sender.set_overflow(policy == OverflowPolicy::DropOldest);
+        for msg in pending {
+            // The capacity is enough for all of them.
+            let _ = sender.try_broadcast(msg);
+        }
+        let overflow = Arc::new(Overflow::new(policy, lagged as usize));
+
+        // If the socket reader is gone, the queue can only contain what was pending.
+        if let Some(shared) = senders.get_mut(&rule.cloned()) {
+            shared
+                .private
+                .push(MsgBroadcaster::new(sender, overflow.clone()));
+        }
+
+        (private_receiver, overflow)
+    }
+
     pub(crate) async fn remove_match(&self, rule: OwnedMatchRule) -> Result<bool> {
         use std::collections::hash_map::Entry;
         let mut subscriptions = self.inner.subscriptions.lock().await;
@@ -1133,31 +1425,45 @@ impl Connection {
         self.inner.method_timeout
     }
 
+    /// The maximum number of blocking interface methods run concurrently. See
+    /// [Builder::blocking_pool_size] for details.
+    pub fn blocking_pool_size(&self) -> usize {
+        self.inner.blocking_pool.size()
+    }
+
+    pub(crate) fn blocking_pool(&self) -> &BlockingPool {
+        &self.inner.blocking_pool
+    }
+
     pub(crate) async fn new(
         auth: Authenticated,
         #[allow(unused)] bus_connection: bool,
         executor: Executor<'static>,
         method_timeout: Option<Duration>,
+        blocking_pool_size: usize,
     ) -> Result<Self> {
         #[cfg(unix)]
         let cap_unix_fd = auth.cap_unix_fd;
 
         macro_rules! create_msg_broadcast_channel {
             ($size:expr) => {{
-                let (msg_sender, msg_receiver) = broadcast($size);
+                let (sender, msg_receiver) = broadcast($size);
                 let mut msg_receiver = msg_receiver.deactivate();
                 msg_receiver.set_await_active(false);
+                let overflow = Arc::new(Overflow::default());
+                let msg_sender = MsgBroadcaster::new(sender, overflow.clone());
 
-                (msg_sender, msg_receiver)
+                (msg_sender, msg_receiver, overflow)
             }};
         }
         // The unfiltered message channel.
-        let (msg_sender, msg_receiver) = create_msg_broadcast_channel!(DEFAULT_MAX_QUEUED);
+        let (msg_sender, msg_receiver, msg_overflow) =
+            create_msg_broadcast_channel!(DEFAULT_MAX_QUEUED);
         let mut msg_senders = HashMap::new();
         msg_senders.insert(None, msg_sender);
 
         // The special method return & error channel.
-        let (method_return_sender, method_return_receiver) =
+        let (method_return_sender, method_return_receiver, method_return_overflow) =
             create_msg_broadcast_channel!(DEFAULT_MAX_METHOD_RETURN_QUEUED);
         let rule = MatchRule::builder()
             .msg_type(Type::MethodReturn)
@@ -1172,6 +1478,7 @@ impl Connection {
         let connection = Self {
             inner: Arc::new(ConnectionInner {
                 activity_event: Arc::new(Event::new()),
+                disconnect: Arc::new(Disconnect::default()),
                 socket_write: Mutex::new(auth.socket_write),
                 server_guid: auth.server_guid,
                 #[cfg(unix)]
@@ -1186,10 +1493,16 @@ impl Connection {
                 socket_reader_task: OnceLock::new(),
                 msg_senders,
                 msg_receiver,
+                msg_overflow,
                 method_return_receiver,
+                method_return_overflow,
                 registered_names: Mutex::new(HashMap::new()),
                 drop_event: Event::new(),
+                credentials_cache: CredentialsCache::default(),
+                vanished_names: VanishedNames::default(),
+                idle: Arc::default(),
                 method_timeout,
+                blocking_pool: BlockingPool::new(blocking_pool_size),
             }),
         };
 
@@ -1234,11 +1547,28 @@ impl Connection {
             .await
     }
 
+    /// The credentials of the caller `sender`, or of the peer on peer-to-peer connections.
+    ///
+    /// Served from a per-connection cache after the first query.
+    pub(crate) async fn cached_credentials(
+        &self,
+        sender: Option<&UniqueName<'_>>,
+    ) -> crate::fdo::Result<Arc<ConnectionCredentials>> {
+        self.inner.credentials_cache.get(self, sender).await
+    }
+
+    /// Start watching the unique names leaving the bus, unless already done.
+    pub(crate) async fn watch_vanished_names(&self) -> Result<()> {
+        self.inner.vanished_names.start(self).await
+    }
+
     /// Close the connection.
     ///
-    /// After this call, all reading and writing operations will fail.
+    /// After this call, all reading and writing operations will fail. Pending method calls fail
+    /// with [`Error::Disconnected`], with [`DisconnectReason::Closed`] as the reason.
     pub async fn close(self) -> Result<()> {
         self.inner.activity_event.notify(usize::MAX);
+        self.inner.disconnect.set(DisconnectReason::Closed);
         self.inner
             .socket_write
             .lock()
@@ -1248,6 +1578,19 @@ impl Connection {
             .map_err(Into::into)
     }
 
+    /// Wait for the connection to be disconnected.
+    ///
+    /// Resolves once the connection was closed through [`Connection::close`] (from a clone of
+    /// this connection) or the socket reader stopped, either because the peer hung up, an I/O error
+    /// occurred or an invalid message was received. If the connection is already disconnected, the
+    /// reason is returned immediately.
+    ///
+    /// Method calls pending at that time fail with [`Error::Disconnected`], carrying the same
+    /// reason.
+    pub async fn closed(&self) -> DisconnectReason {
+        self.inner.disconnect.wait().await
+    }
+
     /// Gracefully close the connection, waiting for all other references to be dropped.
     ///
     /// This will not disrupt any incoming or outgoing method calls, and will await their
@@ -1314,6 +1657,7 @@ impl Connection {
                     #[cfg(unix)]
                     already_received_fds,
                     inner.activity_event.clone(),
+                    inner.disconnect.clone(),
                 )
                 .spawn(&inner.executor),
             )
@@ -1546,19 +1890,74 @@ mod tests {
         // The method call should have been allowed to finish properly.
         done_listener.await;
     }
+
+    #[test]
+    #[timeout(15000)]
+    fn watch_name() {
+        crate::utils::block_on(test_watch_name()).unwrap();
+    }
+
+    async fn test_watch_name() -> Result<()> {
+        let name = "org.zbus.WatchNameTest";
+        let conn = Connection::session().await?;
+        let mut watcher = conn.watch_name(name).await?;
+        assert_eq!(watcher.name(), name);
+        assert_eq!(watcher.next().await, Some(NameEvent::Vanished));
+        assert_eq!(watcher.owner(), None);
+
+        let owner1 = Connection::session().await?;
+        owner1
+            .request_name_with_flags(
+                name,
+                RequestNameFlags::AllowReplacement | RequestNameFlags::DoNotQueue,
+            )
+            .await?;
+        let unique1 = owner1.unique_name().unwrap().clone();
+        assert_eq!(
+            watcher.next().await,
+            Some(NameEvent::Appeared(unique1.clone()))
+        );
+
+        // A new watcher reports the current owner right away.
+        let mut watcher2 = conn.watch_name(name).await?;
+        assert_eq!(
+            watcher2.next().await,
+            Some(NameEvent::Appeared(unique1.clone()))
+        );
+        assert_eq!(watcher2.owner(), Some(&unique1));
+        drop(watcher2);
+
+        // A direct handover is reported as the name vanishing and then reappearing.
+        let owner2 = Connection::session().await?;
+        owner2
+            .request_name_with_flags(name, RequestNameFlags::ReplaceExisting.into())
+            .await?;
+        let unique2 = owner2.unique_name().unwrap().clone();
+        assert_eq!(watcher.next().await, Some(NameEvent::Vanished));
+        assert_eq!(
+            watcher.next().await,
+            Some(NameEvent::Appeared(unique2.clone()))
+        );
+
+        drop(owner2);
+        assert_eq!(watcher.next().await, Some(NameEvent::Vanished));
+        assert_eq!(watcher.owner(), None);
+
+        Ok(())
+    }
 }
 
 #[cfg(feature = "p2p")]
 #[cfg(test)]
 mod p2p_tests {
     use event_listener::Event;
-    use futures_util::TryStreamExt;
+    use futures_util::{StreamExt, TryStreamExt};
     use ntest::timeout;
     use test_log::test;
     use zvariant::{Endian, NATIVE_ENDIAN};
 
-    use super::{socket, Builder, Connection};
-    use crate::{conn::AuthMechanism, Guid, Message, MessageStream, Result};
+    use super::{socket, Builder, Connection, DisconnectReason};
+    use crate::{conn::AuthMechanism, Error, Guid, Message, MessageStream, Result};
 
     // Same numbered client and server are already paired up.
     async fn test_p2p(
@@ -1735,6 +2134,44 @@ mod p2p_tests {
         )
     }
 
+    #[cfg(unix)]
+    #[test]
+    #[timeout(15000)]
+    fn closed() {
+        crate::utils::block_on(test_closed()).unwrap();
+    }
+
+    #[cfg(unix)]
+    async fn test_closed() -> Result<()> {
+        // The peer hanging up fails pending method calls with the same reason.
+        let (server, client) = unix_p2p_pipe().await?;
+        let mut stream = MessageStream::from(&server);
+        let (reply, ()) = futures_util::join!(
+            client.call_method(None::<()>, "/", Some("org.zbus.p2p"), "Test", &()),
+            async {
+                stream.next().await.unwrap().unwrap();
+                drop(stream);
+                drop(server);
+            }
+        );
+        match reply {
+            Err(Error::Disconnected(DisconnectReason::PeerHangUp)) => (),
+            res => panic!("unexpected result: {res:?}"),
+        }
+        assert_eq!(client.closed().await, DisconnectReason::PeerHangUp);
+        // Once disconnected, the reason is returned right away.
+        assert_eq!(client.closed().await, DisconnectReason::PeerHangUp);
+
+        // Closing the connection ourselves.
+        let (server, client) = unix_p2p_pipe().await?;
+        let (reason, res) = futures_util::join!(client.closed(), client.clone().close());
+        res?;
+        assert_eq!(reason, DisconnectReason::Closed);
+        assert_eq!(server.closed().await, DisconnectReason::PeerHangUp);
+
+        Ok(())
+    }
+
     #[cfg(any(
         all(feature = "vsock", not(feature = "tokio")),
         feature = "tokio-vsock"
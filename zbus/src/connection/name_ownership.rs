@@ -0,0 +1,219 @@
+use enumflags2::BitFlags;
+use futures_core::{ready, stream};
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tracing::{debug, warn};
+use zbus_names::WellKnownName;
+
+use crate::{
+    fdo::{self, ReleaseNameReply, RequestNameFlags, RequestNameReply},
+    message::Type,
+    object_server::{Activity, ActivityKind},
+    proxy::CacheProperties,
+    Connection, Error, MatchRule, MessageStream, Result,
+};
+
+/// A change in the ownership of a name, yielded by a [`NameOwnership`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NameOwnershipEvent {
+    /// The name is owned by another peer and the connection was placed in its queue.
+    Queued,
+    /// The connection became the primary owner of the name for the first time.
+    Acquired,
+    /// The connection is no longer the primary owner of the name.
+    ///
+    /// Unless the name was requested with [`RequestNameFlags::DoNotQueue`], the connection is
+    /// placed back in the queue of the name.
+    Lost,
+    /// The connection became the primary owner of the name again, after having lost it.
+    Reacquired,
+}
+
+/// An owned, or queued for, well-known name on the bus.
+///
+/// Use [`Connection::own_name`] to create an instance of this type.
+///
+/// This is a [`stream::Stream`] of the ownership changes of the name. The first item is always the
+/// state right after the request: either [`NameOwnershipEvent::Acquired`] or
+/// [`NameOwnershipEvent::Queued`].
+///
+/// The name is released when this guard is dropped. Use [`NameOwnership::release`] to wait for
+/// the release to complete.
+#[derive(Debug)]
+#[must_use = "the name is released when the guard is dropped"]
+pub struct NameOwnership {
+    name: WellKnownName<'static>,
+    dbus_proxy: fdo::DBusProxy<'static>,
+    stream: MessageStream,
+    pending: Option<NameOwnershipEvent>,
+    owner: bool,
+    acquired_before: bool,
+    released: bool,
+    // Keeps the service busy, as far as `IdleTracker` is concerned.
+    _activity: Activity,
+}
+
+impl NameOwnership {
+    pub(crate) async fn new(
+        conn: &Connection,
+        name: WellKnownName<'static>,
+        flags: BitFlags<RequestNameFlags>,
+    ) -> Result<Self> {
+        if !conn.is_bus() {
+            return Err(Error::Unsupported);
+        }
+
+        let dbus_proxy = fdo::DBusProxy::builder(conn)
+            .cache_properties(CacheProperties::No)
+            .build()
+            .await?;
+        // Subscribe before the request so that no change is lost in between. This covers both
+        // `NameAcquired` and `NameLost`, in the order they're sent.
+        let rule = MatchRule::builder()
+            .msg_type(Type::Signal)
+            .sender("org.freedesktop.DBus")?
+            .path("/org/freedesktop/DBus")?
+            .interface("org.freedesktop.DBus")?
+            .arg(0, name.as_str())?
+            .build();
+        let stream = MessageStream::for_match_rule(rule, conn, None).await?;
+
+        let (initial, owner) = match dbus_proxy.request_name(name.clone(), flags).await? {
+            RequestNameReply::PrimaryOwner | RequestNameReply::AlreadyOwner => {
+                (NameOwnershipEvent::Acquired, true)
+            }
+            RequestNameReply::InQueue => (NameOwnershipEvent::Queued, false),
+            RequestNameReply::Exists => return Err(Error::NameTaken),
+        };
+
+        Ok(Self {
+            name,
+            dbus_proxy,
+            stream,
+            pending: Some(initial),
+            owner,
+            acquired_before: owner,
+            released: false,
+            _activity: conn.idle_state().start(ActivityKind::Name),
+        })
+    }
+
+    /// The name.
+    pub fn name(&self) -> &WellKnownName<'static> {
+        &self.name
+    }
+
+    /// Whether the connection is the primary owner of the name, as far as the events yielded so
+    /// far tell.
+    pub fn is_owner(&self) -> bool {
+        self.owner
+    }
+
+    /// The position of the connection in the queue of the name.
+    ///
+    /// `Some(0)` means the connection is the primary owner. `None` means it's not in the queue
+    /// at all, which happens after the name was lost, if it was requested with
+    /// [`RequestNameFlags::DoNotQueue`].
+    ///
+    /// This queries the bus, through the `ListQueuedOwners` method.
+    pub async fn queue_position(&self) -> Result<Option<usize>> {
+        let conn = self.dbus_proxy.inner().connection();
+        let owners = match self.dbus_proxy.list_queued_owners(self.name.clone()).await {
+            Ok(owners) => owners,
+            Err(fdo::Error::NameHasNoOwner(_)) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        Ok(conn
+            .unique_name()
+            .and_then(|unique_name| owners.iter().position(|owner| owner == unique_name)))
+    }
+
+    /// Release the name, and wait for the bus to confirm it.
+    ///
+    /// Returns `Ok(true)` if the name was owned, or queued for, and it's now released.
+    pub async fn release(mut self) -> Result<bool> {
+        self.released = true;
+
+        release_name(&self.dbus_proxy, self.name.clone()).await
+    }
+}
+
+impl stream::Stream for NameOwnership {
+    type Item = NameOwnershipEvent;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        if let Some(event) = this.pending.take() {
+            return Poll::Ready(Some(event));
+        }
+
+        loop {
+            let msg = match ready!(Pin::new(&mut this.stream).poll_next(cx)) {
+                Some(Ok(msg)) => msg,
+                Some(Err(e)) => {
+                    warn!("Error receiving ownership changes of `{}`: {e}", this.name);
+
+                    continue;
+                }
+                None => return Poll::Ready(None),
+            };
+            let header = msg.header();
+            let event = match header.member().map(|m| m.as_str()) {
+                // The initial acquisition is already reported.
+                Some("NameAcquired") if !this.owner => {
+                    this.owner = true;
+                    if std::mem::replace(&mut this.acquired_before, true) {
+                        NameOwnershipEvent::Reacquired
+                    } else {
+                        NameOwnershipEvent::Acquired
+                    }
+                }
+                Some("NameLost") if this.owner => {
+                    this.owner = false;
+
+                    NameOwnershipEvent::Lost
+                }
+                _ => continue,
+            };
+
+            return Poll::Ready(Some(event));
+        }
+    }
+}
+
+impl Drop for NameOwnership {
+    fn drop(&mut self) {
+        if self.released {
+            return;
+        }
+
+        let dbus_proxy = self.dbus_proxy.clone();
+        let name = self.name.clone();
+        let task_name = format!("Release name `{name}`");
+        self.dbus_proxy
+            .inner()
+            .connection()
+            .executor()
+            .spawn(
+                async move {
+                    if let Err(e) = release_name(&dbus_proxy, name.clone()).await {
+                        debug!("Failed to release name `{name}`: {e}");
+                    }
+                },
+                &task_name,
+            )
+            .detach();
+    }
+}
+
+async fn release_name(dbus_proxy: &fdo::DBusProxy<'_>, name: WellKnownName<'_>) -> Result<bool> {
+    dbus_proxy
+        .release_name(name)
+        .await
+        .map(|reply| reply == ReleaseNameReply::Released)
+        .map_err(Into::into)
+}
@@ -0,0 +1,151 @@
+use enumflags2::{bitflags, BitFlags};
+use futures_core::{ready, stream};
+use std::{
+    collections::VecDeque,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use zbus_names::{BusName, OwnedUniqueName};
+
+use crate::{fdo, proxy::CacheProperties, Connection, Error, Result};
+
+/// Flags to use with [`Connection::watch_name_with_flags`].
+#[bitflags]
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum WatchNameFlags {
+    /// Ask the bus to [start][ss] the service owning the name, if it isn't running already.
+    ///
+    /// This is ignored for unique names, which can't be activated.
+    ///
+    /// [ss]: https://dbus.freedesktop.org/doc/dbus-specification.html#message-bus-starting-services
+    AutoStart = 0x1,
+}
+
+/// An event yielded by a [`NameWatcher`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum NameEvent {
+    /// The name is owned by the peer with the given unique name.
+    Appeared(OwnedUniqueName),
+    /// The name has no owner.
+    Vanished,
+}
+
+/// A [`stream::Stream`] of the ownership changes of a bus name.
+///
+/// The first item is always the ownership state at the time of the creation of the stream. Then,
+/// an item is yielded each time the name is acquired or released. When the ownership is passed
+/// directly from one peer to another, a [`NameEvent::Vanished`] is yielded before the
+/// [`NameEvent::Appeared`] of the new owner.
+///
+/// Use [`Connection::watch_name`] to create an instance of this type.
+#[derive(Debug)]
+pub struct NameWatcher {
+    name: BusName<'static>,
+    owner: Option<OwnedUniqueName>,
+    pending: VecDeque<NameEvent>,
+    stream: fdo::NameOwnerChangedStream,
+}
+
+impl NameWatcher {
+    pub(crate) async fn new(
+        conn: &Connection,
+        name: BusName<'static>,
+        flags: BitFlags<WatchNameFlags>,
+    ) -> Result<Self> {
+        if !conn.is_bus() {
+            return Err(Error::Unsupported);
+        }
+
+        let dbus_proxy = fdo::DBusProxy::builder(conn)
+            .cache_properties(CacheProperties::No)
+            .build()
+            .await?;
+        // Subscribe before querying the owner so that no change is lost in between.
+        let stream = dbus_proxy
+            .receive_name_owner_changed_with_args(&[(0, name.as_str())])
+            .await?;
+
+        if let (true, BusName::WellKnown(well_known)) =
+            (flags.contains(WatchNameFlags::AutoStart), &name)
+        {
+            match dbus_proxy
+                .start_service_by_name(well_known.clone(), 0)
+                .await
+            {
+                // Not being able to start the service is the same as the name not being owned.
+                Ok(_) | Err(fdo::Error::ServiceUnknown(_)) => (),
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        let owner = match dbus_proxy.get_name_owner(name.clone()).await {
+            Ok(owner) => Some(owner),
+            Err(fdo::Error::NameHasNoOwner(_)) => None,
+            Err(e) => return Err(e.into()),
+        };
+        let initial = match &owner {
+            Some(owner) => NameEvent::Appeared(owner.clone()),
+            None => NameEvent::Vanished,
+        };
+
+        Ok(Self {
+            name,
+            owner,
+            pending: VecDeque::from([initial]),
+            stream,
+        })
+    }
+
+    /// The bus name being watched.
+    pub fn name(&self) -> &BusName<'static> {
+        &self.name
+    }
+
+    /// The current owner of the name, as far as the events yielded so far tell.
+    pub fn owner(&self) -> Option<&OwnedUniqueName> {
+        self.owner.as_ref()
+    }
+
+    fn update_owner(&mut self, new_owner: Option<OwnedUniqueName>) {
+        // Changes that happened before the initial state was queried are already accounted for.
+        if new_owner == self.owner {
+            return;
+        }
+
+        if self.owner.is_some() {
+            self.pending.push_back(NameEvent::Vanished);
+        }
+        if let Some(new_owner) = &new_owner {
+            self.pending
+                .push_back(NameEvent::Appeared(new_owner.clone()));
+        }
+        self.owner = new_owner;
+    }
+}
+
+impl stream::Stream for NameWatcher {
+    type Item = NameEvent;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            if let Some(event) = this.pending.pop_front() {
+                return Poll::Ready(Some(event));
+            }
+
+            let Some(signal) = ready!(Pin::new(&mut this.stream).poll_next(cx)) else {
+                return Poll::Ready(None);
+            };
+            let Ok(args) = signal.args() else {
+                continue;
+            };
+            let new_owner = args
+                .new_owner()
+                .as_ref()
+                .map(|owner| owner.to_owned().into());
+            this.update_owner(new_owner);
+        }
+    }
+}
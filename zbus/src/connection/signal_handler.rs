@@ -0,0 +1,40 @@
+use futures_core::Stream;
+use futures_lite::StreamExt;
+use tracing::{trace_span, Instrument};
+
+use crate::{message::Message, Connection, Task};
+
+/// A signal handler registered with [`Connection::on_signal`] or [`crate::Proxy::on_signal`].
+///
+/// The handler is called for each matching signal, from a task running on the executor of the
+/// connection, for as long as this guard is alive. Dropping it stops the task and removes the
+/// associated match rule.
+#[derive(Debug)]
+#[must_use = "the signal handler is removed when the guard is dropped"]
+pub struct SignalHandler {
+    _task: Task<()>,
+}
+
+impl SignalHandler {
+    /// Spawn a task on the executor of `conn`, calling `handler` for each message of `stream`.
+    pub(crate) fn spawn<S, F>(conn: &Connection, stream: S, mut handler: F, name: &str) -> Self
+    where
+        S: Stream<Item = Message> + Send + 'static,
+        F: FnMut(Message) + Send + 'static,
+    {
+        let task_name = format!("signal handler for `{name}`");
+        let span = trace_span!("signal_handler", %name);
+        let task = conn.executor().spawn(
+            async move {
+                let mut stream = std::pin::pin!(stream);
+                while let Some(msg) = stream.next().await {
+                    handler(msg);
+                }
+            }
+            .instrument(span),
+            &task_name,
+        );
+
+        Self { _task: task }
+    }
+}
@@ -1,13 +1,15 @@
 use std::{collections::HashMap, sync::Arc};
 
+use async_broadcast::TrySendError;
 use event_listener::Event;
 use tracing::{debug, instrument, trace};
 
 use crate::{
-    async_lock::Mutex, connection::MsgBroadcaster, Executor, Message, OwnedMatchRule, Task,
+    async_lock::Mutex, connection::MsgBroadcaster, Error, Executor, Message, OverflowPolicy,
+    OwnedMatchRule, Task,
 };
 
-use super::socket::ReadHalf;
+use super::{socket::ReadHalf, Disconnect, DisconnectReason};
 
 #[derive(Debug)]
 pub(crate) struct SocketReader {
@@ -18,6 +20,7 @@ pub(crate) struct SocketReader {
     already_received_fds: Vec<std::os::fd::OwnedFd>,
     prev_seq: u64,
     activity_event: Arc<Event>,
+    disconnect: Arc<Disconnect>,
 }
 
 impl SocketReader {
@@ -27,6 +30,7 @@ impl SocketReader {
         already_received_bytes: Vec<u8>,
         #[cfg(unix)] already_received_fds: Vec<std::os::fd::OwnedFd>,
         activity_event: Arc<Event>,
+        disconnect: Arc<Disconnect>,
     ) -> Self {
         Self {
             socket,
@@ -36,6 +40,7 @@ impl SocketReader {
             already_received_fds,
             prev_seq: 0,
             activity_event,
+            disconnect,
         }
     }
 
@@ -48,14 +53,24 @@ impl SocketReader {
     async fn receive_msg(mut self) {
         loop {
             trace!("Waiting for message on the socket..");
-            let msg = self.read_socket().await;
-            match &msg {
-                Ok(msg) => trace!("Message received on the socket: {:?}", msg),
-                Err(e) => trace!("Error reading from the socket: {:?}", e),
+            let msg = match self.read_socket().await {
+                Ok(msg) => {
+                    trace!("Message received on the socket: {:?}", msg);
+
+                    Ok(msg)
+                }
+                Err(e) => {
+                    trace!("Error reading from the socket: {:?}", e);
+                    // If the connection was closed on our side, that's the reason, not the error
+                    // that resulted from it.
+                    let reason = self.disconnect.set(DisconnectReason::from_read_error(e));
+
+                    Err(Error::Disconnected(reason.clone()))
+                }
             };
 
             let mut senders = self.senders.lock().await;
-            for (rule, sender) in &*senders {
+            for (rule, sender) in senders.iter_mut() {
                 if let Ok(msg) = &msg {
                     if let Some(rule) = rule.as_ref() {
                         match rule.matches(msg) {
@@ -70,21 +85,11 @@ impl SocketReader {
                     }
                 }
 
-                if let Err(e) = sender.broadcast_direct(msg.clone()).await {
-                    // An error would be due to either of these:
-                    //
-                    // 1. the channel is closed.
-                    // 2. No active receivers.
-                    //
-                    // In either case, just log it unless this is the channel for the generic
-                    // unfiltered stream, where the channel is not created on-demand.
-                    if rule.is_some() {
-                        trace!(
-                            "Error broadcasting message to stream for `{:?}`: {:?}",
-                            rule,
-                            e
-                        );
-                    }
+                broadcast(rule, sender, &msg).await;
+                // The private queues go away with their stream.
+                sender.private.retain(|private| !private.sender.is_closed());
+                for private in &mut sender.private {
+                    broadcast(rule, private, &msg).await;
                 }
             }
             trace!("Broadcasted to all streams: {:?}", msg);
@@ -116,3 +121,53 @@ impl SocketReader {
         Ok(msg)
     }
 }
+
+// Put `msg` in the queue of `sender`, according to its overflow policy.
+async fn broadcast(
+    rule: &Option<OwnedMatchRule>,
+    sender: &mut MsgBroadcaster,
+    msg: &Result<Message, Error>,
+) {
+    let res = match sender.overflow.policy() {
+        OverflowPolicy::DropNewest if msg.is_ok() => {
+            match sender.sender.try_broadcast(msg.clone()) {
+                Err(TrySendError::Full(_)) => {
+                    trace!("Queue for `{:?}` is full, dropping message", rule);
+                    sender.overflow.record_drop();
+
+                    return;
+                }
+                res => res.map_err(|e| format!("{e:?}")),
+            }
+        }
+        policy => {
+            // Errors end the stream so they must not be dropped, but the stream may not be
+            // reading anymore: make room for them instead of waiting.
+            if msg.is_err() && policy == OverflowPolicy::DropNewest {
+                sender.sender.set_overflow(true);
+            }
+            // With `DropOldest`, the channel is in overflow mode so this never waits.
+            sender
+                .sender
+                .broadcast_direct(msg.clone())
+                .await
+                .map_err(|e| format!("{e:?}"))
+        }
+    };
+    if let Err(e) = res {
+        // An error would be due to either of these:
+        //
+        // 1. the channel is closed.
+        // 2. No active receivers.
+        //
+        // In either case, just log it unless this is the channel for the generic unfiltered
+        // stream, where the channel is not created on-demand.
+        if rule.is_some() {
+            trace!(
+                "Error broadcasting message to stream for `{:?}`: {:?}",
+                rule,
+                e
+            );
+        }
+    }
+}
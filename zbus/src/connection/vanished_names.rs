@@ -0,0 +1,78 @@
+use futures_lite::StreamExt;
+use tracing::trace;
+use zbus_names::BusName;
+
+use crate::{
+    async_lock::Mutex, fdo::NameOwnerChanged, message::Type, Connection, MatchRule, OwnedMatchRule,
+    Result, Task,
+};
+
+use super::WeakConnection;
+
+/// Watches the unique names leaving the bus, on behalf of the parts of a connection keeping state
+/// about its peers.
+///
+/// A single subscription is shared by all of them and lasts as long as the connection. The task
+/// reads from the subscription channel directly rather than from a [`crate::MessageStream`], which
+/// would keep the connection alive.
+#[derive(Debug, Default)]
+pub(crate) struct VanishedNames {
+    // Started on first use.
+    task: Mutex<Option<Task<()>>>,
+}
+
+impl VanishedNames {
+    /// Start watching, unless already done.
+    ///
+    /// Once this returns, no departure is missed.
+    pub(crate) async fn start(&self, conn: &Connection) -> Result<()> {
+        let mut task = self.task.lock().await;
+        if task.is_some() {
+            return Ok(());
+        }
+
+        // Peers leaving the bus lose their unique name.
+        let rule: OwnedMatchRule = MatchRule::builder()
+            .msg_type(Type::Signal)
+            .sender("org.freedesktop.DBus")?
+            .path("/org/freedesktop/DBus")?
+            .interface("org.freedesktop.DBus")?
+            .member("NameOwnerChanged")?
+            .arg(2, "")?
+            .build()
+            .into();
+        let mut stream = conn.add_match(rule.clone(), None).await?;
+        let weak_conn = WeakConnection::from(conn);
+        *task = Some(conn.executor().spawn(
+            async move {
+                while let Some(Ok(msg)) = stream.next().await {
+                    let Some(signal) = NameOwnerChanged::from_message(msg) else {
+                        continue;
+                    };
+                    let Ok(args) = signal.args() else {
+                        continue;
+                    };
+                    let BusName::Unique(name) = args.name() else {
+                        continue;
+                    };
+                    let Some(conn) = weak_conn.upgrade() else {
+                        return;
+                    };
+                    trace!("`{name}` left the bus");
+                    conn.inner.credentials_cache.remove(name).await;
+                    if let Some(server) = conn.inner.object_server.get() {
+                        server.remove_client_objects(name).await;
+                    }
+                }
+
+                // Don't leave the subscription behind, in case the connection is still in use.
+                if let Some(conn) = weak_conn.upgrade() {
+                    conn.queue_remove_match(rule);
+                }
+            },
+            "vanished names watcher",
+        ));
+
+        Ok(())
+    }
+}
@@ -3,6 +3,7 @@ use zbus_names::{Error as NamesError, InterfaceName, OwnedErrorName};
 use zvariant::{Error as VariantError, ObjectPath};
 
 use crate::{
+    connection::DisconnectReason,
     fdo,
     message::{Message, Type},
 };
@@ -58,8 +59,15 @@ pub enum Error {
     MissingParameter(&'static str),
     /// Serial number in the message header is 0 (which is invalid).
     InvalidSerial,
+    /// The given number of messages were dropped because the queue of a [`crate::MessageStream`]
+    /// was full.
+    ///
+    /// See [`crate::OverflowPolicy`] for details.
+    Lagged(u64),
     /// The given interface already exists at the given path.
     InterfaceExists(InterfaceName<'static>, ObjectPath<'static>),
+    /// The connection was disconnected for the given reason.
+    Disconnected(DisconnectReason),
 }
 
 impl PartialEq for Error {
@@ -82,9 +90,11 @@ impl PartialEq for Error {
             (Self::Variant(s), Self::Variant(o)) => s == o,
             (Self::Names(s), Self::Names(o)) => s == o,
             (Self::NameTaken, Self::NameTaken) => true,
+            (Self::Lagged(n1), Self::Lagged(n2)) => n1 == n2,
             (Error::InputOutput(_), Self::InputOutput(_)) => false,
             (Self::Failure(s1), Self::Failure(s2)) => s1 == s2,
             (Self::InterfaceExists(s1, s2), Self::InterfaceExists(o1, o2)) => s1 == o1 && s2 == o2,
+            (Self::Disconnected(s), Self::Disconnected(o)) => s == o,
             (_, _) => false,
         }
     }
@@ -113,7 +123,11 @@ impl error::Error for Error {
             Error::Failure(_) => None,
             Error::MissingParameter(_) => None,
             Error::InvalidSerial => None,
+            Error::Lagged(_) => None,
             Error::InterfaceExists(_, _) => None,
+            Error::Disconnected(DisconnectReason::InputOutput(e)) => Some(e),
+            Error::Disconnected(DisconnectReason::InvalidMessage(e)) => Some(e),
+            Error::Disconnected(_) => None,
         }
     }
 }
@@ -148,7 +162,9 @@ impl fmt::Display for Error {
                 write!(f, "Parameter `{p}` was not specified but it is required")
             }
             Error::InvalidSerial => write!(f, "Serial number in the message header is 0"),
+            Error::Lagged(n) => write!(f, "{n} messages were dropped from a full queue"),
             Error::InterfaceExists(i, p) => write!(f, "Interface `{i}` already exists at `{p}`"),
+            Error::Disconnected(reason) => write!(f, "disconnected: {reason}"),
         }
     }
 }
@@ -181,7 +197,9 @@ impl Error {
             Error::Failure(e) => Some(e),
             Error::MissingParameter(_) => Some("A required parameter is missing"),
             Error::InvalidSerial => Some("serial number in the message header is 0"),
+            Error::Lagged(_) => Some("messages were dropped from a full queue"),
             Error::InterfaceExists(_, _) => Some("interface already exists"),
+            Error::Disconnected(_) => Some("disconnected"),
         }
     }
 }
@@ -211,7 +229,9 @@ impl Clone for Error {
             Error::Failure(e) => Error::Failure(e.clone()),
             Error::MissingParameter(p) => Error::MissingParameter(p),
             Error::InvalidSerial => Error::InvalidSerial,
+            Error::Lagged(n) => Error::Lagged(*n),
             Error::InterfaceExists(i, p) => Error::InterfaceExists(i.clone(), p.clone()),
+            Error::Disconnected(reason) => Error::Disconnected(reason.clone()),
         }
     }
 }
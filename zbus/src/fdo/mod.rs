@@ -19,7 +19,7 @@ pub(crate) mod object_manager;
 pub use object_manager::{
     InterfacesAdded, InterfacesAddedArgs, InterfacesAddedStream, InterfacesRemoved,
     InterfacesRemovedArgs, InterfacesRemovedStream, ManagedObjects, ObjectManager,
-    ObjectManagerProxy,
+    ObjectManagerClient, ObjectManagerEvent, ObjectManagerEventStream, ObjectManagerProxy,
 };
 
 pub(crate) mod peer;
@@ -198,4 +198,164 @@ mod tests {
             .collect()
         );
     }
+
+    #[test]
+    #[timeout(15000)]
+    fn object_manager_client() {
+        crate::block_on(object_manager_client_async());
+    }
+
+    async fn object_manager_client_async() {
+        struct TestObj {
+            value: u32,
+        }
+        #[interface(name = "org.zbus.TestObj")]
+        impl TestObj {
+            #[zbus(property)]
+            fn value(&self) -> u32 {
+                self.value
+            }
+
+            #[zbus(property)]
+            fn set_value(&mut self, value: u32) {
+                self.value = value;
+            }
+        }
+
+        let service = zbus::conn::Builder::session()
+            .unwrap()
+            .name("org.zbus.ObjectManagerClientTest")
+            .unwrap()
+            .serve_at("/org/zbus/ObjectManagerClientTest", super::ObjectManager)
+            .unwrap()
+            .serve_at(
+                "/org/zbus/ObjectManagerClientTest/Obj1",
+                TestObj { value: 1 },
+            )
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        let conn = zbus::Connection::session().await.unwrap();
+        let client = super::ObjectManagerClient::new(
+            &conn,
+            "org.zbus.ObjectManagerClientTest",
+            "/org/zbus/ObjectManagerClientTest",
+        )
+        .await
+        .unwrap();
+        assert_eq!(client.owner().as_ref(), service.unique_name());
+        let obj1 = "/org/zbus/ObjectManagerClientTest/Obj1";
+        assert_eq!(client.object_paths(), vec![obj1.try_into().unwrap()]);
+        assert_eq!(
+            client.objects_with_interface("org.zbus.TestObj").unwrap(),
+            vec![obj1.try_into().unwrap()]
+        );
+        let value: Option<u32> = client.property(obj1, "org.zbus.TestObj", "Value").unwrap();
+        assert_eq!(value, Some(1));
+        let proxy = client.interface_proxy(obj1, "org.zbus.TestObj").unwrap();
+        assert_eq!(proxy.cached_property::<u32>("Value").unwrap(), Some(1));
+        assert!(client.interface_proxy(obj1, "org.zbus.Unknown").is_err());
+
+        let mut events = client.receive_events();
+
+        // Property changes are reflected in both the client and the proxy.
+        let iface_ref = service
+            .object_server()
+            .interface::<_, TestObj>(obj1)
+            .await
+            .unwrap();
+        {
+            let mut iface = iface_ref.get_mut().await;
+            iface.value = 2;
+            iface
+                .value_changed(iface_ref.signal_emitter())
+                .await
+                .unwrap();
+        }
+        assert_eq!(
+            events.next().await.unwrap(),
+            super::ObjectManagerEvent::PropertiesChanged {
+                path: obj1.try_into().unwrap(),
+                interface: "org.zbus.TestObj".try_into().unwrap(),
+                changed: vec!["Value".to_string()],
+                invalidated: vec![],
+            }
+        );
+        let value: Option<u32> = client.property(obj1, "org.zbus.TestObj", "Value").unwrap();
+        assert_eq!(value, Some(2));
+        assert_eq!(proxy.cached_property::<u32>("Value").unwrap(), Some(2));
+
+        // Objects coming and going.
+        let obj2 = "/org/zbus/ObjectManagerClientTest/Obj2";
+        service
+            .object_server()
+            .at(obj2, TestObj { value: 3 })
+            .await
+            .unwrap();
+        assert_eq!(
+            events.next().await.unwrap(),
+            super::ObjectManagerEvent::InterfacesAdded {
+                path: obj2.try_into().unwrap(),
+                interfaces: vec!["org.zbus.TestObj".try_into().unwrap()],
+            }
+        );
+        let value: Option<u32> = client.property(obj2, "org.zbus.TestObj", "Value").unwrap();
+        assert_eq!(value, Some(3));
+
+        service
+            .object_server()
+            .remove::<TestObj, _>(obj1)
+            .await
+            .unwrap();
+        assert_eq!(
+            events.next().await.unwrap(),
+            super::ObjectManagerEvent::InterfacesRemoved {
+                path: obj1.try_into().unwrap(),
+                interfaces: vec!["org.zbus.TestObj".try_into().unwrap()],
+            }
+        );
+        assert_eq!(client.object_paths(), vec![obj2.try_into().unwrap()]);
+        assert_eq!(client.interfaces(obj1).unwrap(), None);
+        assert_eq!(proxy.cached_property::<u32>("Value").unwrap(), None);
+
+        // On a handover to another owner, only the differences are reported.
+        let obj3 = "/org/zbus/ObjectManagerClientTest/Obj3";
+        let service2 = zbus::conn::Builder::session()
+            .unwrap()
+            .serve_at("/org/zbus/ObjectManagerClientTest", super::ObjectManager)
+            .unwrap()
+            .serve_at(obj2, TestObj { value: 3 })
+            .unwrap()
+            .serve_at(obj3, TestObj { value: 4 })
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(
+            service2
+                .request_name_with_flags(
+                    "org.zbus.ObjectManagerClientTest",
+                    enumflags2::BitFlags::empty()
+                )
+                .await
+                .unwrap(),
+            fdo::RequestNameReply::InQueue
+        );
+        service
+            .release_name("org.zbus.ObjectManagerClientTest")
+            .await
+            .unwrap();
+        assert_eq!(
+            events.next().await.unwrap(),
+            super::ObjectManagerEvent::InterfacesAdded {
+                path: obj3.try_into().unwrap(),
+                interfaces: vec!["org.zbus.TestObj".try_into().unwrap()],
+            }
+        );
+        assert_eq!(client.owner().as_ref(), service2.unique_name());
+        let value: Option<u32> = client.property(obj2, "org.zbus.TestObj", "Value").unwrap();
+        assert_eq!(value, Some(3));
+    }
 }
@@ -0,0 +1,828 @@
+use async_broadcast::{broadcast, InactiveReceiver, Receiver, Sender};
+use enumflags2::BitFlags;
+use futures_core::stream;
+use ordered_stream::{join as join_streams, FromFuture, OrderedStream, OrderedStreamExt};
+use std::{
+    collections::HashMap,
+    pin::Pin,
+    sync::{Arc, Mutex, RwLock, Weak},
+    task::{Context, Poll},
+};
+use tracing::{debug, info_span, instrument, trace, warn, Instrument};
+use zbus_names::{BusName, InterfaceName, OwnedInterfaceName, OwnedUniqueName};
+use zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Value};
+
+use super::ManagedObjects;
+use crate::{
+    fdo::{self, InterfacesAdded, InterfacesRemoved, NameOwnerChanged, PropertiesChanged},
+    message::{Message, Sequence, Type},
+    proxy::{self, CacheProperties, Defaults, Either, PropertiesCache},
+    Connection, Error, MatchRule, MessageStream, Proxy, Result, Task,
+};
+
+const OBJECT_MANAGER_INTERFACE: &str = "org.freedesktop.DBus.ObjectManager";
+const MAX_QUEUED_EVENTS: usize = 64;
+
+/// A client-side mirror of the object tree exposed by a remote [Object Manager][om].
+///
+/// On creation, the `GetManagedObjects` method is called on the remote object manager and the
+/// result is kept in sync with the remote tree, by applying the `InterfacesAdded`,
+/// `InterfacesRemoved` and `PropertiesChanged` signals in the order they were received. If the
+/// destination is a well-known name and it changes owner, the cache is repopulated from the new
+/// owner. If that fails, the cache is cleared until the next owner change.
+///
+/// Proxies created through [`ObjectManagerClient::proxy`] and
+/// [`ObjectManagerClient::interface_proxy`] have their properties cache populated from, and kept
+/// updated by, this client. Hence their creation involves no round trips to the peer.
+///
+/// # Example
+///
+/// ```no_run
+/// # use std::error::Error;
+/// use futures_util::StreamExt;
+/// use zbus::{fdo::ObjectManagerClient, Connection};
+///
+/// # zbus::block_on(async {
+/// let connection = Connection::system().await?;
+/// let client = ObjectManagerClient::new(&connection, "org.bluez", "/").await?;
+///
+/// for path in client.objects_with_interface("org.bluez.Device1")? {
+///     let name: Option<String> = client.property(&path, "org.bluez.Device1", "Name")?;
+///     println!("{path}: {name:?}");
+/// }
+///
+/// let mut events = client.receive_events();
+/// while let Some(event) = events.next().await {
+///     println!("{event:?}");
+/// }
+/// # Ok::<(), Box<dyn Error + Send + Sync>>(())
+/// # }).unwrap();
+/// ```
+///
+/// [om]: https://dbus.freedesktop.org/doc/dbus-specification.html#standard-interfaces-objectmanager
+#[derive(Clone, Debug)]
+pub struct ObjectManagerClient {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    conn: Connection,
+    destination: BusName<'static>,
+    path: OwnedObjectPath,
+    cache: Arc<Cache>,
+    _task: Task<()>,
+}
+
+/// A change to the object tree mirrored by [`ObjectManagerClient`].
+///
+/// The new values can be looked up through the client, once the event is received.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ObjectManagerEvent {
+    /// Interfaces were added to an object, which is created if it didn't exist before.
+    InterfacesAdded {
+        /// The path of the object.
+        path: OwnedObjectPath,
+        /// The interfaces added.
+        interfaces: Vec<OwnedInterfaceName>,
+    },
+    /// Interfaces were removed from an object, which is removed if it has no interfaces left.
+    InterfacesRemoved {
+        /// The path of the object.
+        path: OwnedObjectPath,
+        /// The interfaces removed.
+        interfaces: Vec<OwnedInterfaceName>,
+    },
+    /// Properties of an object's interface changed.
+    PropertiesChanged {
+        /// The path of the object.
+        path: OwnedObjectPath,
+        /// The interface the properties belong to.
+        interface: OwnedInterfaceName,
+        /// The properties whose values have been updated.
+        changed: Vec<String>,
+        /// The properties that were invalidated. These are removed from the cache.
+        invalidated: Vec<String>,
+    },
+}
+
+impl ObjectManagerClient {
+    /// Create a new client for the object manager at `path` on `destination`.
+    ///
+    /// The returned client is fully populated.
+    pub async fn new<D, P>(conn: &Connection, destination: D, path: P) -> Result<Self>
+    where
+        D: TryInto<BusName<'static>>,
+        P: TryInto<ObjectPath<'static>>,
+        D::Error: Into<Error>,
+        P::Error: Into<Error>,
+    {
+        let destination = destination.try_into().map_err(Into::into)?;
+        let path = OwnedObjectPath::from(path.try_into().map_err(Into::into)?);
+        let cache = Arc::new(Cache::new());
+
+        let signals = subscribe(conn, &destination, &path).await?;
+        let (signals, synced) = cache.sync(conn, &destination, &path, signals).await;
+        synced?;
+
+        let task_cache = cache.clone();
+        let task_conn = conn.clone();
+        let task_destination = destination.clone();
+        let task_path = path.clone();
+        let task_name = format!("{destination} object manager client");
+        let task = conn.executor().spawn(
+            async move {
+                task_cache
+                    .keep_updated(&task_conn, &task_destination, &task_path, signals)
+                    .await
+            }
+            .instrument(info_span!("{}", task_name)),
+            &task_name,
+        );
+
+        Ok(Self {
+            inner: Arc::new(Inner {
+                conn: conn.clone(),
+                destination,
+                path,
+                cache,
+                _task: task,
+            }),
+        })
+    }
+
+    /// The associated connection.
+    pub fn connection(&self) -> &Connection {
+        &self.inner.conn
+    }
+
+    /// The destination service name.
+    pub fn destination(&self) -> &BusName<'static> {
+        &self.inner.destination
+    }
+
+    /// The path of the remote object manager.
+    pub fn path(&self) -> &ObjectPath<'static> {
+        &self.inner.path
+    }
+
+    /// The unique name of the current owner of the destination, if known.
+    pub fn owner(&self) -> Option<OwnedUniqueName> {
+        self.inner
+            .cache
+            .owner
+            .read()
+            .expect("lock poisoned")
+            .clone()
+    }
+
+    /// The paths of all the managed objects.
+    pub fn object_paths(&self) -> Vec<OwnedObjectPath> {
+        self.inner.cache.read().keys().cloned().collect()
+    }
+
+    /// The interfaces implemented by the object at `path`.
+    ///
+    /// Returns `None` if there is no such managed object.
+    pub fn interfaces<'p, P>(&self, path: P) -> Result<Option<Vec<OwnedInterfaceName>>>
+    where
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+    {
+        let path = path.try_into().map_err(Into::into)?;
+
+        Ok(self
+            .inner
+            .cache
+            .read()
+            .get(&path)
+            .map(|interfaces| interfaces.keys().cloned().collect()))
+    }
+
+    /// The paths of all the managed objects implementing `interface`.
+    pub fn objects_with_interface<'i, I>(&self, interface: I) -> Result<Vec<OwnedObjectPath>>
+    where
+        I: TryInto<InterfaceName<'i>>,
+        I::Error: Into<Error>,
+    {
+        let interface = interface.try_into().map_err(Into::into)?;
+
+        Ok(self
+            .inner
+            .cache
+            .read()
+            .iter()
+            .filter(|(_, interfaces)| interfaces.contains_key(interface.as_str()))
+            .map(|(path, _)| path.clone())
+            .collect())
+    }
+
+    /// The cached value of the property `name` of `interface` on the object at `path`.
+    ///
+    /// Returns `None` if the object, the interface or the property is not in the cache.
+    pub fn property<'p, 'i, P, I, T>(&self, path: P, interface: I, name: &str) -> Result<Option<T>>
+    where
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+        I: TryInto<InterfaceName<'i>>,
+        I::Error: Into<Error>,
+        T: TryFrom<OwnedValue>,
+        T::Error: Into<Error>,
+    {
+        let path = path.try_into().map_err(Into::into)?;
+        let interface = interface.try_into().map_err(Into::into)?;
+
+        self.inner
+            .cache
+            .read()
+            .get(&path)
+            .and_then(|interfaces| interfaces.get(interface.as_str()))
+            .and_then(|properties| properties.get(name))
+            .map(|value| T::try_from(value.try_clone()?).map_err(Into::into))
+            .transpose()
+    }
+
+    /// A copy of all the cached managed objects.
+    pub fn managed_objects(&self) -> Result<ManagedObjects> {
+        let objects = self.inner.cache.read();
+        let mut copy = ManagedObjects::with_capacity(objects.len());
+        for (path, interfaces) in objects.iter() {
+            let interfaces = interfaces
+                .iter()
+                .map(|(interface, properties)| {
+                    Ok((interface.clone(), clone_properties(properties)?))
+                })
+                .collect::<Result<_>>()?;
+            copy.insert(path.clone(), interfaces);
+        }
+
+        Ok(copy)
+    }
+
+    /// Create a proxy of type `T` for the managed object at `path`.
+    ///
+    /// The interface is the default one of `T`. The proxy's properties cache is populated from the
+    /// cached data, so no round trips to the peer are needed.
+    ///
+    /// # Errors
+    ///
+    /// [`fdo::Error::UnknownObject`] or [`fdo::Error::UnknownInterface`] is returned if the object
+    /// or the interface is not in the cache.
+    pub fn proxy<'p, T, P>(&self, path: P) -> Result<T>
+    where
+        T: From<Proxy<'static>> + Defaults,
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+    {
+        let interface = T::INTERFACE
+            .as_ref()
+            .ok_or(Error::MissingParameter("interface"))?;
+
+        self.proxy_builder::<T>(path.try_into().map_err(Into::into)?, interface.as_ref())?
+            .build_internal()
+            .map(Into::into)
+    }
+
+    /// Create a [`Proxy`] for `interface` on the managed object at `path`.
+    ///
+    /// Same as [`ObjectManagerClient::proxy`], but for the untyped proxy.
+    pub fn interface_proxy<'p, 'i, P, I>(&self, path: P, interface: I) -> Result<Proxy<'static>>
+    where
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+        I: TryInto<InterfaceName<'i>>,
+        I::Error: Into<Error>,
+    {
+        let path = path.try_into().map_err(Into::into)?;
+        let interface = interface.try_into().map_err(Into::into)?;
+
+        self.proxy_builder::<Proxy<'static>>(path, interface)?
+            .build_internal()
+    }
+
+    fn proxy_builder<T>(
+        &self,
+        path: ObjectPath<'_>,
+        interface: InterfaceName<'_>,
+    ) -> Result<proxy::Builder<'static, T>>
+    where
+        T: Defaults,
+    {
+        let path = OwnedObjectPath::from(path.into_owned());
+        let interface = OwnedInterfaceName::from(interface.into_owned());
+        let properties_cache = {
+            let objects = self.inner.cache.read();
+            let properties = objects
+                .get(&path)
+                .ok_or_else(|| fdo::Error::UnknownObject(format!("Unknown object '{path}'")))?
+                .get(&interface)
+                .ok_or_else(|| {
+                    fdo::Error::UnknownInterface(format!("Unknown interface '{interface}'"))
+                })?;
+            let properties_cache = PropertiesCache::new_populated(clone_properties(properties)?);
+            // Register while still holding the lock, so no updates are missed.
+            self.inner.cache.register_proxy_cache(
+                path.clone(),
+                interface.clone(),
+                &properties_cache,
+            );
+
+            properties_cache
+        };
+
+        Ok(proxy::Builder::new(&self.inner.conn)
+            .destination(self.inner.destination.clone())?
+            .path(path.into_inner())?
+            .interface(interface.into_inner())?
+            .cache_properties(CacheProperties::Yes)
+            .populated_cache(properties_cache))
+    }
+
+    /// Get a stream of the changes to the object tree.
+    ///
+    /// Only the changes happening after this call are yielded. If the stream is not consumed fast
+    /// enough, the oldest events are dropped.
+    pub fn receive_events(&self) -> ObjectManagerEventStream {
+        ObjectManagerEventStream {
+            receiver: self.inner.cache.events_receiver.activate_cloned(),
+        }
+    }
+}
+
+/// A [`stream::Stream`] of the changes to the object tree mirrored by an [`ObjectManagerClient`].
+///
+/// Use [`ObjectManagerClient::receive_events`] to create an instance of this type.
+#[derive(Debug)]
+pub struct ObjectManagerEventStream {
+    receiver: Receiver<ObjectManagerEvent>,
+}
+
+impl stream::Stream for ObjectManagerEventStream {
+    type Item = ObjectManagerEvent;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        stream::Stream::poll_next(Pin::new(&mut self.get_mut().receiver), cx)
+    }
+}
+
+type ProxyCaches = HashMap<(OwnedObjectPath, OwnedInterfaceName), Vec<Weak<PropertiesCache>>>;
+
+#[derive(Debug)]
+struct Cache {
+    objects: RwLock<ManagedObjects>,
+    owner: RwLock<Option<OwnedUniqueName>>,
+    /// The properties caches of the proxies created through the client.
+    proxy_caches: Mutex<ProxyCaches>,
+    events: Sender<ObjectManagerEvent>,
+    events_receiver: InactiveReceiver<ObjectManagerEvent>,
+}
+
+/// The outcome of processing a signal.
+enum Update {
+    Applied,
+    OwnerChanged(Option<OwnedUniqueName>),
+}
+
+impl Cache {
+    fn new() -> Self {
+        let (mut events, events_receiver) = broadcast(MAX_QUEUED_EVENTS);
+        events.set_overflow(true);
+        events.set_await_active(false);
+
+        Self {
+            objects: RwLock::new(ManagedObjects::new()),
+            owner: RwLock::new(None),
+            proxy_caches: Mutex::new(HashMap::new()),
+            events,
+            events_receiver: events_receiver.deactivate(),
+        }
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, ManagedObjects> {
+        self.objects.read().expect("lock poisoned")
+    }
+
+    /// Populate the cache from `GetManagedObjects`, discarding the signals that are older than the
+    /// reply.
+    ///
+    /// The signal stream is given back even on failure, so the caller can keep listening.
+    async fn sync<S>(
+        &self,
+        conn: &Connection,
+        destination: &BusName<'_>,
+        path: &ObjectPath<'_>,
+        mut signals: S,
+    ) -> (S, Result<()>)
+    where
+        S: OrderedStream<Data = Result<Message>, Ordering = Sequence> + Unpin,
+    {
+        loop {
+            let reply = conn
+                .call_method_raw(
+                    Some(destination),
+                    path,
+                    Some(&InterfaceName::from_static_str_unchecked(
+                        OBJECT_MANAGER_INTERFACE,
+                    )),
+                    "GetManagedObjects",
+                    BitFlags::empty(),
+                    &(),
+                )
+                .await;
+            let get_managed_objects = match reply {
+                Ok(Some(reply)) => FromFuture::from(reply).map(Either::Right),
+                // Only calls with the `NoReplyExpected` flag have no reply.
+                Ok(None) => return (signals, Err(Error::Failure("No reply expected".into()))),
+                Err(e) => return (signals, Err(e)),
+            };
+
+            let mut join = join_streams(signals.map(Either::Left), get_managed_objects);
+            let synced = loop {
+                match join.next().await {
+                    Some(Either::Left(_signal)) => {
+                        // discard signals prior to the population
+                    }
+                    Some(Either::Right(reply)) => {
+                        break reply.and_then(|reply| {
+                            let owner = reply.header().sender().map(|s| s.to_owned().into());
+                            let objects = reply.body().deserialize()?;
+                            self.populate(owner, objects);
+
+                            Ok(())
+                        });
+                    }
+                    None => {
+                        break Err(Error::InputOutput(
+                            std::io::Error::other("stream ended").into(),
+                        ))
+                    }
+                }
+            };
+            let update = match Pin::new(&mut join).take_buffered() {
+                // The signal was received after the reply and hence needs to be applied.
+                Some((Either::Left(Ok(msg)), _)) if synced.is_ok() => self.update(&msg),
+                _ => Update::Applied,
+            };
+            signals = join.into_inner().0.into_inner();
+
+            match update {
+                Update::Applied => return (signals, synced),
+                Update::OwnerChanged(None) => {
+                    self.populate(None, ManagedObjects::new());
+
+                    return (signals, synced);
+                }
+                Update::OwnerChanged(Some(_)) => {
+                    trace!("Owner changed right after population, repopulating");
+                }
+            }
+        }
+    }
+
+    async fn keep_updated<S>(
+        &self,
+        conn: &Connection,
+        destination: &BusName<'_>,
+        path: &ObjectPath<'_>,
+        mut signals: S,
+    ) where
+        S: OrderedStream<Data = Result<Message>, Ordering = Sequence> + Unpin,
+    {
+        trace!("Listening for changes to the objects managed at {path}...");
+        while let Some(msg) = signals.next().await {
+            let msg = match msg {
+                Ok(msg) => msg,
+                Err(e) => {
+                    debug!("Error receiving object manager signals: {e}");
+
+                    continue;
+                }
+            };
+            match self.update(&msg) {
+                Update::Applied => (),
+                Update::OwnerChanged(None) => self.populate(None, ManagedObjects::new()),
+                Update::OwnerChanged(Some(_)) => {
+                    let synced;
+                    (signals, synced) = self.sync(conn, destination, path, signals).await;
+                    if let Err(e) = synced {
+                        // Better no objects than stale ones. The next owner change triggers
+                        // another attempt.
+                        warn!("Failed to repopulate the objects managed at {path}: {e}");
+                        self.populate(None, ManagedObjects::new());
+                    }
+                }
+            }
+        }
+    }
+
+    /// Replace the cached objects with `objects`, broadcasting the differences.
+    #[instrument(skip(self, objects))]
+    fn populate(&self, owner: Option<OwnedUniqueName>, objects: ManagedObjects) {
+        *self.owner.write().expect("lock poisoned") = owner;
+
+        let old = std::mem::replace(&mut *self.objects.write().expect("lock poisoned"), objects);
+        let objects = self.read();
+
+        let mut proxy_caches = self.proxy_caches.lock().expect("lock poisoned");
+        proxy_caches.retain(|(path, interface), caches| {
+            caches.retain(|cache| cache.strong_count() > 0);
+            let properties = objects
+                .get(path)
+                .and_then(|interfaces| interfaces.get(interface))
+                .and_then(|properties| clone_properties(properties).ok())
+                .unwrap_or_default();
+            for cache in caches.iter().filter_map(Weak::upgrade) {
+                cache.reset(clone_properties(&properties).unwrap_or_default());
+            }
+
+            !caches.is_empty()
+        });
+        drop(proxy_caches);
+
+        for (path, old_interfaces) in &old {
+            let interfaces = objects.get(path);
+            let removed: Vec<_> = old_interfaces
+                .keys()
+                .filter(|interface| !interfaces.is_some_and(|i| i.contains_key(*interface)))
+                .cloned()
+                .collect();
+            if !removed.is_empty() {
+                self.broadcast(ObjectManagerEvent::InterfacesRemoved {
+                    path: path.clone(),
+                    interfaces: removed,
+                });
+            }
+
+            let Some(interfaces) = interfaces else {
+                continue;
+            };
+            for (interface, old_properties) in old_interfaces {
+                let Some(properties) = interfaces.get(interface) else {
+                    continue;
+                };
+                let changed: Vec<_> = properties
+                    .iter()
+                    .filter(|(name, value)| old_properties.get(*name) != Some(*value))
+                    .map(|(name, _)| name.clone())
+                    .collect();
+                let invalidated: Vec<_> = old_properties
+                    .keys()
+                    .filter(|name| !properties.contains_key(*name))
+                    .cloned()
+                    .collect();
+                if !changed.is_empty() || !invalidated.is_empty() {
+                    self.broadcast(ObjectManagerEvent::PropertiesChanged {
+                        path: path.clone(),
+                        interface: interface.clone(),
+                        changed,
+                        invalidated,
+                    });
+                }
+            }
+        }
+        for (path, interfaces) in objects.iter() {
+            let old_interfaces = old.get(path);
+            let added: Vec<_> = interfaces
+                .keys()
+                .filter(|interface| !old_interfaces.is_some_and(|i| i.contains_key(*interface)))
+                .cloned()
+                .collect();
+            if !added.is_empty() {
+                self.broadcast(ObjectManagerEvent::InterfacesAdded {
+                    path: path.clone(),
+                    interfaces: added,
+                });
+            }
+        }
+    }
+
+    /// Apply the change carried by `msg`, if any.
+    fn update(&self, msg: &Message) -> Update {
+        let hdr = msg.header();
+        if hdr.interface().map(|i| i.as_str()) == Some("org.freedesktop.DBus") {
+            let Some(signal) = NameOwnerChanged::from_message(msg.clone()) else {
+                return Update::Applied;
+            };
+            let Ok(args) = signal.args() else {
+                return Update::Applied;
+            };
+            let new_owner = args.new_owner().as_ref().map(|o| o.to_owned().into());
+            if new_owner == *self.owner.read().expect("lock poisoned") {
+                return Update::Applied;
+            }
+            debug!("Object manager owner changed to {new_owner:?}");
+
+            return Update::OwnerChanged(new_owner);
+        }
+
+        // Ignore signals from other peers, that our match rules let through.
+        if let (Some(owner), Some(sender)) =
+            (&*self.owner.read().expect("lock poisoned"), hdr.sender())
+        {
+            if owner != sender {
+                return Update::Applied;
+            }
+        }
+
+        if let Some(signal) = InterfacesAdded::from_message(msg.clone()) {
+            if let Ok(args) = signal.args() {
+                self.add_interfaces(args.object_path(), args.interfaces_and_properties());
+            }
+        } else if let Some(signal) = InterfacesRemoved::from_message(msg.clone()) {
+            if let Ok(args) = signal.args() {
+                self.remove_interfaces(args.object_path(), args.interfaces());
+            }
+        } else if let Some(signal) = PropertiesChanged::from_message(msg.clone()) {
+            if let (Ok(args), Some(path)) = (signal.args(), hdr.path()) {
+                self.update_properties(
+                    path,
+                    args.interface_name(),
+                    args.changed_properties(),
+                    args.invalidated_properties(),
+                );
+            }
+        }
+
+        Update::Applied
+    }
+
+    fn add_interfaces(
+        &self,
+        path: &ObjectPath<'_>,
+        interfaces: &HashMap<InterfaceName<'_>, HashMap<&str, Value<'_>>>,
+    ) {
+        let path = OwnedObjectPath::from(path.to_owned());
+        {
+            let mut objects = self.objects.write().expect("lock poisoned");
+            let object = objects.entry(path.clone()).or_default();
+            for (interface, properties) in interfaces {
+                let properties = properties
+                    .iter()
+                    .filter_map(|(name, value)| {
+                        OwnedValue::try_from(value)
+                            .map(|value| (name.to_string(), value))
+                            .ok()
+                    })
+                    .collect();
+                object.insert(interface.to_owned().into(), properties);
+            }
+        }
+        trace!("Interfaces added to {path}");
+
+        self.broadcast(ObjectManagerEvent::InterfacesAdded {
+            path,
+            interfaces: interfaces.keys().map(|i| i.to_owned().into()).collect(),
+        });
+    }
+
+    fn remove_interfaces(&self, path: &ObjectPath<'_>, interfaces: &[InterfaceName<'_>]) {
+        let path = OwnedObjectPath::from(path.to_owned());
+        {
+            let mut objects = self.objects.write().expect("lock poisoned");
+            if let Some(object) = objects.get_mut(&path) {
+                for interface in interfaces {
+                    object.remove(interface.as_str());
+                }
+                if object.is_empty() {
+                    objects.remove(&path);
+                }
+            }
+        }
+        for interface in interfaces {
+            let key = (path.clone(), OwnedInterfaceName::from(interface.to_owned()));
+            for cache in self.proxy_caches(&key) {
+                cache.reset(HashMap::new());
+            }
+        }
+        trace!("Interfaces removed from {path}");
+
+        self.broadcast(ObjectManagerEvent::InterfacesRemoved {
+            path,
+            interfaces: interfaces.iter().map(|i| i.to_owned().into()).collect(),
+        });
+    }
+
+    fn update_properties(
+        &self,
+        path: &ObjectPath<'_>,
+        interface: &InterfaceName<'_>,
+        changed: &HashMap<&str, Value<'_>>,
+        invalidated: &[&str],
+    ) {
+        let path = OwnedObjectPath::from(path.to_owned());
+        let interface = OwnedInterfaceName::from(interface.to_owned());
+        {
+            let mut objects = self.objects.write().expect("lock poisoned");
+            let Some(properties) = objects
+                .get_mut(&path)
+                .and_then(|interfaces| interfaces.get_mut(&interface))
+            else {
+                // Not a managed object.
+                return;
+            };
+            for name in invalidated {
+                properties.remove(*name);
+            }
+            for (name, value) in changed {
+                match OwnedValue::try_from(value) {
+                    Ok(value) => {
+                        properties.insert(name.to_string(), value);
+                    }
+                    Err(e) => debug!("Failed to convert property `{interface}.{name}`: {e}"),
+                }
+            }
+        }
+        let key = (path, interface);
+        for cache in self.proxy_caches(&key) {
+            cache.update_cache(&Default::default(), changed, invalidated, &key.1);
+        }
+        let (path, interface) = key;
+
+        self.broadcast(ObjectManagerEvent::PropertiesChanged {
+            path,
+            interface,
+            changed: changed.keys().map(|name| name.to_string()).collect(),
+            invalidated: invalidated.iter().map(|name| name.to_string()).collect(),
+        });
+    }
+
+    fn register_proxy_cache(
+        &self,
+        path: OwnedObjectPath,
+        interface: OwnedInterfaceName,
+        cache: &Arc<PropertiesCache>,
+    ) {
+        let mut proxy_caches = self.proxy_caches.lock().expect("lock poisoned");
+        let caches = proxy_caches.entry((path, interface)).or_default();
+        caches.retain(|cache| cache.strong_count() > 0);
+        caches.push(Arc::downgrade(cache));
+    }
+
+    /// The live properties caches of the proxies for `key`.
+    fn proxy_caches(
+        &self,
+        key: &(OwnedObjectPath, OwnedInterfaceName),
+    ) -> Vec<Arc<PropertiesCache>> {
+        self.proxy_caches
+            .lock()
+            .expect("lock poisoned")
+            .get(key)
+            .map(|caches| caches.iter().filter_map(Weak::upgrade).collect())
+            .unwrap_or_default()
+    }
+
+    fn broadcast(&self, event: ObjectManagerEvent) {
+        // Errors only mean that there are no active receivers or that the oldest event was
+        // dropped, both of which are fine.
+        let _ = self.events.try_broadcast(event);
+    }
+}
+
+/// Subscribe to all the signals needed to keep the cache updated.
+async fn subscribe(
+    conn: &Connection,
+    destination: &BusName<'_>,
+    path: &ObjectPath<'_>,
+) -> Result<impl OrderedStream<Data = Result<Message>, Ordering = Sequence> + Unpin> {
+    let object_manager_rule = MatchRule::builder()
+        .msg_type(Type::Signal)
+        .sender(destination)?
+        .path(path)?
+        .interface(OBJECT_MANAGER_INTERFACE)?
+        .build();
+    let properties_rule = MatchRule::builder()
+        .msg_type(Type::Signal)
+        .sender(destination)?
+        .path_namespace(path)?
+        .interface("org.freedesktop.DBus.Properties")?
+        .member("PropertiesChanged")?
+        .build();
+    let owner_rule = MatchRule::builder()
+        .msg_type(Type::Signal)
+        .sender("org.freedesktop.DBus")?
+        .path("/org/freedesktop/DBus")?
+        .interface("org.freedesktop.DBus")?
+        .member("NameOwnerChanged")?
+        .add_arg(destination.as_str())?
+        .build();
+
+    let object_manager_signals =
+        MessageStream::for_match_rule(object_manager_rule, conn, None).await?;
+    let properties_signals = MessageStream::for_match_rule(properties_rule, conn, None).await?;
+    let owner_signals = MessageStream::for_match_rule(owner_rule, conn, None).await?;
+
+    Ok(join_streams(
+        owner_signals,
+        join_streams(object_manager_signals, properties_signals),
+    ))
+}
+
+fn clone_properties(
+    properties: &HashMap<String, OwnedValue>,
+) -> Result<HashMap<String, OwnedValue>> {
+    properties
+        .iter()
+        .map(|(name, value)| Ok((name.clone(), value.try_clone()?)))
+        .collect()
+}
@@ -10,6 +10,9 @@ use zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Value};
 use super::{Error, Result};
 use crate::{interface, message::Header, object_server::SignalEmitter, Connection, ObjectServer};
 
+mod client;
+pub use client::{ObjectManagerClient, ObjectManagerEvent, ObjectManagerEventStream};
+
 /// The type returned by the [`ObjectManagerProxy::get_managed_objects`] method.
 pub type ManagedObjects =
     HashMap<OwnedObjectPath, HashMap<OwnedInterfaceName, HashMap<String, OwnedValue>>>;
@@ -5,7 +5,7 @@
 
 use std::{borrow::Cow, collections::HashMap};
 use zbus_names::InterfaceName;
-use zvariant::{OwnedValue, Value};
+use zvariant::{OwnedValue, Signature, Value};
 
 use super::{Error, Result};
 use crate::{interface, message::Header, object_server::SignalEmitter, Connection, ObjectServer};
@@ -74,7 +74,11 @@ impl Properties {
                 Error::UnknownInterface(format!("Unknown interface '{interface_name}'"))
             })?;
 
-        match iface.instance.read().await.set(
+        let instance = iface.instance.read().await;
+        if let Some((signature, writable)) = instance.property_signature(property_name) {
+            Self::validate_set(property_name, &signature, writable, &value)?;
+        }
+        match instance.set(
             property_name,
             &value,
             server,
@@ -89,9 +93,13 @@ impl Properties {
                 )));
             }
             zbus::object_server::DispatchResult::Async(f) => {
-                return f.await.map_err(Into::into);
+                return f.await.map_err(|e| match e {
+                    crate::Error::FDO(e) => *e,
+                    e => e.into(),
+                });
             }
         }
+        drop(instance);
         let res = iface
             .instance
             .write()
@@ -149,3 +157,86 @@ impl Properties {
         invalidated_properties: Cow<'_, [&str]>,
     ) -> zbus::Result<()>;
 }
+
+impl Properties {
+    /// Emit the `org.freedesktop.DBus.Properties.PropertiesChanged` signal for an interface.
+    ///
+    /// Unlike [`Properties::properties_changed`], if the interface is registered at multiple paths
+    /// through [`crate::ObjectServer::alias`], the signal is emitted at all of them (subject to
+    /// [`crate::ObjectServer::set_signal_emission`]). The `<property>_changed` methods generated by
+    /// the [`interface`] macro make use of this.
+    pub async fn notify_properties_changed(
+        emitter: &SignalEmitter<'_>,
+        interface_name: InterfaceName<'_>,
+        changed_properties: HashMap<&str, Value<'_>>,
+        invalidated_properties: Cow<'_, [&str]>,
+    ) -> zbus::Result<()> {
+        let conn = emitter.connection();
+        let paths = conn
+            .existing_object_server()
+            .and_then(|server| server.signal_paths(emitter.path(), &interface_name));
+        let Some(paths) = paths else {
+            return Self::properties_changed(
+                emitter,
+                interface_name,
+                changed_properties,
+                invalidated_properties,
+            )
+            .await;
+        };
+
+        for path in paths {
+            let mut alias_emitter = SignalEmitter::from_parts(conn.clone(), path.into());
+            if let Some(destination) = emitter.destination() {
+                alias_emitter = alias_emitter.set_destination(destination.clone());
+            }
+            let changed = changed_properties
+                .iter()
+                .map(|(name, value)| Ok((*name, value.try_clone()?)))
+                .collect::<zbus::Result<_>>()?;
+            Self::properties_changed(
+                &alias_emitter,
+                interface_name.clone(),
+                changed,
+                invalidated_properties.clone(),
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Validate a value passed to `org.freedesktop.DBus.Properties.Set`.
+    ///
+    /// Returns [`Error::PropertyReadOnly`] if the property is not `writable` and
+    /// [`Error::InvalidArgs`] if the signature of `value` doesn't match the `signature` of the
+    /// property. Properties of variant type (`v`) accept values of any type. The error messages
+    /// contain both the expected and the actual signatures.
+    ///
+    /// `Set` calls this before dispatching the value to the interface, with the property signature
+    /// returned by [`crate::object_server::Interface::property_signature`], so you only need this
+    /// if you set properties through other means.
+    pub fn validate_set(
+        property_name: &str,
+        signature: &Signature,
+        writable: bool,
+        value: &Value<'_>,
+    ) -> Result<()> {
+        let value_signature = value.value_signature();
+        if !writable {
+            return Err(Error::PropertyReadOnly(format!(
+                "Property '{property_name}' (signature '{signature}') is read-only, refusing to \
+                 set it to a value of signature '{value_signature}'"
+            )));
+        }
+
+        if *signature != Signature::Variant && value_signature != signature {
+            return Err(Error::InvalidArgs(format!(
+                "Invalid value for property '{property_name}': expected signature \
+                 '{signature}', got '{value_signature}'"
+            )));
+        }
+
+        Ok(())
+    }
+}
@@ -77,6 +77,9 @@ pub use match_rule::{MatchRule, OwnedMatchRule};
 pub mod proxy;
 pub use proxy::Proxy;
 
+mod properties;
+pub use properties::Properties;
+
 pub mod object_server;
 pub use object_server::ObjectServer;
 
@@ -89,7 +92,13 @@ pub mod fdo;
 #[cfg(feature = "blocking-api")]
 pub mod blocking;
 
-pub use zbus_macros::{interface, proxy, DBusError};
+#[cfg(feature = "tower")]
+pub mod tower;
+
+#[cfg(unix)]
+pub mod app;
+
+pub use zbus_macros::{interface, proxy, DBusError, FieldProperties, Properties};
 
 // Required for the macros to function within this crate.
 extern crate self as zbus;
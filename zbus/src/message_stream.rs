@@ -1,18 +1,24 @@
 use std::{
     pin::Pin,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicU8, AtomicUsize, Ordering},
+        Arc,
+    },
     task::{Context, Poll},
 };
 
-use async_broadcast::Receiver as ActiveReceiver;
-use futures_core::stream::{self, FusedStream};
+use async_broadcast::{Receiver as ActiveReceiver, RecvError};
+use futures_core::{
+    ready,
+    stream::{self, FusedStream},
+};
 use ordered_stream::{OrderedStream, PollResult};
 use tracing::warn;
 
 use crate::{
     connection::ConnectionInner,
     message::{Message, Sequence},
-    AsyncDrop, Connection, MatchRule, OwnedMatchRule, Result,
+    AsyncDrop, Connection, Error, MatchRule, OwnedMatchRule, Result,
 };
 
 /// A [`stream::Stream`] implementation that yields [`Message`] items.
@@ -131,10 +137,13 @@ impl MessageStream {
         R::Error: Into<crate::Error>,
     {
         let rule = rule.try_into().map_err(Into::into)?;
-        let msg_receiver = conn.add_match(rule.clone(), max_queued).await?;
+        let (msg_receiver, overflow) = conn
+            .add_match_with_overflow(rule.clone(), max_queued)
+            .await?;
 
         Ok(Self::for_subscription_channel(
             msg_receiver,
+            overflow,
             Some(rule),
             conn,
         ))
@@ -160,30 +169,154 @@ impl MessageStream {
         self.inner.msg_receiver.set_capacity(max_queued);
     }
 
+    /// The policy applied when the queue of this stream is full.
+    pub fn overflow_policy(&self) -> OverflowPolicy {
+        self.inner.overflow.policy()
+    }
+
+    /// Set the policy to apply when the queue of this stream is full.
+    ///
+    /// Unless the policy is [`OverflowPolicy::Block`] (the default), the stream yields an
+    /// [`Error::Lagged`] error in place of the messages it missed, so that consumers mirroring
+    /// some remote state know to resynchronize it.
+    ///
+    /// The first time it's called, the stream is moved to a queue of its own, so that the policy
+    /// doesn't apply to the other streams of the same connection for the same match rule (or
+    /// without a match rule).
+    pub async fn set_overflow_policy(&mut self, policy: OverflowPolicy) {
+        let inner = &mut self.inner;
+        if inner.private_queue {
+            inner.overflow.set_policy(policy);
+            inner
+                .msg_receiver
+                .set_overflow(policy == OverflowPolicy::DropOldest);
+
+            return;
+        }
+        if policy == OverflowPolicy::Block {
+            return;
+        }
+
+        let conn = Connection {
+            inner: inner.conn_inner.clone(),
+        };
+        let (msg_receiver, overflow) = conn
+            .add_private_queue(inner.match_rule.as_ref(), &mut inner.msg_receiver, policy)
+            .await;
+        inner.msg_receiver = msg_receiver;
+        inner.overflow = overflow;
+        inner.seen_dropped = 0;
+        inner.private_queue = true;
+    }
+
+    /// The number of messages this stream missed so far because its queue was full.
+    pub fn lagged(&self) -> u64 {
+        self.inner.lagged
+    }
+
     pub(crate) fn for_subscription_channel(
         msg_receiver: ActiveReceiver<Result<Message>>,
+        overflow: Arc<Overflow>,
         rule: Option<OwnedMatchRule>,
         conn: &Connection,
     ) -> Self {
         let conn_inner = conn.inner.clone();
 
         Self {
-            inner: Inner {
-                conn_inner,
-                msg_receiver,
-                match_rule: rule,
-            },
+            inner: Inner::new(conn_inner, msg_receiver, overflow, rule),
+        }
+    }
+}
+
+/// What to do with new messages for a [`MessageStream`] whose queue is full.
+///
+/// Use [`MessageStream::set_overflow_policy`] to set the policy of a stream.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum OverflowPolicy {
+    /// Stop reading from the socket until the stream catches up (the default).
+    ///
+    /// No message is lost but, in the meantime, none of the streams of the connection receive
+    /// any message, including method replies.
+    #[default]
+    Block,
+    /// Drop the oldest message in the queue to make room for the new one.
+    DropOldest,
+    /// Drop the new message.
+    DropNewest,
+}
+
+/// The overflow state of a message queue, shared by its sender and receivers.
+#[derive(Debug, Default)]
+pub(crate) struct Overflow {
+    policy: AtomicU8,
+    /// The number of messages dropped by the sender, under [`OverflowPolicy::DropNewest`].
+    dropped: AtomicUsize,
+}
+
+impl Overflow {
+    pub(crate) fn policy(&self) -> OverflowPolicy {
+        match self.policy.load(Ordering::Relaxed) {
+            1 => OverflowPolicy::DropOldest,
+            2 => OverflowPolicy::DropNewest,
+            _ => OverflowPolicy::Block,
         }
     }
+
+    pub(crate) fn new(policy: OverflowPolicy, dropped: usize) -> Self {
+        let overflow = Self {
+            policy: AtomicU8::new(0),
+            dropped: AtomicUsize::new(dropped),
+        };
+        overflow.set_policy(policy);
+
+        overflow
+    }
+
+    fn set_policy(&self, policy: OverflowPolicy) {
+        let policy = match policy {
+            OverflowPolicy::Block => 0,
+            OverflowPolicy::DropOldest => 1,
+            OverflowPolicy::DropNewest => 2,
+        };
+        self.policy.store(policy, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_drop(&self) {
+        self.dropped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn dropped(&self) -> usize {
+        self.dropped.load(Ordering::Relaxed)
+    }
 }
 
 impl stream::Stream for MessageStream {
     type Item = Result<Message>;
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        let this = self.get_mut();
+        let inner = &mut self.get_mut().inner;
+
+        // The messages dropped by the sender were not queued yet, so they can only be reported as
+        // soon as possible.
+        let dropped = inner.overflow.dropped();
+        if dropped != inner.seen_dropped {
+            let n = dropped.wrapping_sub(inner.seen_dropped) as u64;
+            inner.seen_dropped = dropped;
+            inner.lagged += n;
 
-        Pin::new(&mut this.inner.msg_receiver).poll_next(cx)
+            return Poll::Ready(Some(Err(Error::Lagged(n))));
+        }
+
+        match ready!(Pin::new(&mut inner.msg_receiver).poll_recv(cx)) {
+            Some(Ok(msg)) => Poll::Ready(Some(msg)),
+            Some(Err(RecvError::Overflowed(n))) => {
+                inner.lagged += n;
+
+                Poll::Ready(Some(Err(Error::Lagged(n))))
+            }
+            Some(Err(RecvError::Closed)) | None => Poll::Ready(None),
+        }
     }
 }
 
@@ -236,13 +369,10 @@ impl From<Connection> for MessageStream {
     fn from(conn: Connection) -> Self {
         let conn_inner = conn.inner;
         let msg_receiver = conn_inner.msg_receiver.activate_cloned();
+        let overflow = conn_inner.msg_overflow.clone();
 
         Self {
-            inner: Inner {
-                conn_inner,
-                msg_receiver,
-                match_rule: None,
-            },
+            inner: Inner::new(conn_inner, msg_receiver, overflow, None),
         }
     }
 }
@@ -272,6 +402,32 @@ struct Inner {
     conn_inner: Arc<ConnectionInner>,
     msg_receiver: ActiveReceiver<Result<Message>>,
     match_rule: Option<OwnedMatchRule>,
+    overflow: Arc<Overflow>,
+    // Whether `msg_receiver` is a queue of its own, rather than the one shared for `match_rule`.
+    private_queue: bool,
+    seen_dropped: usize,
+    lagged: u64,
+}
+
+impl Inner {
+    fn new(
+        conn_inner: Arc<ConnectionInner>,
+        msg_receiver: ActiveReceiver<Result<Message>>,
+        overflow: Arc<Overflow>,
+        match_rule: Option<OwnedMatchRule>,
+    ) -> Self {
+        let seen_dropped = overflow.dropped();
+
+        Self {
+            conn_inner,
+            msg_receiver,
+            match_rule,
+            overflow,
+            private_queue: false,
+            seen_dropped,
+            lagged: 0,
+        }
+    }
 }
 
 impl Drop for Inner {
@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+
+use zbus_names::{InterfaceName, OwnedInterfaceName};
+use zvariant::{ObjectPath, OwnedObjectPath};
+
+type Key = (OwnedObjectPath, OwnedInterfaceName);
+
+/// Keeps track of the interface instances registered at multiple paths.
+///
+/// All the paths an instance is registered at, form a group. Signals emitted for the instance
+/// at any of these paths, are emitted at all the paths of the group that have signal emission
+/// enabled.
+#[derive(Debug, Default)]
+pub(super) struct Aliases {
+    groups: HashMap<u64, Vec<(OwnedObjectPath, bool)>>,
+    group_ids: HashMap<Key, u64>,
+    next_group_id: u64,
+}
+
+impl Aliases {
+    /// Add `alias` to the group of `path`, creating the group if needed.
+    ///
+    /// `path` is `None` if the instance isn't registered anywhere else anymore.
+    pub fn add(
+        &mut self,
+        path: Option<&ObjectPath<'_>>,
+        alias: &ObjectPath<'_>,
+        interface: &InterfaceName<'_>,
+    ) {
+        let group_id = match path {
+            Some(path) => self.group_id(path, interface),
+            None => self.new_group(),
+        };
+        self.groups
+            .get_mut(&group_id)
+            .expect("alias group not found")
+            .push((alias.to_owned().into(), true));
+        self.group_ids.insert(key(alias, interface), group_id);
+    }
+
+    /// Remove the interface registered at `path` from its group.
+    pub fn remove(&mut self, path: &ObjectPath<'_>, interface: &InterfaceName<'_>) {
+        let Some(group_id) = self.group_ids.remove(&key(path, interface)) else {
+            return;
+        };
+        let group = self
+            .groups
+            .get_mut(&group_id)
+            .expect("alias group not found");
+        group.retain(|(p, _)| p.as_str() != path.as_str());
+        if group.is_empty() {
+            self.groups.remove(&group_id);
+        }
+    }
+
+    /// Enable or disable the emission of signals at `path`.
+    pub fn set_signal_emission(
+        &mut self,
+        path: &ObjectPath<'_>,
+        interface: &InterfaceName<'_>,
+        enabled: bool,
+    ) {
+        let group_id = self.group_id(path, interface);
+        let group = self
+            .groups
+            .get_mut(&group_id)
+            .expect("alias group not found");
+        if let Some((_, emit)) = group.iter_mut().find(|(p, _)| p.as_str() == path.as_str()) {
+            *emit = enabled;
+        }
+    }
+
+    /// Whether no interface is part of a group.
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    /// The paths to emit a signal at, for a signal emitted at `path`.
+    ///
+    /// Returns `None` if the interface isn't part of a group, in which case the signal should only
+    /// be emitted at `path`.
+    pub fn signal_paths(
+        &self,
+        path: &ObjectPath<'_>,
+        interface: &InterfaceName<'_>,
+    ) -> Option<Vec<OwnedObjectPath>> {
+        let group_id = self.group_ids.get(&key(path, interface))?;
+        let paths = self.groups[group_id]
+            .iter()
+            .filter(|(_, emit)| *emit)
+            .map(|(p, _)| p.clone())
+            .collect();
+
+        Some(paths)
+    }
+
+    /// The group of the interface at `path`, which is created if it doesn't exist.
+    fn group_id(&mut self, path: &ObjectPath<'_>, interface: &InterfaceName<'_>) -> u64 {
+        let key = key(path, interface);
+        if let Some(group_id) = self.group_ids.get(&key) {
+            return *group_id;
+        }
+
+        let group_id = self.new_group();
+        self.groups
+            .get_mut(&group_id)
+            .expect("alias group not found")
+            .push((key.0.clone(), true));
+        self.group_ids.insert(key, group_id);
+
+        group_id
+    }
+
+    fn new_group(&mut self) -> u64 {
+        let group_id = self.next_group_id;
+        self.next_group_id += 1;
+        self.groups.insert(group_id, Vec::new());
+
+        group_id
+    }
+}
+
+fn key(path: &ObjectPath<'_>, interface: &InterfaceName<'_>) -> Key {
+    (path.to_owned().into(), interface.to_owned().into())
+}
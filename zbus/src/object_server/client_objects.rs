@@ -0,0 +1,72 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use zbus_names::{BusName, OwnedUniqueName, UniqueName};
+use zvariant::{ObjectPath, OwnedObjectPath};
+
+use super::idle::{Activity, ActivityKind, IdleState};
+use crate::{fdo::DBusProxy, proxy::CacheProperties, Connection, Result};
+
+#[derive(Debug)]
+struct Binding {
+    client: OwnedUniqueName,
+    _activity: Activity,
+}
+
+/// The objects of an object server that are bound to the lifetime of a client.
+#[derive(Debug, Default)]
+pub(super) struct ClientObjects {
+    bindings: Mutex<HashMap<OwnedObjectPath, Binding>>,
+}
+
+impl ClientObjects {
+    /// Bind the object at `path` to `client`.
+    ///
+    /// Returns `false` if the client already left the bus, in which case the object needs to be
+    /// removed by the caller.
+    pub(super) async fn bind(
+        &self,
+        conn: &Connection,
+        idle: &Arc<IdleState>,
+        path: ObjectPath<'_>,
+        client: UniqueName<'_>,
+    ) -> Result<bool> {
+        // Watch before checking if the client is still around, so its departure isn't missed.
+        conn.watch_vanished_names().await?;
+        let client = OwnedUniqueName::from(client.into_owned());
+        self.bindings.lock().expect("lock poisoned").insert(
+            path.into(),
+            Binding {
+                client: client.clone(),
+                _activity: idle.start(ActivityKind::ClientObject),
+            },
+        );
+        let dbus = DBusProxy::builder(conn)
+            .cache_properties(CacheProperties::No)
+            .build()
+            .await?;
+
+        Ok(dbus.name_has_owner(BusName::from(client.as_ref())).await?)
+    }
+
+    /// Forget about the binding of the object at `path`, if any.
+    pub(super) fn unbind(&self, path: &ObjectPath<'_>) {
+        self.bindings
+            .lock()
+            .expect("lock poisoned")
+            .remove(&OwnedObjectPath::from(path.to_owned()));
+    }
+
+    /// The objects bound to `client`, which left the bus.
+    pub(super) fn client_paths(&self, client: &UniqueName<'_>) -> Vec<OwnedObjectPath> {
+        self.bindings
+            .lock()
+            .expect("lock poisoned")
+            .iter()
+            .filter(|(_, binding)| binding.client == *client)
+            .map(|(path, _)| path.clone())
+            .collect()
+    }
+}
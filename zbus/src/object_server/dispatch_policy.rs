@@ -0,0 +1,459 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    future::Future,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use event_listener::Event;
+use zbus_names::{OwnedInterfaceName, OwnedUniqueName};
+use zvariant::OwnedObjectPath;
+
+use crate::{async_lock::Semaphore, fdo, message::Header};
+
+/// The order in which the [`ObjectServer`](crate::ObjectServer) runs method calls.
+///
+/// When calls are ordered, the calls with the same key (e.g the same object path for
+/// [`CallOrdering::PerObject`]) are run one at a time, in the order they were received. Calls with
+/// different keys still run concurrently.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum CallOrdering {
+    /// Calls are run concurrently, without any ordering guarantee.
+    #[default]
+    Unordered,
+    /// Calls to the same object path are ordered.
+    PerObject,
+    /// Calls to the same interface of the same object path are ordered.
+    ///
+    /// Note that calls made through the standard interfaces, e.g `Get` and `Set` calls on
+    /// `org.freedesktop.DBus.Properties`, are ordered with the other calls to that standard
+    /// interface and not with the calls to the interface they concern.
+    PerInterface,
+    /// Calls from the same sender are ordered.
+    PerSender,
+}
+
+/// Limits on the method calls of each sender.
+///
+/// Senders are identified by their unique name. Calls exceeding any of the limits are refused with
+/// an `org.freedesktop.DBus.Error.LimitsExceeded` error, so that a misbehaving peer can't flood
+/// the [`ObjectServer`](crate::ObjectServer). By default, there are no limits.
+///
+/// The limits are set for all interfaces through [`DispatchPolicy::sender_limits`] and can be
+/// overridden for an interface through the `limits` attribute of the [`macro@crate::interface`]
+/// macro. The calls to an interface with its own limits are accounted separately from the calls to
+/// the other interfaces.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SenderLimits {
+    max_concurrent_calls: Option<usize>,
+    max_calls_per_second: Option<u32>,
+    max_queued_calls: Option<usize>,
+}
+
+impl SenderLimits {
+    /// Create limits without any limit set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the maximum number of calls from a sender that are handled at the same time, i.e
+    /// received but not replied to yet.
+    pub fn max_concurrent_calls(mut self, max: usize) -> Self {
+        self.max_concurrent_calls = Some(max);
+
+        self
+    }
+
+    /// Set the maximum number of calls a sender can make per second.
+    pub fn max_calls_per_second(mut self, max: u32) -> Self {
+        self.max_calls_per_second = Some(max);
+
+        self
+    }
+
+    /// Set the maximum number of calls from a sender waiting to be run.
+    ///
+    /// Calls wait when they're ordered after other calls or when all the workers are busy (see
+    /// [`DispatchPolicy`]).
+    pub fn max_queued_calls(mut self, max: usize) -> Self {
+        self.max_queued_calls = Some(max);
+
+        self
+    }
+
+    fn is_unlimited(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// The policy the [`ObjectServer`](crate::ObjectServer) dispatches method calls with.
+///
+/// By default, each method call is run concurrently in its own task, without any limit on the
+/// number of calls running at the same time. This can be changed through
+/// [`ObjectServer::set_dispatch_policy`] or [`crate::connection::Builder::dispatch_policy`].
+///
+/// The methods of interfaces that opted out of spawning tasks for method calls (through the `spawn`
+/// attribute of the [`macro@crate::interface`] macro) are subject to the policy as well. However,
+/// since these are called from the task dispatching all the method calls, no other call is
+/// dispatched while one of them waits for its turn.
+///
+/// # Example
+///
+/// ```
+/// use zbus::object_server::{CallOrdering, DispatchPolicy};
+///
+/// // Run at most 4 calls at the same time and calls to each object in order.
+/// let policy = DispatchPolicy::new()
+///     .ordering(CallOrdering::PerObject)
+///     .max_workers(4);
+/// # let _ = policy;
+/// ```
+///
+/// [`ObjectServer::set_dispatch_policy`]: crate::ObjectServer::set_dispatch_policy
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DispatchPolicy {
+    ordering: CallOrdering,
+    max_workers: Option<usize>,
+    sender_limits: SenderLimits,
+}
+
+impl DispatchPolicy {
+    /// Create the default policy.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the order in which method calls are run.
+    pub fn ordering(mut self, ordering: CallOrdering) -> Self {
+        self.ordering = ordering;
+
+        self
+    }
+
+    /// Set the maximum number of method calls that run at the same time.
+    ///
+    /// Additional calls wait for a running call to finish before they're run. Note that this limits
+    /// the number of calls being run, not the number of tasks spawned for the calls: each waiting
+    /// call still has its own task. Use [`SenderLimits::max_queued_calls`] to bound the number of
+    /// waiting calls.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max` is 0.
+    pub fn max_workers(mut self, max: usize) -> Self {
+        assert!(max > 0, "maximum number of workers must be greater than 0");
+        self.max_workers = Some(max);
+
+        self
+    }
+
+    /// Set the limits on the method calls of each sender.
+    ///
+    /// See [`SenderLimits`] for details.
+    pub fn sender_limits(mut self, limits: SenderLimits) -> Self {
+        self.sender_limits = limits;
+
+        self
+    }
+}
+
+/// Schedules the method calls according to a [`DispatchPolicy`].
+pub(crate) struct Dispatcher {
+    policy: DispatchPolicy,
+    workers: Option<Semaphore>,
+    // The last scheduled call for each key.
+    queues: Mutex<HashMap<QueueKey, Arc<Completion>>>,
+    senders: Mutex<Senders>,
+}
+
+impl Dispatcher {
+    pub fn new(policy: DispatchPolicy) -> Self {
+        Self {
+            policy,
+            workers: policy.max_workers.map(Semaphore::new),
+            queues: Mutex::new(HashMap::new()),
+            senders: Mutex::new(Senders {
+                states: HashMap::new(),
+                last_pruned: Instant::now(),
+            }),
+        }
+    }
+
+    pub fn policy(&self) -> DispatchPolicy {
+        self.policy
+    }
+
+    /// Schedule the call of `hdr`.
+    ///
+    /// Calls must be scheduled in the order they were received. `limits` are the limits of the
+    /// called interface, if it overrides the ones of the policy. If the call exceeds the limits, a
+    /// `LimitsExceeded` error is returned.
+    pub fn schedule(
+        self: &Arc<Self>,
+        hdr: &Header<'_>,
+        limits: Option<SenderLimits>,
+    ) -> fdo::Result<ScheduledCall> {
+        let (limits, interface) = match limits {
+            Some(limits) => (limits, hdr.interface().map(|i| i.to_owned().into())),
+            None => (self.policy.sender_limits, None),
+        };
+        let sender = if limits.is_unlimited() {
+            None
+        } else {
+            let key = (hdr.sender().map(|s| s.to_owned().into()), interface);
+            self.admit(&key, &limits)?;
+
+            Some(key)
+        };
+
+        let queue = self.queue_key(hdr).map(|key| {
+            let completion = Arc::new(Completion::default());
+            let previous = self
+                .queues
+                .lock()
+                .expect("lock poisoned")
+                .insert(key.clone(), completion.clone());
+
+            Queued {
+                key,
+                previous,
+                completion,
+            }
+        });
+
+        Ok(ScheduledCall {
+            dispatcher: self.clone(),
+            queue,
+            sender,
+            started: false,
+        })
+    }
+
+    /// Account for a new call from `sender`, unless it exceeds `limits`.
+    fn admit(&self, sender: &SenderKey, limits: &SenderLimits) -> fdo::Result<()> {
+        let mut senders = self.senders.lock().expect("lock poisoned");
+        let now = Instant::now();
+        // Forget about the senders we don't need to keep track of anymore. Only done once per rate
+        // window, so it doesn't cost a scan of all the senders for each call.
+        if now.duration_since(senders.last_pruned) >= RATE_WINDOW {
+            senders.states.retain(|_, state| !state.is_idle(now));
+            senders.last_pruned = now;
+        }
+        let state = senders
+            .states
+            .entry(sender.clone())
+            .or_insert_with(|| SenderState::new(now));
+
+        if now.duration_since(state.window_start) >= RATE_WINDOW {
+            state.window_start = now;
+            state.window_calls = 0;
+        }
+        let exceeded = if limits
+            .max_calls_per_second
+            .is_some_and(|max| state.window_calls >= max)
+        {
+            Some("calls per second")
+        } else if limits
+            .max_concurrent_calls
+            .is_some_and(|max| state.pending >= max)
+        {
+            Some("concurrent calls")
+        } else if limits
+            .max_queued_calls
+            .is_some_and(|max| state.queued >= max)
+        {
+            Some("queued calls")
+        } else {
+            None
+        };
+        if let Some(exceeded) = exceeded {
+            let sender = sender.0.as_ref().map_or("peer", |s| s.as_str());
+
+            return Err(fdo::Error::LimitsExceeded(format!(
+                "Too many {exceeded} from `{sender}`"
+            )));
+        }
+
+        state.window_calls += 1;
+        state.pending += 1;
+        state.queued += 1;
+
+        Ok(())
+    }
+
+    fn update_sender<F>(&self, sender: &SenderKey, f: F)
+    where
+        F: FnOnce(&mut SenderState),
+    {
+        if let Some(state) = self
+            .senders
+            .lock()
+            .expect("lock poisoned")
+            .states
+            .get_mut(sender)
+        {
+            f(state);
+        }
+    }
+
+    fn queue_key(&self, hdr: &Header<'_>) -> Option<QueueKey> {
+        let key = match self.policy.ordering {
+            CallOrdering::Unordered => return None,
+            CallOrdering::PerObject => QueueKey::Object(hdr.path()?.to_owned().into()),
+            CallOrdering::PerInterface => QueueKey::Interface(
+                hdr.path()?.to_owned().into(),
+                hdr.interface()?.to_owned().into(),
+            ),
+            CallOrdering::PerSender => QueueKey::Sender(hdr.sender().map(|s| s.to_owned().into())),
+        };
+
+        Some(key)
+    }
+}
+
+impl fmt::Debug for Dispatcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Dispatcher")
+            .field("policy", &self.policy)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A method call scheduled by a [`Dispatcher`].
+pub(crate) struct ScheduledCall {
+    dispatcher: Arc<Dispatcher>,
+    queue: Option<Queued>,
+    // Only set if the call is subject to sender limits.
+    sender: Option<SenderKey>,
+    started: bool,
+}
+
+impl ScheduledCall {
+    /// Run the call, once the previous calls in its queue are done and a worker is available.
+    pub async fn run<F>(mut self, call: F) -> F::Output
+    where
+        F: Future,
+    {
+        if let Some(previous) = self.queue.as_ref().and_then(|q| q.previous.as_ref()) {
+            previous.wait().await;
+        }
+        let _permit = match &self.dispatcher.workers {
+            Some(workers) => Some(workers.acquire().await),
+            None => None,
+        };
+        if let Some(sender) = &self.sender {
+            self.dispatcher
+                .update_sender(sender, |state| state.queued -= 1);
+        }
+        self.started = true;
+
+        call.await
+    }
+}
+
+impl Drop for ScheduledCall {
+    fn drop(&mut self) {
+        if let Some(sender) = &self.sender {
+            let started = self.started;
+            self.dispatcher.update_sender(sender, |state| {
+                state.pending -= 1;
+                if !started {
+                    state.queued -= 1;
+                }
+            });
+        }
+
+        let Some(queue) = self.queue.take() else {
+            return;
+        };
+        queue.completion.complete();
+
+        let mut queues = self.dispatcher.queues.lock().expect("lock poisoned");
+        if queues
+            .get(&queue.key)
+            .is_some_and(|last| Arc::ptr_eq(last, &queue.completion))
+        {
+            queues.remove(&queue.key);
+        }
+    }
+}
+
+struct Queued {
+    key: QueueKey,
+    previous: Option<Arc<Completion>>,
+    completion: Arc<Completion>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum QueueKey {
+    Object(OwnedObjectPath),
+    Interface(OwnedObjectPath, OwnedInterfaceName),
+    Sender(Option<OwnedUniqueName>),
+}
+
+type SenderKey = (Option<OwnedUniqueName>, Option<OwnedInterfaceName>);
+
+const RATE_WINDOW: Duration = Duration::from_secs(1);
+
+/// The senders accounted against their limits.
+#[derive(Debug)]
+struct Senders {
+    states: HashMap<SenderKey, SenderState>,
+    last_pruned: Instant,
+}
+
+/// The calls of a sender, accounted against its limits.
+#[derive(Debug)]
+struct SenderState {
+    pending: usize,
+    queued: usize,
+    window_start: Instant,
+    window_calls: u32,
+}
+
+impl SenderState {
+    fn new(now: Instant) -> Self {
+        Self {
+            pending: 0,
+            queued: 0,
+            window_start: now,
+            window_calls: 0,
+        }
+    }
+
+    fn is_idle(&self, now: Instant) -> bool {
+        self.pending == 0 && now.duration_since(self.window_start) >= RATE_WINDOW
+    }
+}
+
+/// Signals the completion of a call.
+#[derive(Debug, Default)]
+struct Completion {
+    done: AtomicBool,
+    event: Event,
+}
+
+impl Completion {
+    fn complete(&self) {
+        self.done.store(true, Ordering::SeqCst);
+        self.event.notify(usize::MAX);
+    }
+
+    async fn wait(&self) {
+        loop {
+            if self.done.load(Ordering::SeqCst) {
+                return;
+            }
+            let listener = self.event.listen();
+            if self.done.load(Ordering::SeqCst) {
+                return;
+            }
+            listener.await;
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use std::{future::Future, sync::Arc};
+
+use zbus_names::UniqueName;
+
+use crate::{
+    fdo::{self, ConnectionCredentials},
+    message::{Header, Message},
+    Connection, ObjectServer,
+};
+
+/// The context of a method call received by the [`ObjectServer`].
+///
+/// This is passed to [`FromMethodCall`] implementations to derive values from.
+#[derive(Debug, Clone, Copy)]
+pub struct CallContext<'c> {
+    message: &'c Message,
+    connection: &'c Connection,
+    object_server: &'c ObjectServer,
+}
+
+impl<'c> CallContext<'c> {
+    /// Create a new context for the method call `message`.
+    pub fn new(
+        message: &'c Message,
+        connection: &'c Connection,
+        object_server: &'c ObjectServer,
+    ) -> Self {
+        Self {
+            message,
+            connection,
+            object_server,
+        }
+    }
+
+    /// The method call message.
+    pub fn message(&self) -> &'c Message {
+        self.message
+    }
+
+    /// The header of the method call message.
+    pub fn header(&self) -> Header<'c> {
+        self.message.header()
+    }
+
+    /// The unique name of the caller, if any.
+    ///
+    /// This is `None` on peer-to-peer connections.
+    pub fn sender(&self) -> Option<UniqueName<'c>> {
+        self.message.header().sender().cloned()
+    }
+
+    /// The connection the method call was received on.
+    pub fn connection(&self) -> &'c Connection {
+        self.connection
+    }
+
+    /// The object server the method call was dispatched by.
+    pub fn object_server(&self) -> &'c ObjectServer {
+        self.object_server
+    }
+
+    /// The credentials of the caller.
+    ///
+    /// On a bus connection, these are queried from the bus. On peer-to-peer connections, these are
+    /// the credentials of the peer. Either way, they're cached by the connection so only the first
+    /// call from a given peer requires a query. On the bus, the cached credentials of a peer are
+    /// dropped when it disconnects.
+    ///
+    /// On Linux, if the bus doesn't provide a process FD (pidfd) for the caller, one is opened for
+    /// its process ID, if possible.
+    pub async fn sender_credentials(&self) -> fdo::Result<Arc<ConnectionCredentials>> {
+        self.connection
+            .cached_credentials(self.sender().as_ref())
+            .await
+    }
+}
+
+/// Types that can be derived from the context of a method call.
+///
+/// Arguments of [`macro@crate::interface`] methods marked with the `extract` attribute are created
+/// through this trait, before the method is called. This makes it possible to factor out the
+/// common logic of the methods, e.g access control. The method call is refused, with the returned
+/// error, if an extractor fails.
+///
+/// # Example
+///
+/// ```
+/// use zbus::{
+///     fdo,
+///     interface,
+///     object_server::{CallContext, FromMethodCall},
+/// };
+///
+/// /// The UID of the caller, if it's root.
+/// struct Root(u32);
+///
+/// impl FromMethodCall for Root {
+///     async fn from_method_call(call: &CallContext<'_>) -> fdo::Result<Self> {
+///         let credentials = call.sender_credentials().await?;
+///         match credentials.unix_user_id() {
+///             Some(0) => Ok(Root(0)),
+///             _ => Err(fdo::Error::AccessDenied("Only root can do that".into())),
+///         }
+///     }
+/// }
+///
+/// struct Service;
+///
+/// #[interface(name = "org.example.Service")]
+/// impl Service {
+///     fn reboot(&self, #[zbus(extract)] _root: Root) {
+///         // Reboot the system.
+///     }
+/// }
+/// ```
+pub trait FromMethodCall: Sized {
+    /// Derive a value from the context of a method call.
+    ///
+    /// Returning an error refuses the call with that error.
+    fn from_method_call(call: &CallContext<'_>) -> impl Future<Output = fdo::Result<Self>> + Send;
+}
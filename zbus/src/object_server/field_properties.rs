@@ -0,0 +1,198 @@
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fmt::{self, Write},
+    ops::{Deref, DerefMut},
+};
+
+use tracing::debug;
+use zvariant::{OwnedValue, Signature, Value};
+
+use super::{Interface, InterfaceDerefMut, SignalEmitter};
+use crate::{fdo, Result};
+
+/// D-Bus properties backed by the fields of a struct.
+///
+/// This trait is implemented by the [`FieldProperties` macro][fpm] and used by the
+/// [`interface`][im] macro when given the `field_properties` attribute, so that the fields marked
+/// as properties are exposed alongside the property methods of the interface.
+///
+/// As with [`super::Interface`], it is not recommended to implement this trait manually.
+///
+/// [fpm]: derive@crate::FieldProperties
+/// [im]: macro@crate::interface
+pub trait FieldProperties {
+    /// Whether `name` is a property backed by a field.
+    fn has_field_property(name: &str) -> bool;
+
+    /// Get the value of the field-backed property `name`, if it exists.
+    fn get_field_property(&self, name: &str) -> Option<fdo::Result<OwnedValue>>;
+
+    /// Get the values of all the field-backed properties.
+    fn get_all_field_properties(&self) -> fdo::Result<HashMap<String, OwnedValue>>;
+
+    /// The signature of the field-backed property `name` and whether it's writable, if it exists.
+    fn field_property_signature(name: &str) -> Option<(Signature, bool)>;
+
+    /// Set the value of the field-backed property `name`, if it exists.
+    ///
+    /// Returns whether the value of the field changed.
+    fn set_field_property(&mut self, name: &str, value: &Value<'_>) -> Option<fdo::Result<bool>>;
+
+    /// Write the introspection XML of the field-backed properties to `writer`.
+    fn introspect_field_properties(writer: &mut dyn Write, level: usize);
+}
+
+/// A mutable reference to an interface, tracking the changes to its field-backed properties.
+///
+/// The fields can be assigned to directly through this guard. Once it's dropped, or
+/// [committed](FieldPropertiesGuard::commit), the `PropertiesChanged` signal is emitted for the
+/// properties whose value changed in the meantime.
+///
+/// Use [`InterfaceRef::get_mut_tracked`](super::InterfaceRef::get_mut_tracked) to create an
+/// instance of this type.
+pub struct FieldPropertiesGuard<'a, I>
+where
+    I: Interface + FieldProperties,
+{
+    // Only `None` once released.
+    iface: Option<InterfaceDerefMut<'a, I>>,
+    emitter: &'a SignalEmitter<'static>,
+    initial: HashMap<String, OwnedValue>,
+}
+
+impl<'a, I> FieldPropertiesGuard<'a, I>
+where
+    I: Interface + FieldProperties,
+{
+    pub(super) fn new(
+        iface: InterfaceDerefMut<'a, I>,
+        emitter: &'a SignalEmitter<'static>,
+    ) -> Self {
+        let initial = iface.get_all_field_properties().unwrap_or_default();
+
+        Self {
+            iface: Some(iface),
+            emitter,
+            initial,
+        }
+    }
+
+    /// Release the interface and emit the `PropertiesChanged` signal for the changed properties.
+    ///
+    /// Unlike dropping the guard, this waits for the signal to be emitted and returns the errors.
+    pub async fn commit(mut self) -> Result<()> {
+        let changed = self.release();
+
+        notify_changes::<I>(self.emitter, changed).await
+    }
+
+    /// Release the interface, returning the properties that changed.
+    fn release(&mut self) -> HashMap<String, OwnedValue> {
+        let Some(iface) = self.iface.take() else {
+            return HashMap::new();
+        };
+        let mut current = iface.get_all_field_properties().unwrap_or_default();
+        current.retain(|name, value| self.initial.get(name) != Some(value));
+
+        current
+    }
+}
+
+impl<I> Deref for FieldPropertiesGuard<'_, I>
+where
+    I: Interface + FieldProperties,
+{
+    type Target = I;
+
+    fn deref(&self) -> &I {
+        self.iface.as_ref().expect("interface released")
+    }
+}
+
+impl<I> DerefMut for FieldPropertiesGuard<'_, I>
+where
+    I: Interface + FieldProperties,
+{
+    fn deref_mut(&mut self) -> &mut I {
+        self.iface.as_mut().expect("interface released")
+    }
+}
+
+impl<I> Drop for FieldPropertiesGuard<'_, I>
+where
+    I: Interface + FieldProperties,
+{
+    fn drop(&mut self) {
+        let changed = self.release();
+        if changed.is_empty() {
+            return;
+        }
+
+        let emitter = self.emitter.clone();
+        let executor = emitter.connection().executor().clone();
+        executor
+            .spawn(
+                async move {
+                    if let Err(e) = notify_changes::<I>(&emitter, changed).await {
+                        debug!("Failed to emit `PropertiesChanged`: {e}");
+                    }
+                },
+                "field properties change notifier",
+            )
+            .detach();
+    }
+}
+
+impl<I> fmt::Debug for FieldPropertiesGuard<'_, I>
+where
+    I: Interface + FieldProperties,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FieldPropertiesGuard")
+            .field("path", self.emitter.path())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Emit the `PropertiesChanged` signal for the field-backed property `name` of `I`, which was just
+/// set to `value`.
+///
+/// This is used by the code generated by the [`interface`][im] macro.
+///
+/// [im]: macro@crate::interface
+#[doc(hidden)]
+pub async fn notify_field_property_changed<I>(
+    emitter: &SignalEmitter<'_>,
+    name: &str,
+    value: &Value<'_>,
+) -> fdo::Result<()>
+where
+    I: Interface,
+{
+    let value = OwnedValue::try_from(value).map_err(crate::Error::from)?;
+    let changed = HashMap::from([(name.to_string(), value)]);
+
+    notify_changes::<I>(emitter, changed)
+        .await
+        .map_err(Into::into)
+}
+
+async fn notify_changes<I>(
+    emitter: &SignalEmitter<'_>,
+    changed: HashMap<String, OwnedValue>,
+) -> Result<()>
+where
+    I: Interface,
+{
+    if changed.is_empty() {
+        return Ok(());
+    }
+    let changed = changed
+        .iter()
+        .map(|(name, value)| Ok((name.as_str(), Value::try_from(value)?)))
+        .collect::<Result<_>>()?;
+
+    fdo::Properties::notify_properties_changed(emitter, I::name(), changed, Cow::Borrowed(&[]))
+        .await
+}
@@ -0,0 +1,233 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use event_listener::Event;
+
+use crate::timeout::timeout;
+
+/// Tracks whether a service is in use, so that it can exit once it's been idle for a while.
+///
+/// Use [`ObjectServer::idle_tracker`] to create an instance of this type. A service is considered
+/// busy as long as any of the following exists:
+///
+/// * A method call being handled by the [`ObjectServer`].
+/// * A name owned, or queued for, through [`Connection::own_name`]. Names requested through
+///   [`connection::Builder::name`] or [`Connection::request_name`] are not counted, as these are
+///   typically the names the service is activated for.
+/// * An object bound to a client through [`ObjectServer::bind_to_client`].
+///
+/// This is typically used by activatable services, to exit when they are not needed anymore:
+///
+/// ```no_run
+/// # use std::error::Error;
+/// # use std::time::Duration;
+/// # use zbus::{connection::Builder, interface};
+/// # use async_io::block_on;
+/// #
+/// struct Greeter;
+///
+/// #[interface(name = "org.zbus.Greeter1")]
+/// impl Greeter {
+///     fn say_hello(&self, name: &str) -> String {
+///         format!("Hello {name}!")
+///     }
+/// }
+///
+/// # block_on(async {
+/// let connection = Builder::starter()?
+///     .name("org.zbus.Greeter")?
+///     .serve_at("/org/zbus/Greeter", Greeter)?
+///     .build()
+///     .await?;
+///
+/// connection
+///     .object_server()
+///     .idle_tracker(Duration::from_secs(30))
+///     .idle()
+///     .await;
+/// // Not used for 30 seconds, time to exit.
+/// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+/// # })?;
+/// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+/// ```
+///
+/// [`ObjectServer`]: super::ObjectServer
+/// [`ObjectServer::idle_tracker`]: super::ObjectServer::idle_tracker
+/// [`ObjectServer::bind_to_client`]: super::ObjectServer::bind_to_client
+/// [`Connection::own_name`]: crate::Connection::own_name
+/// [`Connection::request_name`]: crate::Connection::request_name
+/// [`connection::Builder::name`]: crate::connection::Builder::name
+#[derive(Debug, Clone)]
+pub struct IdleTracker {
+    state: Arc<IdleState>,
+    timeout: Duration,
+    created: Instant,
+}
+
+impl IdleTracker {
+    pub(super) fn new(state: Arc<IdleState>, timeout: Duration) -> Self {
+        Self {
+            state,
+            timeout,
+            created: Instant::now(),
+        }
+    }
+
+    /// The duration the service needs to be idle for, before [`IdleTracker::idle`] resolves.
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// The number of method calls currently being handled.
+    pub fn in_flight_calls(&self) -> usize {
+        self.state.counts().calls
+    }
+
+    /// The number of names currently owned, or queued for, through
+    /// [`Connection::own_name`](crate::Connection::own_name).
+    pub fn owned_names(&self) -> usize {
+        self.state.counts().names
+    }
+
+    /// The number of objects currently bound to a client.
+    pub fn client_objects(&self) -> usize {
+        self.state.counts().client_objects
+    }
+
+    /// Whether the service is currently idle.
+    ///
+    /// This doesn't take the timeout into account.
+    pub fn is_idle(&self) -> bool {
+        self.state.counts().is_idle()
+    }
+
+    /// Wait until the service has been idle for the [timeout](IdleTracker::timeout).
+    ///
+    /// The idle period starts at the creation of the tracker at the earliest.
+    pub async fn idle(&self) {
+        loop {
+            // Listen before checking, so we don't miss a change in between.
+            let listener = self.state.event.listen();
+            let idle_since = {
+                let counts = self.state.counts();
+                counts
+                    .is_idle()
+                    .then(|| counts.idle_since.max(self.created))
+            };
+            let Some(idle_since) = idle_since else {
+                listener.await;
+
+                continue;
+            };
+            let remaining = self.timeout.saturating_sub(idle_since.elapsed());
+            if remaining.is_zero() {
+                return;
+            }
+            // Either the timeout elapses, or the activity changes and we check again.
+            let _ = timeout(
+                async {
+                    listener.await;
+
+                    Ok(())
+                },
+                remaining,
+            )
+            .await;
+        }
+    }
+}
+
+/// The activity of a connection, shared by its object server and name ownership guards.
+#[derive(Debug)]
+pub(crate) struct IdleState {
+    counts: Mutex<Counts>,
+    event: Event,
+}
+
+impl IdleState {
+    /// Account for a new activity, until the returned token is dropped.
+    pub(crate) fn start(self: &Arc<Self>, kind: ActivityKind) -> Activity {
+        *self.counts_mut().get_mut(kind) += 1;
+        self.event.notify(usize::MAX);
+
+        Activity {
+            state: self.clone(),
+            kind,
+        }
+    }
+
+    fn counts(&self) -> Counts {
+        *self.counts_mut()
+    }
+
+    fn counts_mut(&self) -> std::sync::MutexGuard<'_, Counts> {
+        self.counts.lock().expect("lock poisoned")
+    }
+}
+
+impl Default for IdleState {
+    fn default() -> Self {
+        Self {
+            counts: Mutex::new(Counts {
+                calls: 0,
+                names: 0,
+                client_objects: 0,
+                idle_since: Instant::now(),
+            }),
+            event: Event::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Counts {
+    calls: usize,
+    names: usize,
+    client_objects: usize,
+    // Only meaningful while idle.
+    idle_since: Instant,
+}
+
+impl Counts {
+    fn is_idle(&self) -> bool {
+        self.calls == 0 && self.names == 0 && self.client_objects == 0
+    }
+
+    fn get_mut(&mut self, kind: ActivityKind) -> &mut usize {
+        match kind {
+            ActivityKind::Call => &mut self.calls,
+            ActivityKind::Name => &mut self.names,
+            ActivityKind::ClientObject => &mut self.client_objects,
+        }
+    }
+}
+
+/// The kinds of activity keeping a service busy.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum ActivityKind {
+    Call,
+    Name,
+    ClientObject,
+}
+
+/// An ongoing activity, ending when dropped.
+#[derive(Debug)]
+pub(crate) struct Activity {
+    state: Arc<IdleState>,
+    kind: ActivityKind,
+}
+
+impl Drop for Activity {
+    fn drop(&mut self) {
+        {
+            let mut counts = self.state.counts_mut();
+            *counts.get_mut(self.kind) -= 1;
+            if counts.is_idle() {
+                counts.idle_since = Instant::now();
+            }
+        }
+        self.state.event.notify(usize::MAX);
+    }
+}
@@ -0,0 +1,110 @@
+use std::{collections::HashMap, fmt, marker::PhantomData, sync::Arc};
+
+use zbus_names::InterfaceName;
+use zvariant::{ObjectPath, OwnedValue};
+
+use super::{Interface, InterfaceRef, SignalEmitter};
+use crate::{async_lock::RwLock, fdo};
+
+/// A type-erased handle to an interface registered on an [`ObjectServer`].
+///
+/// Unlike [`InterfaceRef`], this doesn't require knowing the concrete type of the interface. It is
+/// mainly useful for inspecting the contents of an [`ObjectServer`], e.g for debugging purposes.
+/// Use [`InterfaceHandle::downcast`] to get an [`InterfaceRef`] if you do know the type.
+///
+/// Use [`ObjectServer::interface_handle`] to get an instance of this type.
+///
+/// [`ObjectServer`]: crate::ObjectServer
+/// [`ObjectServer::interface_handle`]: crate::ObjectServer::interface_handle
+#[derive(Clone)]
+pub struct InterfaceHandle {
+    pub(crate) name: InterfaceName<'static>,
+    pub(crate) emitter: SignalEmitter<'static>,
+    pub(crate) lock: Arc<RwLock<dyn Interface>>,
+}
+
+impl InterfaceHandle {
+    /// The name of the interface.
+    pub fn name(&self) -> &InterfaceName<'static> {
+        &self.name
+    }
+
+    /// The object path the interface is registered at.
+    pub fn path(&self) -> &ObjectPath<'static> {
+        self.emitter.path()
+    }
+
+    /// The signal emitter for the object the interface is registered at.
+    pub fn signal_emitter(&self) -> &SignalEmitter<'static> {
+        &self.emitter
+    }
+
+    /// Whether the interface is of type `I`.
+    pub async fn is<I>(&self) -> bool
+    where
+        I: Interface,
+    {
+        self.lock.read().await.downcast_ref::<I>().is_some()
+    }
+
+    /// Get an [`InterfaceRef`] for the interface, if it is of type `I`.
+    pub async fn downcast<I>(&self) -> Option<InterfaceRef<I>>
+    where
+        I: Interface,
+    {
+        if !self.is::<I>().await {
+            return None;
+        }
+
+        Some(InterfaceRef {
+            emitter: self.emitter.clone(),
+            lock: self.lock.clone(),
+            phantom: PhantomData,
+        })
+    }
+
+    /// Get the value of a property of the interface.
+    ///
+    /// Returns `None` if the property doesn't exist.
+    pub async fn get_property(&self, property_name: &str) -> Option<fdo::Result<OwnedValue>> {
+        let conn = self.emitter.connection();
+        self.lock
+            .read()
+            .await
+            .get(
+                property_name,
+                conn.object_server(),
+                conn,
+                None,
+                &self.emitter,
+            )
+            .await
+    }
+
+    /// Get the values of all the properties of the interface.
+    pub async fn get_all_properties(&self) -> fdo::Result<HashMap<String, OwnedValue>> {
+        let conn = self.emitter.connection();
+        self.lock
+            .read()
+            .await
+            .get_all(conn.object_server(), conn, None, &self.emitter)
+            .await
+    }
+
+    /// The introspection XML of the interface.
+    pub async fn introspect(&self) -> String {
+        let mut xml = String::with_capacity(512);
+        self.lock.read().await.introspect_to_writer(&mut xml, 0);
+
+        xml
+    }
+}
+
+impl fmt::Debug for InterfaceHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InterfaceHandle")
+            .field("name", &self.name)
+            .field("path", self.path())
+            .finish_non_exhaustive()
+    }
+}
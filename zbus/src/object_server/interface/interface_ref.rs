@@ -1,7 +1,10 @@
 use std::{marker::PhantomData, sync::Arc};
 
 use super::{Interface, InterfaceDeref, InterfaceDerefMut, SignalEmitter};
-use crate::async_lock::RwLock;
+use crate::{
+    async_lock::RwLock,
+    object_server::{FieldProperties, FieldPropertiesGuard},
+};
 
 /// Wrapper over an interface, along with its corresponding `SignalEmitter`
 /// instance. A reference to the underlying interface may be obtained via
@@ -105,6 +108,56 @@ where
     }
 }
 
+impl<I> InterfaceRef<I>
+where
+    I: Interface + FieldProperties,
+{
+    /// Get a mutable reference to the underlying interface, emitting the `PropertiesChanged`
+    /// signal for the field-backed properties changed through it.
+    ///
+    /// This is the same as [`InterfaceRef::get_mut`], except that the fields marked as properties
+    /// with the [`FieldProperties`](derive@crate::FieldProperties) macro can be assigned directly:
+    /// the changes are signaled once the returned guard is dropped or committed.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use async_io::block_on;
+    /// # use zbus::{Connection, interface, FieldProperties};
+    ///
+    /// #[derive(FieldProperties)]
+    /// struct Counter {
+    ///     #[zbus(property)]
+    ///     count: u32,
+    /// }
+    ///
+    /// #[interface(name = "org.myiface.Counter", field_properties)]
+    /// impl Counter {}
+    ///
+    /// # block_on(async {
+    /// # let connection = Connection::session().await?;
+    /// #
+    /// # let path = "/org/zbus/path";
+    /// # connection.object_server().at(path, Counter { count: 0 }).await?;
+    /// let iface_ref = connection
+    ///     .object_server()
+    ///     .interface::<_, Counter>(path)
+    ///     .await?;
+    /// let mut counter = iface_ref.get_mut_tracked().await;
+    /// counter.count += 1;
+    /// // Emits the `PropertiesChanged` signal for `Count`.
+    /// counter.commit().await?;
+    /// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+    /// # })?;
+    /// #
+    /// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+    /// ```
+    pub async fn get_mut_tracked(&self) -> FieldPropertiesGuard<'_, I> {
+        FieldPropertiesGuard::new(self.get_mut().await, &self.emitter)
+    }
+}
+
 impl<I> Clone for InterfaceRef<I> {
     fn clone(&self) -> Self {
         Self {
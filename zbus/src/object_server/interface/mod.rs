@@ -4,6 +4,8 @@ mod interface_ref;
 pub use interface_ref::*;
 mod interface_deref;
 pub use interface_deref::*;
+mod interface_handle;
+pub use interface_handle::*;
 
 use std::{
     any::{Any, TypeId},
@@ -14,13 +16,13 @@ use std::{
 
 use async_trait::async_trait;
 use zbus_names::{InterfaceName, MemberName};
-use zvariant::{OwnedValue, Value};
+use zvariant::{OwnedValue, Signature, Value};
 
 use crate::{
     async_lock::RwLock,
     fdo,
     message::{self, Header, Message},
-    object_server::SignalEmitter,
+    object_server::{SenderLimits, SignalEmitter},
     Connection, ObjectServer,
 };
 
@@ -47,6 +49,22 @@ pub trait Interface: Any + Send + Sync {
         true
     }
 
+    /// The limits on the method calls of each sender, overriding the ones of the
+    /// [`DispatchPolicy`](crate::object_server::DispatchPolicy) for this interface.
+    fn sender_limits(&self) -> Option<SenderLimits> {
+        None
+    }
+
+    /// Whether the method `name` is blocking and needs to be run on the blocking thread pool.
+    ///
+    /// Calls to blocking methods are dispatched from a thread of the connection's blocking thread
+    /// pool, instead of the async runtime. See [`crate::connection::Builder::blocking_pool_size`].
+    fn is_blocking_method(&self, name: &MemberName<'_>) -> bool {
+        let _ = name;
+
+        false
+    }
+
     /// Get a property value. Returns `None` if the property doesn't exist.
     ///
     /// Note: The header parameter will be None when the getter is not being called as part
@@ -71,6 +89,18 @@ pub trait Interface: Any + Send + Sync {
         emitter: &SignalEmitter<'_>,
     ) -> fdo::Result<HashMap<String, OwnedValue>>;
 
+    /// The signature of the property `property_name` and whether it's writable.
+    ///
+    /// `org.freedesktop.DBus.Properties.Set` uses this to validate the values before passing them
+    /// to [`Interface::set`], through [`fdo::Properties::validate_set`]. Returns `None` if the
+    /// property doesn't exist, in which case no validation happens. The default implementation
+    /// always returns `None`.
+    fn property_signature(&self, property_name: &str) -> Option<(Signature, bool)> {
+        let _ = property_name;
+
+        None
+    }
+
     /// Set a property value.
     ///
     /// Return [`DispatchResult::NotFound`] if the property doesn't exist, or
@@ -146,6 +176,7 @@ pub trait Interface: Any + Send + Sync {
 pub(crate) struct ArcInterface {
     pub instance: Arc<RwLock<dyn Interface>>,
     pub spawn_tasks_for_methods: bool,
+    pub sender_limits: Option<SenderLimits>,
 }
 
 impl ArcInterface {
@@ -154,9 +185,11 @@ impl ArcInterface {
         I: Interface,
     {
         let spawn_tasks_for_methods = iface.spawn_tasks_for_methods();
+        let sender_limits = iface.sender_limits();
         Self {
             instance: Arc::new(RwLock::new(iface)),
             spawn_tasks_for_methods,
+            sender_limits,
         }
     }
 }
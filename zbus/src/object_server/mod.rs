@@ -1,13 +1,22 @@
 //! The object server API.
 
-use std::{collections::HashMap, marker::PhantomData, sync::Arc};
+use async_broadcast::{broadcast, InactiveReceiver, Sender};
+use std::{
+    collections::HashMap,
+    marker::PhantomData,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 use tracing::{debug, instrument, trace, trace_span, Instrument};
 
-use zbus_names::InterfaceName;
-use zvariant::{ObjectPath, Value};
+use zbus_names::{InterfaceName, MemberName, UniqueName};
+use zvariant::{ObjectPath, OwnedObjectPath, Value};
 
 use crate::{
-    async_lock::RwLock,
+    async_lock::{RwLock, RwLockReadGuard},
     connection::WeakConnection,
     fdo,
     fdo::ObjectManager,
@@ -17,7 +26,14 @@ use crate::{
 
 mod interface;
 pub(crate) use interface::ArcInterface;
-pub use interface::{DispatchResult, Interface, InterfaceDeref, InterfaceDerefMut, InterfaceRef};
+pub use interface::{
+    DispatchResult, Interface, InterfaceDeref, InterfaceDerefMut, InterfaceHandle, InterfaceRef,
+};
+
+mod field_properties;
+#[doc(hidden)]
+pub use field_properties::notify_field_property_changed;
+pub use field_properties::{FieldProperties, FieldPropertiesGuard};
 
 mod signal_emitter;
 pub use signal_emitter::SignalEmitter;
@@ -30,6 +46,29 @@ pub use dispatch_notifier::ResponseDispatchNotifier;
 mod node;
 pub(crate) use node::Node;
 
+mod aliases;
+use aliases::Aliases;
+
+mod registration;
+pub use registration::{RegistrationChange, RegistrationChangeStream};
+
+mod extract;
+pub use extract::{CallContext, FromMethodCall};
+
+mod dispatch_policy;
+use dispatch_policy::Dispatcher;
+pub use dispatch_policy::{CallOrdering, DispatchPolicy, SenderLimits};
+
+mod idle;
+pub use idle::IdleTracker;
+pub(crate) use idle::{Activity, ActivityKind, IdleState};
+
+mod client_objects;
+use client_objects::ClientObjects;
+
+/// The maximum number of registration changes kept for slow receivers.
+const MAX_QUEUED_REGISTRATION_CHANGES: usize = 64;
+
 /// An object server, holding server-side D-Bus objects & interfaces.
 ///
 /// Object servers hold interfaces on various object paths, and expose them over D-Bus.
@@ -91,16 +130,42 @@ pub(crate) use node::Node;
 pub struct ObjectServer {
     conn: WeakConnection,
     root: Arc<RwLock<Node>>,
+    registration_changes: Sender<RegistrationChange>,
+    // Keeps the channel open, even if nobody is listening.
+    registration_changes_receiver: InactiveReceiver<RegistrationChange>,
+    aliases: Arc<std::sync::Mutex<Aliases>>,
+    // Whether `aliases` is non-empty, so signals can be emitted without locking it in most cases.
+    has_aliases: Arc<AtomicBool>,
+    dispatcher: Arc<std::sync::Mutex<Arc<Dispatcher>>>,
+    #[cfg(feature = "tower")]
+    dispatch_stack: Arc<std::sync::RwLock<Option<crate::tower::DispatchStack>>>,
+    idle: Arc<IdleState>,
+    client_objects: Arc<ClientObjects>,
 }
 
 impl ObjectServer {
     /// Create a new D-Bus `ObjectServer`.
     pub(crate) fn new(conn: &Connection) -> Self {
+        let (mut registration_changes, receiver) = broadcast(MAX_QUEUED_REGISTRATION_CHANGES);
+        registration_changes.set_overflow(true);
+        registration_changes.set_await_active(false);
+
         Self {
             conn: conn.into(),
             root: Arc::new(RwLock::new(Node::new(
                 "/".try_into().expect("zvariant bug"),
             ))),
+            registration_changes,
+            registration_changes_receiver: receiver.deactivate(),
+            aliases: Default::default(),
+            has_aliases: Default::default(),
+            dispatcher: Arc::new(std::sync::Mutex::new(Arc::new(Dispatcher::new(
+                DispatchPolicy::default(),
+            )))),
+            #[cfg(feature = "tower")]
+            dispatch_stack: Default::default(),
+            idle: conn.idle_state().clone(),
+            client_objects: Default::default(),
         }
     }
 
@@ -142,6 +207,10 @@ impl ObjectServer {
         let node = node.unwrap();
         let added = node.add_arc_interface(name.clone(), arc_iface);
         if added {
+            self.notify_registration_change(RegistrationChange::Added {
+                path: path.clone().into(),
+                interface: name.clone().into(),
+            });
             if name == ObjectManager::name() {
                 // Just added an object manager. Need to signal all managed objects under it.
                 let emitter = SignalEmitter::new(&self.connection(), path)?;
@@ -189,17 +258,32 @@ impl ObjectServer {
         P::Error: Into<Error>,
     {
         let path = path.try_into().map_err(Into::into)?;
+
+        self.remove_by_name(path, I::name()).await
+    }
+
+    async fn remove_by_name(
+        &self,
+        path: ObjectPath<'_>,
+        name: InterfaceName<'static>,
+    ) -> Result<bool> {
         let mut root = self.root.write().await;
         let (node, manager_path) = root.get_child_mut(&path, false);
         let node = node.ok_or(Error::InterfaceNotFound)?;
-        if !node.remove_interface(I::name()) {
+        if !node.remove_interface(name.clone()) {
             return Err(Error::InterfaceNotFound);
         }
+        self.update_aliases(|aliases| aliases.remove(&path, &name));
+        self.notify_registration_change(RegistrationChange::Removed {
+            path: path.clone().into(),
+            interface: name.clone().into(),
+        });
         if let Some(manager_path) = manager_path {
             let ctxt = SignalEmitter::new(&self.connection(), manager_path.clone())?;
-            ObjectManager::interfaces_removed(&ctxt, path.clone(), (&[I::name()]).into()).await?;
+            ObjectManager::interfaces_removed(&ctxt, path.clone(), (&[name]).into()).await?;
         }
         if node.is_empty() {
+            self.client_objects.unbind(&path);
             let mut path_parts = path.rsplit('/').filter(|i| !i.is_empty());
             let last_part = path_parts.next().unwrap();
             let ppath = ObjectPath::from_string_unchecked(
@@ -214,6 +298,288 @@ impl ObjectServer {
         Ok(false)
     }
 
+    /// Unregister all the interfaces at the given path, destroying the object.
+    ///
+    /// Returns whether the object existed.
+    async fn remove_object(&self, path: ObjectPath<'_>) -> Result<bool> {
+        let names: Vec<_> = {
+            let root = self.root.read().await;
+            root.get_child(&path)
+                .map(|node| node.user_interface_names().cloned().collect())
+                .unwrap_or_default()
+        };
+        if names.is_empty() {
+            self.client_objects.unbind(&path);
+
+            return Ok(false);
+        }
+        for name in names {
+            self.remove_by_name(path.clone(), name).await?;
+        }
+
+        Ok(true)
+    }
+
+    /// Bind the lifetime of the object at the given path to a client.
+    ///
+    /// The object, i.e. all the interfaces registered at `path`, is removed when `client` leaves
+    /// the bus. This is useful for objects created on behalf of a client, e.g. a session or a
+    /// transaction. Objects bound to a client also keep the service busy, as far as the
+    /// [`IdleTracker`] is concerned. The binding goes away with the object, whether it's removed
+    /// automatically or through [`ObjectServer::remove`].
+    ///
+    /// If `client` already left the bus, the object is removed right away.
+    ///
+    /// # Errors
+    ///
+    /// If no interface is registered at the given path, an `Error::InterfaceNotFound` error is
+    /// returned. On peer-to-peer connections, an `Error::Unsupported` error is returned.
+    pub async fn bind_to_client<'p, 'c, P, C>(&self, path: P, client: C) -> Result<()>
+    where
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+        C: TryInto<UniqueName<'c>>,
+        C::Error: Into<Error>,
+    {
+        let path = path.try_into().map_err(Into::into)?;
+        let client = client.try_into().map_err(Into::into)?;
+        let conn = self.connection();
+        if !conn.is_bus() {
+            return Err(Error::Unsupported);
+        }
+        {
+            let root = self.root.read().await;
+            let node = root.get_child(&path).ok_or(Error::InterfaceNotFound)?;
+            if node.is_empty() {
+                return Err(Error::InterfaceNotFound);
+            }
+        }
+
+        if !self
+            .client_objects
+            .bind(&conn, &self.idle, path.clone(), client)
+            .await?
+        {
+            trace!("Removing `{path}`, as its client already left");
+            self.remove_object(path).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Remove the objects bound to `client`, which left the bus.
+    pub(crate) async fn remove_client_objects(&self, client: &UniqueName<'_>) {
+        for path in self.client_objects.client_paths(client) {
+            trace!("Removing `{path}`, as its client `{client}` left");
+            if let Err(e) = self.remove_object(path.as_ref()).await {
+                debug!("Failed to remove `{path}`: {e}");
+            }
+        }
+    }
+
+    /// Create an [`IdleTracker`], to find out when the service is no longer in use.
+    ///
+    /// The tracker considers the service idle once it was not in use for `timeout`. See
+    /// [`IdleTracker`] for what counts as use.
+    pub fn idle_tracker(&self, timeout: Duration) -> IdleTracker {
+        IdleTracker::new(self.idle.clone(), timeout)
+    }
+
+    /// Register an already registered interface at an additional path.
+    ///
+    /// The interface instance behind `iface` is shared between all the paths it is registered at,
+    /// so all of them expose the same state. Moreover, the signals emitted for the interface at any
+    /// of these paths, are emitted at all of them. The same goes for the property change
+    /// notifications emitted through [`crate::fdo::Properties::notify_properties_changed`], which
+    /// the methods generated by the [`crate::interface`] macro make use of.
+    /// Use [`ObjectServer::set_signal_emission`] to restrict the signal emission to a subset of the
+    /// paths.
+    ///
+    /// Unregistering the interface at one of the paths through [`ObjectServer::remove`] doesn't
+    /// affect the other paths.
+    ///
+    /// If an interface of the same type already exists at this path, returns false.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use zbus::{Connection, interface};
+    /// # use async_io::block_on;
+    /// #
+    /// struct Device;
+    ///
+    /// #[interface(name = "org.example.Device")]
+    /// impl Device {}
+    ///
+    /// # block_on(async {
+    /// let connection = Connection::session().await?;
+    /// let object_server = connection.object_server();
+    /// object_server.at("/org/example/Devices/0", Device).await?;
+    /// let iface_ref = object_server
+    ///     .interface::<_, Device>("/org/example/Devices/0")
+    ///     .await?;
+    /// object_server.alias("/org/example/Default", &iface_ref).await?;
+    /// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+    /// # })?;
+    /// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+    /// ```
+    pub async fn alias<'p, P, I>(&self, path: P, iface: &InterfaceRef<I>) -> Result<bool>
+    where
+        I: Interface,
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+    {
+        let path = path.try_into().map_err(Into::into)?;
+        let (spawn_tasks_for_methods, sender_limits) = {
+            let instance = iface.lock.read().await;
+            (instance.spawn_tasks_for_methods(), instance.sender_limits())
+        };
+        let arc_iface = ArcInterface {
+            instance: iface.lock.clone(),
+            spawn_tasks_for_methods,
+            sender_limits,
+        };
+        // The interface may since have been removed from its original path.
+        let original_path = iface.signal_emitter().path();
+        let registered = {
+            let root = self.root.read().await;
+            root.get_child(original_path)
+                .and_then(|node| node.interface_lock(I::name()))
+                .is_some_and(|i| Arc::ptr_eq(&i.instance, &iface.lock))
+        };
+
+        let added = self
+            .add_arc_interface(path.clone(), I::name(), arc_iface)
+            .await?;
+        if added {
+            self.update_aliases(|aliases| {
+                aliases.add(registered.then_some(original_path), &path, &I::name())
+            });
+        }
+
+        Ok(added)
+    }
+
+    /// Enable or disable the emission of the signals of the interface `I`, at the given path.
+    ///
+    /// This is mainly useful for interfaces registered at multiple paths through
+    /// [`ObjectServer::alias`], to emit the signals only at a subset of these paths. By default,
+    /// signals are emitted at all the paths.
+    ///
+    /// Note that disabling the emission of an interface that is only registered at `path`,
+    /// silences its signals completely.
+    ///
+    /// # Errors
+    ///
+    /// If the interface is not registered at the given path, an `Error::InterfaceNotFound` error is
+    /// returned.
+    pub async fn set_signal_emission<'p, P, I>(&self, path: P, enabled: bool) -> Result<()>
+    where
+        I: Interface,
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+    {
+        let path = path.try_into().map_err(Into::into)?;
+        let root = self.root.read().await;
+        root.get_child(&path)
+            .and_then(|node| node.interface_lock(I::name()))
+            .ok_or(Error::InterfaceNotFound)?;
+        self.update_aliases(|aliases| aliases.set_signal_emission(&path, &I::name(), enabled));
+
+        Ok(())
+    }
+
+    /// Set the policy method calls are dispatched with.
+    ///
+    /// The policy applies to the calls received after this call. See [`DispatchPolicy`] for
+    /// details.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// use zbus::{
+    ///     object_server::{CallOrdering, DispatchPolicy},
+    ///     Connection,
+    /// };
+    ///
+    /// # async_io::block_on(async {
+    /// let connection = Connection::session().await?;
+    /// // Run the calls to each object in the order they were received.
+    /// connection
+    ///     .object_server()
+    ///     .set_dispatch_policy(DispatchPolicy::new().ordering(CallOrdering::PerObject));
+    /// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+    /// # })?;
+    /// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+    /// ```
+    pub fn set_dispatch_policy(&self, policy: DispatchPolicy) {
+        *self.dispatcher.lock().expect("lock poisoned") = Arc::new(Dispatcher::new(policy));
+    }
+
+    /// The policy method calls are dispatched with.
+    pub fn dispatch_policy(&self) -> DispatchPolicy {
+        self.dispatcher.lock().expect("lock poisoned").policy()
+    }
+
+    /// Pass the incoming method calls through the given `tower` middleware.
+    ///
+    /// `layer` wraps the [`Dispatch`](crate::tower::Dispatch) service, which calls the method of
+    /// the interface and sends the reply. The calls pass through the resulting stack before
+    /// reaching the interface, allowing the middleware to e.g log, time out or refuse them. If the
+    /// stack fails, the error is sent as the reply to the call. Errors other than [`fdo::Error`]
+    /// are sent as `org.freedesktop.DBus.Error.Failed` errors.
+    ///
+    /// Multiple layers can be combined with `tower::ServiceBuilder`. Setting a layer replaces the
+    /// previous one, if any.
+    ///
+    /// Requires the `tower` feature to be enabled.
+    #[cfg(feature = "tower")]
+    pub fn set_dispatch_layer<L>(&self, layer: L)
+    where
+        L: crate::tower::Layer<crate::tower::Dispatch>,
+        L::Service: crate::tower::Service<crate::tower::DispatchRequest, Response = ()>
+            + Clone
+            + Send
+            + Sync
+            + 'static,
+        <L::Service as crate::tower::Service<crate::tower::DispatchRequest>>::Error:
+            Into<Box<dyn std::error::Error + Send + Sync>>,
+        <L::Service as crate::tower::Service<crate::tower::DispatchRequest>>::Future:
+            Send + 'static,
+    {
+        *self.dispatch_stack.write().expect("lock poisoned") =
+            Some(crate::tower::DispatchStack::new(layer));
+    }
+
+    /// The paths to emit a signal at, for a signal of `interface` emitted at `path`.
+    ///
+    /// Returns `None` if the signal should only be emitted at `path`.
+    pub(crate) fn signal_paths(
+        &self,
+        path: &ObjectPath<'_>,
+        interface: &InterfaceName<'_>,
+    ) -> Option<Vec<OwnedObjectPath>> {
+        if !self.has_aliases.load(Ordering::Acquire) {
+            return None;
+        }
+
+        self.aliases
+            .lock()
+            .expect("lock poisoned")
+            .signal_paths(path, interface)
+    }
+
+    fn update_aliases<R>(&self, f: impl FnOnce(&mut Aliases) -> R) -> R {
+        let mut aliases = self.aliases.lock().expect("lock poisoned");
+        let res = f(&mut aliases);
+        self.has_aliases
+            .store(!aliases.is_empty(), Ordering::Release);
+
+        res
+    }
+
     /// Get the interface at the given path.
     ///
     /// # Errors
@@ -289,12 +655,120 @@ impl ObjectServer {
         })
     }
 
+    /// The paths of all the objects served.
+    ///
+    /// This includes the objects that were implicitly created as the parents of the objects that
+    /// interfaces were registered at. The paths are sorted.
+    pub async fn paths(&self) -> Vec<OwnedObjectPath> {
+        let root = self.root.read().await;
+        let mut paths: Vec<_> = root.descendants().map(|node| node.path().clone()).collect();
+        paths.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+
+        paths
+    }
+
+    /// The names of all the interfaces registered at the given path.
+    ///
+    /// This includes the standard interfaces (e.g `org.freedesktop.DBus.Properties`) that are
+    /// provided for every object. The names are sorted. If there is no object at the given path, an
+    /// empty list is returned.
+    pub async fn interface_names<'p, P>(&self, path: P) -> Result<Vec<InterfaceName<'static>>>
+    where
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+    {
+        let path = path.try_into().map_err(Into::into)?;
+        let root = self.root.read().await;
+        let Some(node) = root.get_child(&path) else {
+            return Ok(vec![]);
+        };
+        let mut names: Vec<_> = node.interfaces().map(|(name, _)| name.clone()).collect();
+        names.sort();
+
+        Ok(names)
+    }
+
+    /// Get a handle to the interface with the given name, at the given path.
+    ///
+    /// In contrast to [`ObjectServer::interface`], this doesn't require knowing the type of the
+    /// interface.
+    ///
+    /// # Errors
+    ///
+    /// If the interface is not registered at the given path, an `Error::InterfaceNotFound` error is
+    /// returned.
+    pub async fn interface_handle<'p, 'i, P, N>(&self, path: P, name: N) -> Result<InterfaceHandle>
+    where
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+        N: TryInto<InterfaceName<'i>>,
+        N::Error: Into<Error>,
+    {
+        let path = path.try_into().map_err(Into::into)?;
+        let name = name.try_into().map_err(Into::into)?;
+        let root = self.root().read().await;
+        let node = root.get_child(&path).ok_or(Error::InterfaceNotFound)?;
+        let lock = node
+            .interface_lock(name.as_ref())
+            .ok_or(Error::InterfaceNotFound)?
+            .instance;
+        let conn = self.connection();
+        // SAFETY: We know that there is a valid path on the node as we already converted w/o error.
+        let emitter = SignalEmitter::new(&conn, path).unwrap().into_owned();
+
+        Ok(InterfaceHandle {
+            name: name.into_owned(),
+            emitter,
+            lock,
+        })
+    }
+
+    /// Receive the changes to the registered interfaces.
+    ///
+    /// A [`RegistrationChange`] is yielded each time an interface is registered (e.g through
+    /// [`ObjectServer::at`]) or unregistered (e.g through [`ObjectServer::remove`]). The standard
+    /// interfaces, that are provided for every object, are not reported.
+    pub fn receive_registration_changes(&self) -> RegistrationChangeStream {
+        RegistrationChangeStream {
+            receiver: self.registration_changes_receiver.activate_cloned(),
+        }
+    }
+
+    fn notify_registration_change(&self, change: RegistrationChange) {
+        // Errors only mean that there are no active receivers or that the oldest change was
+        // dropped, both of which are fine.
+        let _ = self.registration_changes.try_broadcast(change);
+    }
+
     async fn dispatch_call_to_iface(
         &self,
         iface: Arc<RwLock<dyn Interface>>,
         connection: &Connection,
         msg: &Message,
         hdr: &Header<'_>,
+    ) -> fdo::Result<()> {
+        #[cfg(feature = "tower")]
+        {
+            let stack = self.dispatch_stack.read().expect("lock poisoned").clone();
+            if let Some(stack) = stack {
+                let req =
+                    crate::tower::DispatchRequest::new(msg.clone(), connection.clone(), iface);
+
+                return stack.dispatch(req).await;
+            }
+        }
+
+        self.dispatch_call_to_iface_direct(iface, connection, msg, hdr)
+            .await
+    }
+
+    /// Dispatch the call to the interface, bypassing the tower dispatch stack.
+    pub(crate) async fn dispatch_call_to_iface_direct(
+        &self,
+        iface: Arc<RwLock<dyn Interface>>,
+        connection: &Connection,
+        msg: &Message,
+        hdr: &Header<'_>,
     ) -> fdo::Result<()> {
         let member = hdr
             .member()
@@ -306,6 +780,59 @@ impl ObjectServer {
         trace!("acquiring read lock on interface `{}`", iface_name);
         let read_lock = iface.read().await;
         trace!("acquired read lock on interface `{}`", iface_name);
+        if read_lock.is_blocking_method(member) {
+            drop(read_lock);
+
+            return Self::dispatch_blocking_call_to_iface(
+                iface,
+                connection,
+                msg,
+                iface_name.to_owned(),
+                member.to_owned(),
+            )
+            .await;
+        }
+        self.call_iface(&iface, read_lock, connection, msg, iface_name, member)
+            .await
+    }
+
+    /// Dispatch a call to a blocking method, from a thread of the blocking thread pool.
+    async fn dispatch_blocking_call_to_iface(
+        iface: Arc<RwLock<dyn Interface>>,
+        connection: &Connection,
+        msg: &Message,
+        iface_name: InterfaceName<'static>,
+        member: MemberName<'static>,
+    ) -> fdo::Result<()> {
+        let task_name = format!("`{msg}` blocking method dispatcher");
+        let conn = connection.clone();
+        let msg = msg.clone();
+        let call = async move {
+            let server = conn.object_server();
+            let read_lock = iface.read().await;
+            server
+                .call_iface(&iface, read_lock, &conn, &msg, &iface_name, &member)
+                .await
+        }
+        .instrument(trace_span!("{}", task_name));
+
+        connection
+            .blocking_pool()
+            .block_on(call, &task_name)
+            .await
+            .map_err(|e| fdo::Error::Failed(format!("Failed to run blocking method: {e}")))?
+    }
+
+    /// Call the method on the interface, falling back to a mutable call if needed.
+    async fn call_iface(
+        &self,
+        iface: &RwLock<dyn Interface>,
+        read_lock: RwLockReadGuard<'_, dyn Interface>,
+        connection: &Connection,
+        msg: &Message,
+        iface_name: &InterfaceName<'_>,
+        member: &MemberName<'_>,
+    ) -> fdo::Result<()> {
         match read_lock.call(self, connection, msg, member.as_ref()) {
             DispatchResult::NotFound => {
                 return Err(fdo::Error::UnknownMethod(format!(
@@ -345,6 +872,7 @@ impl ObjectServer {
         connection: &Connection,
         msg: &Message,
         hdr: &Header<'_>,
+        activity: &mut Option<Activity>,
     ) -> fdo::Result<()> {
         let path = hdr
             .path()
@@ -367,7 +895,7 @@ impl ObjectServer {
 
         // Ensure the root lock isn't held while dispatching the message. That
         // way, the object server can be mutated during that time.
-        let (iface, with_spawn) = {
+        let (iface, with_spawn, sender_limits) = {
             let root = self.root.read().await;
             let node = root
                 .get_child(path)
@@ -376,21 +904,32 @@ impl ObjectServer {
             let iface = node.interface_lock(iface_name.as_ref()).ok_or_else(|| {
                 fdo::Error::UnknownInterface(format!("Unknown interface '{iface_name}'"))
             })?;
-            (iface.instance, iface.spawn_tasks_for_methods)
+            (
+                iface.instance,
+                iface.spawn_tasks_for_methods,
+                iface.sender_limits,
+            )
         };
 
+        let call = self
+            .dispatcher
+            .lock()
+            .expect("lock poisoned")
+            .schedule(hdr, sender_limits)?;
         if with_spawn {
             let executor = connection.executor().clone();
             let task_name = format!("`{msg}` method dispatcher");
             let connection = connection.clone();
             let msg = msg.clone();
+            let activity = activity.take();
             executor
                 .spawn(
                     async move {
+                        let _activity = activity;
                         let server = connection.object_server();
                         let hdr = msg.header();
-                        if let Err(e) = server
-                            .dispatch_call_to_iface(iface, &connection, &msg, &hdr)
+                        if let Err(e) = call
+                            .run(server.dispatch_call_to_iface(iface, &connection, &msg, &hdr))
                             .await
                         {
                             // When not spawning a task, this error is handled by the caller.
@@ -409,7 +948,7 @@ impl ObjectServer {
                 .detach();
             Ok(())
         } else {
-            self.dispatch_call_to_iface(iface, connection, msg, hdr)
+            call.run(self.dispatch_call_to_iface(iface, connection, msg, hdr))
                 .await
         }
     }
@@ -429,8 +968,13 @@ impl ObjectServer {
     #[instrument(skip(self))]
     pub(crate) async fn dispatch_call(&self, msg: &Message, hdr: &Header<'_>) -> Result<()> {
         let conn = self.connection();
+        // The call is in flight until replied to, even if that happens from another task.
+        let mut activity = Some(self.idle.start(ActivityKind::Call));
 
-        if let Err(e) = self.dispatch_method_call_try(&conn, msg, hdr).await {
+        if let Err(e) = self
+            .dispatch_method_call_try(&conn, msg, hdr, &mut activity)
+            .await
+        {
             debug!("Returning error: {}", e);
             conn.reply_dbus_error(hdr, e).await?;
         }
@@ -452,3 +996,666 @@ impl From<crate::blocking::ObjectServer> for ObjectServer {
         server.into_inner()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use futures_util::StreamExt;
+    use ntest::timeout;
+    use test_log::test;
+    use zvariant::OwnedValue;
+
+    use super::*;
+    use crate::{interface, MessageStream};
+
+    struct TestObj {
+        value: u32,
+    }
+
+    #[interface(name = "org.zbus.TestObj")]
+    impl TestObj {
+        #[zbus(property)]
+        fn value(&self) -> u32 {
+            self.value
+        }
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn enumeration() {
+        crate::block_on(enumeration_async());
+    }
+
+    async fn enumeration_async() {
+        let conn = Connection::session().await.unwrap();
+        let server = conn.object_server();
+        let mut changes = server.receive_registration_changes();
+
+        let obj = "/org/zbus/Enumeration/Obj";
+        assert!(server.at(obj, TestObj { value: 1 }).await.unwrap());
+        assert_eq!(
+            changes.next().await.unwrap(),
+            RegistrationChange::Added {
+                path: obj.try_into().unwrap(),
+                interface: "org.zbus.TestObj".try_into().unwrap(),
+            }
+        );
+
+        let paths = server.paths().await;
+        for path in ["/", "/org/zbus/Enumeration", obj] {
+            assert!(paths.iter().any(|p| p.as_str() == path), "{path} missing");
+        }
+        assert_eq!(
+            server.interface_names(obj).await.unwrap(),
+            [
+                "org.freedesktop.DBus.Introspectable",
+                "org.freedesktop.DBus.Peer",
+                "org.freedesktop.DBus.Properties",
+                "org.zbus.TestObj",
+            ]
+        );
+        assert!(server
+            .interface_names("/org/zbus/Unknown")
+            .await
+            .unwrap()
+            .is_empty());
+
+        let handle = server
+            .interface_handle(obj, "org.zbus.TestObj")
+            .await
+            .unwrap();
+        assert_eq!(handle.name(), "org.zbus.TestObj");
+        assert_eq!(handle.path(), obj);
+        assert!(handle
+            .introspect()
+            .await
+            .contains("<property name=\"Value\""));
+        assert_eq!(
+            handle.get_property("Value").await.unwrap().unwrap(),
+            OwnedValue::from(1u32)
+        );
+        assert!(handle.get_property("Unknown").await.is_none());
+        assert!(handle.downcast::<crate::fdo::Peer>().await.is_none());
+        let iface_ref = handle.downcast::<TestObj>().await.unwrap();
+        iface_ref.get_mut().await.value = 2;
+        assert_eq!(
+            handle.get_all_properties().await.unwrap(),
+            HashMap::from([("Value".to_string(), OwnedValue::from(2u32))])
+        );
+        assert!(matches!(
+            server
+                .interface_handle(obj, "org.zbus.Unknown")
+                .await
+                .unwrap_err(),
+            Error::InterfaceNotFound
+        ));
+
+        assert!(server.remove::<TestObj, _>(obj).await.unwrap());
+        assert_eq!(
+            changes.next().await.unwrap(),
+            RegistrationChange::Removed {
+                path: obj.try_into().unwrap(),
+                interface: "org.zbus.TestObj".try_into().unwrap(),
+            }
+        );
+        assert!(!server.paths().await.iter().any(|p| p.as_str() == obj));
+    }
+
+    #[cfg(feature = "blocking-api")]
+    #[test]
+    #[timeout(15000)]
+    fn blocking_enumeration() {
+        let conn = crate::blocking::Connection::session().unwrap();
+        let server = conn.object_server();
+        let mut changes = server.receive_registration_changes();
+
+        let obj = "/org/zbus/BlockingEnumeration/Obj";
+        assert!(server.at(obj, TestObj { value: 1 }).unwrap());
+        assert_eq!(
+            changes.next().unwrap(),
+            RegistrationChange::Added {
+                path: obj.try_into().unwrap(),
+                interface: "org.zbus.TestObj".try_into().unwrap(),
+            }
+        );
+
+        let handle = server.interface_handle(obj, "org.zbus.TestObj").unwrap();
+        assert_eq!(handle.path(), obj);
+        assert_eq!(
+            handle.get_property("Value").unwrap().unwrap(),
+            OwnedValue::from(1u32)
+        );
+        handle.downcast::<TestObj>().unwrap().get_mut().value = 2;
+        assert_eq!(
+            handle.get_all_properties().unwrap(),
+            HashMap::from([("Value".to_string(), OwnedValue::from(2u32))])
+        );
+
+        assert!(server.remove::<TestObj, _>(obj).unwrap());
+        assert_eq!(
+            changes.next().unwrap(),
+            RegistrationChange::Removed {
+                path: obj.try_into().unwrap(),
+                interface: "org.zbus.TestObj".try_into().unwrap(),
+            }
+        );
+        assert!(server.interface_names(obj).unwrap().is_empty());
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn aliases() {
+        crate::block_on(aliases_async());
+    }
+
+    async fn next_signal_path(stream: &mut MessageStream) -> String {
+        let msg = stream.next().await.unwrap().unwrap();
+
+        msg.header().path().unwrap().to_string()
+    }
+
+    async fn aliases_async() {
+        use crate::{match_rule::MatchRule, message::Type};
+
+        let conn = Connection::session().await.unwrap();
+        let server = conn.object_server();
+        let canonical = "/org/zbus/Aliases/Canonical";
+        let alias = "/org/zbus/Aliases/Default";
+        server.at(canonical, TestObj { value: 1 }).await.unwrap();
+        let iface_ref = server.interface::<_, TestObj>(canonical).await.unwrap();
+        assert!(server.alias(alias, &iface_ref).await.unwrap());
+        assert!(!server.alias(alias, &iface_ref).await.unwrap());
+
+        // The state is shared.
+        iface_ref.get_mut().await.value = 2;
+        let alias_ref = server.interface::<_, TestObj>(alias).await.unwrap();
+        assert_eq!(alias_ref.get().await.value, 2);
+
+        let rule = MatchRule::builder()
+            .msg_type(Type::Signal)
+            .sender(conn.unique_name().unwrap())
+            .unwrap()
+            .path_namespace("/org/zbus/Aliases")
+            .unwrap()
+            .interface("org.freedesktop.DBus.Properties")
+            .unwrap()
+            .member("PropertiesChanged")
+            .unwrap()
+            .build();
+        let mut stream = MessageStream::for_match_rule(rule, &conn, None)
+            .await
+            .unwrap();
+        // Property changes are emitted at all the paths, whichever path they're emitted for.
+        alias_ref
+            .get()
+            .await
+            .value_changed(alias_ref.signal_emitter())
+            .await
+            .unwrap();
+        let mut paths = vec![
+            next_signal_path(&mut stream).await,
+            next_signal_path(&mut stream).await,
+        ];
+        paths.sort();
+        assert_eq!(paths, [canonical, alias]);
+
+        // Restrict the emission to the alias.
+        server
+            .set_signal_emission::<_, TestObj>(canonical, false)
+            .await
+            .unwrap();
+        iface_ref
+            .get()
+            .await
+            .value_changed(iface_ref.signal_emitter())
+            .await
+            .unwrap();
+        assert_eq!(next_signal_path(&mut stream).await, alias);
+
+        // Removing the interface from one path doesn't affect the other.
+        server.remove::<TestObj, _>(alias).await.unwrap();
+        assert_eq!(
+            server
+                .interface::<_, TestObj>(canonical)
+                .await
+                .unwrap()
+                .get()
+                .await
+                .value,
+            2
+        );
+        assert!(matches!(
+            server.set_signal_emission::<_, TestObj>(alias, true).await,
+            Err(Error::InterfaceNotFound)
+        ));
+
+        // Signals are emitted without looking up the aliases, once there are none left.
+        assert!(server.has_aliases.load(Ordering::Acquire));
+        server.remove::<TestObj, _>(canonical).await.unwrap();
+        assert!(!server.has_aliases.load(Ordering::Acquire));
+    }
+
+    struct Rendezvous {
+        barrier: std::sync::Barrier,
+    }
+
+    #[interface(name = "org.zbus.Rendezvous", blocking)]
+    impl Rendezvous {
+        fn wait(&self) {
+            self.barrier.wait();
+        }
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn blocking_methods() {
+        crate::block_on(blocking_methods_async());
+    }
+
+    async fn blocking_methods_async() {
+        let rendezvous = Rendezvous {
+            barrier: std::sync::Barrier::new(2),
+        };
+        let service = crate::connection::Builder::session()
+            .unwrap()
+            .serve_at("/org/zbus/Rendezvous", rendezvous)
+            .unwrap()
+            .blocking_pool_size(2)
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(service.blocking_pool_size(), 2);
+
+        let client = Connection::session().await.unwrap();
+        let proxy = crate::Proxy::new(
+            &client,
+            service.unique_name().unwrap().to_owned(),
+            "/org/zbus/Rendezvous",
+            "org.zbus.Rendezvous",
+        )
+        .await
+        .unwrap();
+
+        // Each call blocks its thread until the other one is made, which would stall the runtime if
+        // the calls weren't run on the blocking thread pool.
+        let (first, second) = futures_util::future::join(
+            proxy.call_method("Wait", &()),
+            proxy.call_method("Wait", &()),
+        )
+        .await;
+        first.unwrap();
+        second.unwrap();
+    }
+
+    async fn sleep(delay: std::time::Duration) {
+        #[cfg(not(feature = "tokio"))]
+        async_io::Timer::after(delay).await;
+        #[cfg(feature = "tokio")]
+        tokio::time::sleep(delay).await;
+    }
+
+    #[derive(Default)]
+    struct Journal {
+        entries: Arc<std::sync::Mutex<Vec<u32>>>,
+    }
+
+    #[interface(name = "org.zbus.Journal")]
+    impl Journal {
+        async fn record(&self, entry: u32) {
+            // The earlier entries take longer to record, so they'd be recorded last if the calls
+            // were run concurrently.
+            sleep(std::time::Duration::from_millis(u64::from(5 - entry) * 20)).await;
+
+            self.entries.lock().unwrap().push(entry);
+        }
+
+        fn entries(&self) -> Vec<u32> {
+            self.entries.lock().unwrap().clone()
+        }
+    }
+
+    struct JournalReader {
+        entries: Arc<std::sync::Mutex<Vec<u32>>>,
+    }
+
+    #[interface(name = "org.zbus.JournalReader", spawn = false)]
+    impl JournalReader {
+        fn entries(&self) -> Vec<u32> {
+            self.entries.lock().unwrap().clone()
+        }
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn dispatch_policy() {
+        crate::block_on(dispatch_policy_async());
+    }
+
+    async fn dispatch_policy_async() {
+        let policy = DispatchPolicy::new()
+            .ordering(CallOrdering::PerObject)
+            .max_workers(2);
+        let journal = Journal::default();
+        let reader = JournalReader {
+            entries: journal.entries.clone(),
+        };
+        let service = crate::connection::Builder::session()
+            .unwrap()
+            .serve_at("/org/zbus/Journal", journal)
+            .unwrap()
+            .serve_at("/org/zbus/Journal", reader)
+            .unwrap()
+            .dispatch_policy(policy)
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(service.object_server().dispatch_policy(), policy);
+        let service_name = service.unique_name().unwrap().to_owned();
+
+        let client = Connection::session().await.unwrap();
+        for entry in 0..5u32 {
+            let msg = Message::method_call("/org/zbus/Journal", "Record")
+                .unwrap()
+                .destination(&service_name)
+                .unwrap()
+                .interface("org.zbus.Journal")
+                .unwrap()
+                .build(&entry)
+                .unwrap();
+            client.send(&msg).await.unwrap();
+        }
+        // Ordered after all the `Record` calls, even though it's not run from its own task.
+        let entries: Vec<u32> = client
+            .call_method(
+                Some(&service_name),
+                "/org/zbus/Journal",
+                Some("org.zbus.JournalReader"),
+                "Entries",
+                &(),
+            )
+            .await
+            .unwrap()
+            .body()
+            .deserialize()
+            .unwrap();
+        assert_eq!(entries, [0, 1, 2, 3, 4]);
+    }
+
+    struct Limited;
+
+    #[interface(name = "org.zbus.Limited", limits(max_concurrent_calls = 1))]
+    impl Limited {
+        async fn hold(&self) {
+            sleep(std::time::Duration::from_millis(500)).await;
+        }
+
+        fn ping(&self) {}
+    }
+
+    struct LimitedSequential;
+
+    #[interface(
+        name = "org.zbus.LimitedSequential",
+        spawn = false,
+        limits(max_calls_per_second = 1)
+    )]
+    impl LimitedSequential {
+        fn ping(&self) {}
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn sender_limits() {
+        crate::block_on(sender_limits_async());
+    }
+
+    async fn sender_limits_async() {
+        let policy =
+            DispatchPolicy::new().sender_limits(SenderLimits::new().max_calls_per_second(2));
+        let service = crate::connection::Builder::session()
+            .unwrap()
+            .serve_at("/org/zbus/Journal", Journal::default())
+            .unwrap()
+            .serve_at("/org/zbus/Limited", Limited)
+            .unwrap()
+            .serve_at("/org/zbus/Limited", LimitedSequential)
+            .unwrap()
+            .dispatch_policy(policy)
+            .build()
+            .await
+            .unwrap();
+        let service_name = service.unique_name().unwrap().to_owned();
+        let client = Connection::session().await.unwrap();
+        let call = |path, iface, method| {
+            client.call_method(Some(&service_name), path, Some(iface), method, &())
+        };
+        let assert_limits_exceeded = |res: Result<Message>| match res {
+            Err(Error::MethodError(name, _, _)) => {
+                assert_eq!(name, "org.freedesktop.DBus.Error.LimitsExceeded")
+            }
+            res => panic!("unexpected result: {res:?}"),
+        };
+
+        // The global limits.
+        for _ in 0..2 {
+            call("/org/zbus/Journal", "org.zbus.Journal", "Entries")
+                .await
+                .unwrap();
+        }
+        assert_limits_exceeded(call("/org/zbus/Journal", "org.zbus.Journal", "Entries").await);
+
+        // The limits of the interface override the global ones.
+        let hold = Message::method_call("/org/zbus/Limited", "Hold")
+            .unwrap()
+            .destination(&service_name)
+            .unwrap()
+            .interface("org.zbus.Limited")
+            .unwrap()
+            .build(&())
+            .unwrap();
+        client.send(&hold).await.unwrap();
+        assert_limits_exceeded(call("/org/zbus/Limited", "org.zbus.Limited", "Ping").await);
+
+        // The limits also apply to the interfaces not spawning tasks for their calls.
+        call("/org/zbus/Limited", "org.zbus.LimitedSequential", "Ping")
+            .await
+            .unwrap();
+        assert_limits_exceeded(
+            call("/org/zbus/Limited", "org.zbus.LimitedSequential", "Ping").await,
+        );
+    }
+
+    struct Caller(String);
+
+    impl FromMethodCall for Caller {
+        async fn from_method_call(call: &CallContext<'_>) -> fdo::Result<Self> {
+            Ok(Caller(call.sender().unwrap().to_string()))
+        }
+    }
+
+    struct Denied;
+
+    impl FromMethodCall for Denied {
+        async fn from_method_call(_call: &CallContext<'_>) -> fdo::Result<Self> {
+            Err(fdo::Error::AccessDenied("Denied".into()))
+        }
+    }
+
+    #[derive(Default)]
+    struct Guarded {
+        called: std::sync::atomic::AtomicBool,
+    }
+
+    #[interface(name = "org.zbus.Guarded")]
+    impl Guarded {
+        fn caller(&self, #[zbus(extract)] caller: Caller) -> String {
+            caller.0
+        }
+
+        fn denied(&self, #[zbus(extract)] _denied: Denied, _arg: u32) {
+            self.called.store(true, std::sync::atomic::Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn extractors() {
+        crate::block_on(extractors_async());
+    }
+
+    async fn extractors_async() {
+        let service = crate::connection::Builder::session()
+            .unwrap()
+            .serve_at("/org/zbus/Guarded", Guarded::default())
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+        let client = Connection::session().await.unwrap();
+        let proxy = crate::Proxy::new(
+            &client,
+            service.unique_name().unwrap().to_owned(),
+            "/org/zbus/Guarded",
+            "org.zbus.Guarded",
+        )
+        .await
+        .unwrap();
+
+        let caller: String = proxy.call("Caller", &()).await.unwrap();
+        assert_eq!(caller, client.unique_name().unwrap().as_str());
+
+        match proxy.call::<_, _, ()>("Denied", &(1u32,)).await {
+            Err(Error::MethodError(name, _, _)) => {
+                assert_eq!(name, "org.freedesktop.DBus.Error.AccessDenied")
+            }
+            res => panic!("unexpected result: {res:?}"),
+        }
+        let guarded = service
+            .object_server()
+            .interface::<_, Guarded>("/org/zbus/Guarded")
+            .await
+            .unwrap();
+        assert!(!guarded
+            .get()
+            .await
+            .called
+            .load(std::sync::atomic::Ordering::SeqCst));
+
+        // Extracted arguments aren't part of the method signature.
+        let xml = service
+            .object_server()
+            .interface_handle("/org/zbus/Guarded", "org.zbus.Guarded")
+            .await
+            .unwrap()
+            .introspect()
+            .await;
+        assert_eq!(xml.matches(r#"direction="in""#).count(), 1, "{xml}");
+        assert!(!xml.contains("_denied"), "{xml}");
+    }
+
+    struct Sessions {
+        tracker: IdleTracker,
+        next: u32,
+    }
+
+    #[interface(name = "org.zbus.Sessions")]
+    impl Sessions {
+        async fn create(
+            &mut self,
+            #[zbus(header)] hdr: Header<'_>,
+            #[zbus(object_server)] server: &ObjectServer,
+        ) -> fdo::Result<OwnedObjectPath> {
+            let path: OwnedObjectPath = format!("/org/zbus/Sessions/{}", self.next)
+                .try_into()
+                .unwrap();
+            self.next += 1;
+            server.at(&path, TestObj { value: 0 }).await?;
+            server
+                .bind_to_client(&path, hdr.sender().unwrap().clone())
+                .await?;
+
+            Ok(path)
+        }
+
+        fn in_flight_calls(&self) -> u32 {
+            self.tracker.in_flight_calls() as u32
+        }
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn idle_tracker() {
+        crate::block_on(idle_tracker_async());
+    }
+
+    async fn idle_tracker_async() {
+        use crate::timeout::timeout;
+
+        let service = crate::connection::Builder::session()
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+        let server = service.object_server();
+        let tracker = server.idle_tracker(Duration::from_millis(100));
+        server
+            .at(
+                "/org/zbus/Sessions",
+                Sessions {
+                    tracker: tracker.clone(),
+                    next: 0,
+                },
+            )
+            .await
+            .unwrap();
+        assert!(tracker.is_idle());
+        assert!(matches!(
+            server.bind_to_client("/org/zbus/Nothing", ":1.0").await,
+            Err(Error::InterfaceNotFound)
+        ));
+
+        let client = Connection::session().await.unwrap();
+        let proxy = crate::Proxy::new(
+            &client,
+            service.unique_name().unwrap().to_owned(),
+            "/org/zbus/Sessions",
+            "org.zbus.Sessions",
+        )
+        .await
+        .unwrap();
+        let in_flight: u32 = proxy.call("InFlightCalls", &()).await.unwrap();
+        assert_eq!(in_flight, 1);
+        let session: OwnedObjectPath = proxy.call("Create", &()).await.unwrap();
+        // The call is only over once its reply is sent.
+        while tracker.in_flight_calls() != 0 {
+            crate::timeout::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(tracker.client_objects(), 1);
+        assert!(!tracker.is_idle());
+
+        let ownership = service
+            .own_name("org.zbus.IdleTracker", Default::default())
+            .await
+            .unwrap();
+        assert_eq!(tracker.owned_names(), 1);
+        drop(ownership);
+        assert_eq!(tracker.owned_names(), 0);
+        // Still busy with the session.
+        assert!(timeout(
+            async {
+                tracker.idle().await;
+
+                Ok(())
+            },
+            Duration::from_millis(300),
+        )
+        .await
+        .is_err());
+
+        // The session goes away with its client.
+        drop(proxy);
+        drop(client);
+        tracker.idle().await;
+        assert_eq!(tracker.client_objects(), 0);
+        assert!(!server.paths().await.contains(&session));
+    }
+}
@@ -92,6 +92,27 @@ impl Node {
         (Some(node), obj_manager_path)
     }
 
+    pub(super) fn path(&self) -> &OwnedObjectPath {
+        &self.path
+    }
+
+    /// This node and all its descendants.
+    pub(super) fn descendants(&self) -> impl Iterator<Item = &Node> {
+        let mut node_list = vec![self];
+        std::iter::from_fn(move || {
+            let node = node_list.pop()?;
+            node_list.extend(node.children.values());
+
+            Some(node)
+        })
+    }
+
+    pub(super) fn interfaces(
+        &self,
+    ) -> impl Iterator<Item = (&InterfaceName<'static>, &ArcInterface)> {
+        self.interfaces.iter()
+    }
+
     pub(crate) fn interface_lock(&self, interface_name: InterfaceName<'_>) -> Option<ArcInterface> {
         self.interfaces.get(&interface_name).cloned()
     }
@@ -101,11 +122,16 @@ impl Node {
     }
 
     pub(super) fn is_empty(&self) -> bool {
-        !self.interfaces.keys().any(|k| {
-            *k != Peer::name()
-                && *k != Introspectable::name()
-                && *k != Properties::name()
-                && *k != ObjectManager::name()
+        self.user_interface_names().next().is_none()
+    }
+
+    /// The names of the interfaces, except the standard ones provided for every object.
+    pub(super) fn user_interface_names(&self) -> impl Iterator<Item = &InterfaceName<'static>> {
+        self.interfaces.keys().filter(|k| {
+            **k != Peer::name()
+                && **k != Introspectable::name()
+                && **k != Properties::name()
+                && **k != ObjectManager::name()
         })
     }
 
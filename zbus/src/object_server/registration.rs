@@ -0,0 +1,69 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use async_broadcast::Receiver;
+use futures_core::stream;
+use zbus_names::OwnedInterfaceName;
+use zvariant::OwnedObjectPath;
+
+/// A change in the interfaces registered on an [`ObjectServer`](crate::ObjectServer).
+///
+/// Use [`ObjectServer::receive_registration_changes`] to receive these.
+///
+/// [`ObjectServer::receive_registration_changes`]: crate::ObjectServer::receive_registration_changes
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RegistrationChange {
+    /// An interface was registered.
+    Added {
+        /// The object path the interface was registered at.
+        path: OwnedObjectPath,
+        /// The name of the interface.
+        interface: OwnedInterfaceName,
+    },
+    /// An interface was unregistered.
+    Removed {
+        /// The object path the interface was registered at.
+        path: OwnedObjectPath,
+        /// The name of the interface.
+        interface: OwnedInterfaceName,
+    },
+}
+
+impl RegistrationChange {
+    /// The object path of the affected interface.
+    pub fn path(&self) -> &OwnedObjectPath {
+        match self {
+            Self::Added { path, .. } | Self::Removed { path, .. } => path,
+        }
+    }
+
+    /// The name of the affected interface.
+    pub fn interface(&self) -> &OwnedInterfaceName {
+        match self {
+            Self::Added { interface, .. } | Self::Removed { interface, .. } => interface,
+        }
+    }
+}
+
+/// A [`stream::Stream`] of [`RegistrationChange`]s.
+///
+/// Use [`ObjectServer::receive_registration_changes`] to create an instance of this type. Only the
+/// changes made after the creation of the stream are yielded. If the stream is not polled
+/// regularly, the oldest changes are dropped.
+///
+/// [`ObjectServer::receive_registration_changes`]: crate::ObjectServer::receive_registration_changes
+#[derive(Debug)]
+pub struct RegistrationChangeStream {
+    pub(super) receiver: Receiver<RegistrationChange>,
+}
+
+impl stream::Stream for RegistrationChangeStream {
+    type Item = RegistrationChange;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        stream::Stream::poll_next(Pin::new(&mut self.get_mut().receiver), cx)
+    }
+}
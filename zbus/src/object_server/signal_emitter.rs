@@ -49,15 +49,39 @@ impl<'s> SignalEmitter<'s> {
         M::Error: Into<Error>,
         B: serde::ser::Serialize + zvariant::DynamicType,
     {
-        self.conn
-            .emit_signal(
-                self.destination.as_ref(),
-                &self.path,
-                interface,
-                signal_name,
-                body,
-            )
-            .await
+        let interface = interface.try_into().map_err(Into::into)?;
+        // Interfaces registered at multiple paths emit their signals at all of them.
+        let paths = self
+            .conn
+            .existing_object_server()
+            .and_then(|server| server.signal_paths(&self.path, &interface));
+        let Some(paths) = paths else {
+            return self
+                .conn
+                .emit_signal(
+                    self.destination.as_ref(),
+                    &self.path,
+                    interface,
+                    signal_name,
+                    body,
+                )
+                .await;
+        };
+
+        let signal_name = signal_name.try_into().map_err(Into::into)?;
+        for path in paths {
+            self.conn
+                .emit_signal(
+                    self.destination.as_ref(),
+                    &path,
+                    &interface,
+                    &signal_name,
+                    body,
+                )
+                .await?;
+        }
+
+        Ok(())
     }
 
     /// Set the destination for the signal emission.
@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+
+use zvariant::{OwnedValue, Value};
+
+use crate::{fdo::PropertiesChanged, Result};
+
+/// A typed snapshot of the properties of a D-Bus interface.
+///
+/// Instead of reading the properties of an interface one by one, a type implementing this trait
+/// can be fetched in a single round trip with [`crate::Proxy::get_all_as`], and then kept up to
+/// date from the `PropertiesChanged` signal with [`Properties::update_from_signal`].
+///
+/// The easiest way to implement this trait is to make use of the [`Properties` macro][pm], which
+/// maps the fields of a struct to the properties of the same name (in `PascalCase`).
+///
+/// # Example
+///
+/// ```no_run
+/// # use zbus::{Connection, Proxy, Properties, Result};
+/// # use futures_util::StreamExt;
+/// #
+/// #[derive(Debug, Properties)]
+/// #[zbus(interface = "org.freedesktop.login1.Manager")]
+/// struct Login {
+///     idle_hint: bool,
+///     #[zbus(rename = "BlockInhibited")]
+///     blocked: String,
+///     #[zbus(default)]
+///     n_auto_v_ts: u32,
+/// }
+///
+/// # async fn example() -> Result<()> {
+/// let conn = Connection::system().await?;
+/// let proxy = Proxy::new(
+///     &conn,
+///     "org.freedesktop.login1",
+///     "/org/freedesktop/login1",
+///     "org.freedesktop.login1.Manager",
+/// )
+/// .await?;
+///
+/// let mut login: Login = proxy.get_all_as().await?;
+/// let mut changes = zbus::fdo::PropertiesProxy::builder(&conn)
+///     .destination("org.freedesktop.login1")?
+///     .path("/org/freedesktop/login1")?
+///     .build()
+///     .await?
+///     .receive_properties_changed()
+///     .await?;
+/// while let Some(signal) = changes.next().await {
+///     login.update_from_signal(&signal)?;
+///     println!("{login:?}");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [pm]: derive.Properties.html
+pub trait Properties: Sized {
+    /// The name of the interface the properties belong to, if known.
+    ///
+    /// If set, [`Properties::update_from_signal`] ignores the signals for other interfaces.
+    const INTERFACE: Option<&'static str>;
+
+    /// Create an instance from the values of the properties, as returned by the `GetAll` method of
+    /// the `org.freedesktop.DBus.Properties` interface.
+    fn from_properties(properties: HashMap<String, OwnedValue>) -> Result<Self>;
+
+    /// Update `self` with the values of the `changed` properties.
+    ///
+    /// Properties that are not part of `Self` are ignored.
+    fn update_properties(&mut self, changed: &HashMap<&str, Value<'_>>) -> Result<()>;
+
+    /// Update `self` from a `PropertiesChanged` signal.
+    ///
+    /// Returns whether the signal was for the interface of `Self`. Since the signal doesn't carry
+    /// the new values of invalidated properties, those are left untouched.
+    fn update_from_signal(&mut self, signal: &PropertiesChanged) -> Result<bool> {
+        let args = signal.args()?;
+        if Self::INTERFACE.is_some_and(|iface| iface != args.interface_name().as_str()) {
+            return Ok(false);
+        }
+        self.update_properties(args.changed_properties())?;
+
+        Ok(true)
+    }
+}
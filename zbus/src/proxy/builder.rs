@@ -1,9 +1,17 @@
-use std::{collections::HashSet, marker::PhantomData, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    marker::PhantomData,
+    sync::Arc,
+    time::Duration,
+};
 
 use zbus_names::{BusName, InterfaceName};
 use zvariant::{ObjectPath, Str};
 
-use crate::{proxy::ProxyInner, Connection, Error, Proxy, Result};
+use crate::{
+    proxy::{PropertiesCache, ProxyInner, RetryPolicy},
+    Connection, Error, Proxy, Result,
+};
 
 /// The properties caching mode.
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
@@ -19,6 +27,92 @@ pub enum CacheProperties {
     Lazily,
 }
 
+/// The policy for keeping a cached property up to date.
+///
+/// Set with [`Builder::cache_policy`] for all the properties of a proxy, or with
+/// [`Builder::property_cache_policy`] for individual properties.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PropertyCachePolicy {
+    /// Rely on the `PropertiesChanged` signal to keep the value up to date (default).
+    ///
+    /// Once invalidated, the property is fetched from the peer on each read, until the signal
+    /// carries a new value.
+    #[default]
+    Signals,
+    /// Same as [`PropertyCachePolicy::Signals`], but the value fetched on the first read after an
+    /// invalidation is cached.
+    RefreshOnRead,
+    /// Cached values expire after the given duration.
+    ///
+    /// Reading an expired or invalidated property fetches its value from the peer and caches it.
+    Ttl(Duration),
+    /// Fetch the value from the peer periodically, at the given interval.
+    ///
+    /// This is meant for properties that don't emit the `PropertiesChanged` signal. Reading an
+    /// invalidated property fetches its value from the peer and caches it.
+    Poll(Duration),
+}
+
+impl PropertyCachePolicy {
+    pub(crate) fn refreshes_on_read(self) -> bool {
+        self != PropertyCachePolicy::Signals
+    }
+
+    pub(crate) fn ttl(self) -> Option<Duration> {
+        match self {
+            PropertyCachePolicy::Ttl(ttl) => Some(ttl),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn poll_interval(self) -> Option<Duration> {
+        match self {
+            PropertyCachePolicy::Poll(interval) => Some(interval),
+            _ => None,
+        }
+    }
+}
+
+/// The cache policies of the properties of a proxy.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct CachePolicies {
+    default: PropertyCachePolicy,
+    properties: HashMap<String, PropertyCachePolicy>,
+}
+
+impl CachePolicies {
+    /// The policy for `property`.
+    pub(crate) fn get(&self, property: &str) -> PropertyCachePolicy {
+        self.properties
+            .get(property)
+            .copied()
+            .unwrap_or(self.default)
+    }
+
+    /// Whether `property` was given its own policy.
+    pub(crate) fn is_explicit(&self, property: &str) -> bool {
+        self.properties.contains_key(property)
+    }
+
+    /// The shortest interval at which any property is polled, if any.
+    pub(crate) fn min_poll_interval(&self) -> Option<Duration> {
+        self.properties
+            .values()
+            .chain(Some(&self.default))
+            .filter_map(|p| p.poll_interval())
+            .min()
+    }
+
+    /// The properties given their own polling policy.
+    pub(crate) fn polled_properties(&self) -> impl Iterator<Item = &str> {
+        self.properties
+            .iter()
+            .filter(|(_, p)| p.poll_interval().is_some())
+            .map(|(name, _)| name.as_str())
+    }
+}
+
 /// Builder for proxies.
 #[derive(Debug)]
 pub struct Builder<'a, T = ()> {
@@ -29,6 +123,9 @@ pub struct Builder<'a, T = ()> {
     proxy_type: PhantomData<T>,
     cache: CacheProperties,
     uncached_properties: Option<HashSet<Str<'a>>>,
+    populated_cache: Option<Arc<PropertiesCache>>,
+    retry_policy: Option<RetryPolicy>,
+    cache_policies: CachePolicies,
 }
 
 impl<T> Clone for Builder<'_, T> {
@@ -40,6 +137,9 @@ impl<T> Clone for Builder<'_, T> {
             interface: self.interface.clone(),
             cache: self.cache,
             uncached_properties: self.uncached_properties.clone(),
+            populated_cache: self.populated_cache.clone(),
+            retry_policy: self.retry_policy.clone(),
+            cache_policies: self.cache_policies.clone(),
             proxy_type: PhantomData,
         }
     }
@@ -92,6 +192,49 @@ impl<'a, T> Builder<'a, T> {
         self
     }
 
+    /// Set the cache policy of all the properties.
+    ///
+    /// This doesn't apply to the properties excluded from caching, e.g. through
+    /// [`Builder::uncached_properties`].
+    #[must_use]
+    pub fn cache_policy(mut self, policy: PropertyCachePolicy) -> Self {
+        self.cache_policies.default = policy;
+        self
+    }
+
+    /// Set the cache policy of the `property` property.
+    ///
+    /// This overrides the policy set with [`Builder::cache_policy`]. The property is cached even
+    /// if it was excluded from caching, which is useful for properties that don't emit the
+    /// `PropertiesChanged` signal, along with [`PropertyCachePolicy::Ttl`] or
+    /// [`PropertyCachePolicy::Poll`].
+    #[must_use]
+    pub fn property_cache_policy(mut self, property: &str, policy: PropertyCachePolicy) -> Self {
+        self.cache_policies
+            .properties
+            .insert(property.to_string(), policy);
+        self
+    }
+
+    /// Set the policy for retrying method calls that fail with a transient error.
+    ///
+    /// By default, method calls are not retried. See [`RetryPolicy`] for details.
+    #[must_use]
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
+
+    /// Use an already populated properties cache, instead of fetching the properties from the
+    /// peer.
+    ///
+    /// The owner of the cache is responsible for keeping it up to date.
+    #[must_use]
+    pub(crate) fn populated_cache(mut self, cache: Arc<PropertiesCache>) -> Self {
+        self.populated_cache = Some(cache);
+        self
+    }
+
     pub(crate) fn build_internal(self) -> Result<Proxy<'a>> {
         let conn = self.conn;
         let destination = self
@@ -100,7 +243,9 @@ impl<'a, T> Builder<'a, T> {
         let path = self.path.ok_or(Error::MissingParameter("path"))?;
         let interface = self.interface.ok_or(Error::MissingParameter("interface"))?;
         let cache = self.cache;
-        let uncached_properties = self.uncached_properties.unwrap_or_default();
+        let cache_policies = self.cache_policies;
+        let mut uncached_properties = self.uncached_properties.unwrap_or_default();
+        uncached_properties.retain(|p| !cache_policies.is_explicit(p));
 
         Ok(Proxy {
             inner: Arc::new(ProxyInner::new(
@@ -110,6 +255,9 @@ impl<'a, T> Builder<'a, T> {
                 interface,
                 cache,
                 uncached_properties,
+                self.populated_cache,
+                self.retry_policy,
+                cache_policies,
             )),
         })
     }
@@ -153,6 +301,9 @@ where
             interface: T::INTERFACE.clone(),
             cache: CacheProperties::default(),
             uncached_properties: None,
+            populated_cache: None,
+            retry_policy: None,
+            cache_policies: CachePolicies::default(),
             proxy_type: PhantomData,
         }
     }
@@ -0,0 +1,536 @@
+use std::sync::Arc;
+
+use zbus_names::{BusName, InterfaceName, MemberName};
+use zbus_xml::{ArgDirection, Interface, Method, Node, Property, Signal};
+use zvariant::{
+    Array, Dict, ObjectPath, OwnedValue, Signature, Structure, StructureBuilder, Value,
+};
+
+use super::{Proxy, SignalStream};
+use crate::{
+    fdo,
+    message::{Body, Message},
+    Connection, Error, Result,
+};
+
+/// A proxy for an interface only known at runtime.
+///
+/// `DynamicProxy` introspects the remote object on creation, and exposes the methods, properties
+/// and signals of the interface by name. The arguments are passed as [`Value`]s, which are checked
+/// against, and coerced to, the types declared by the interface before being sent:
+///
+/// * Integers are converted to the declared integer type if the value fits in it, and to `f64` if a
+///   double is expected.
+/// * Strings are converted to object paths or signatures, if valid.
+/// * Values are wrapped in a variant if a variant is expected, and unwrapped from a variant if not.
+/// * Arrays, dictionaries and structures are coerced element by element.
+///
+/// The replies and signal arguments are decoded into [`OwnedValue`]s, according to the declared
+/// types.
+///
+/// This is useful for generic tools and bindings to dynamic languages. If the interface is known
+/// at compile time, [`macro@crate::proxy`] is a better fit.
+///
+/// Requires the `dynamic-proxy` feature to be enabled.
+///
+/// # Example
+///
+/// ```no_run
+/// # use std::error::Error;
+/// use zbus::{proxy::DynamicProxy, zvariant::Value, Connection};
+///
+/// # zbus::block_on(async {
+/// let connection = Connection::session().await?;
+/// let proxy = DynamicProxy::new(
+///     &connection,
+///     "org.freedesktop.DBus",
+///     "/org/freedesktop/DBus",
+///     "org.freedesktop.DBus",
+/// )
+/// .await?;
+///
+/// // `NameHasOwner` takes a string, and returns a boolean.
+/// let reply = proxy.call("NameHasOwner", &[Value::from("org.freedesktop.DBus")]).await?;
+/// assert_eq!(*reply[0], Value::Bool(true));
+/// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+/// # })?;
+/// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct DynamicProxy<'p> {
+    proxy: Proxy<'p>,
+    interface: Arc<Interface<'static>>,
+}
+
+impl<'p> DynamicProxy<'p> {
+    /// Create a proxy for the `interface` interface of the object at `path` on `destination`.
+    pub async fn new<D, P, I>(
+        conn: &Connection,
+        destination: D,
+        path: P,
+        interface: I,
+    ) -> Result<DynamicProxy<'p>>
+    where
+        D: TryInto<BusName<'p>>,
+        P: TryInto<ObjectPath<'p>>,
+        I: TryInto<InterfaceName<'p>>,
+        D::Error: Into<Error>,
+        P::Error: Into<Error>,
+        I::Error: Into<Error>,
+    {
+        let proxy = Proxy::new(conn, destination, path, interface).await?;
+
+        Self::from_proxy(proxy).await
+    }
+
+    /// Create a proxy for the interface of `proxy`.
+    ///
+    /// Fails with [`Error::InterfaceNotFound`] if the remote object doesn't implement it.
+    pub async fn from_proxy(proxy: Proxy<'p>) -> Result<DynamicProxy<'p>> {
+        let xml = proxy.introspect().await?;
+        let node = Node::from_reader(xml.as_bytes()).map_err(|e| Error::Failure(e.to_string()))?;
+        let interface = node
+            .interfaces()
+            .iter()
+            .find(|i| i.name() == *proxy.interface())
+            .ok_or(Error::InterfaceNotFound)?
+            .clone();
+
+        Ok(Self {
+            proxy,
+            interface: Arc::new(interface),
+        })
+    }
+
+    /// The introspection data of the interface.
+    pub fn interface(&self) -> &Interface<'static> {
+        &self.interface
+    }
+
+    /// The introspection data of the `name` method, if the interface has one.
+    pub fn method(&self, name: &str) -> Option<&Method<'static>> {
+        self.interface.methods().iter().find(|m| m.name() == name)
+    }
+
+    /// The introspection data of the `name` property, if the interface has one.
+    pub fn property(&self, name: &str) -> Option<&Property<'static>> {
+        self.interface
+            .properties()
+            .iter()
+            .find(|p| p.name() == name)
+    }
+
+    /// The introspection data of the `name` signal, if the interface has one.
+    pub fn signal(&self, name: &str) -> Option<&Signal<'static>> {
+        self.interface.signals().iter().find(|s| s.name() == name)
+    }
+
+    /// The reference to the underlying [`Proxy`].
+    pub fn inner(&self) -> &Proxy<'p> {
+        &self.proxy
+    }
+
+    /// Consumes `self`, returning the underlying [`Proxy`].
+    pub fn into_inner(self) -> Proxy<'p> {
+        self.proxy
+    }
+
+    /// Call the `method_name` method with `args`, and return the values of the reply.
+    ///
+    /// The arguments are coerced to the types declared by the interface. It's an error if they
+    /// can't be, or if their number doesn't match.
+    pub async fn call(&self, method_name: &str, args: &[Value<'_>]) -> Result<Vec<OwnedValue>> {
+        let method = self
+            .method(method_name)
+            .ok_or_else(|| fdo::Error::UnknownMethod(format!("Unknown method `{method_name}`")))?;
+        let in_args: Vec<_> = method
+            .args()
+            .iter()
+            .filter(|a| a.direction() != Some(ArgDirection::Out))
+            .map(|a| a.ty().inner())
+            .collect();
+        let out_args: Vec<_> = method
+            .args()
+            .iter()
+            .filter(|a| a.direction() == Some(ArgDirection::Out))
+            .map(|a| a.ty().inner().clone())
+            .collect();
+        if args.len() != in_args.len() {
+            return Err(fdo::Error::InvalidArgs(format!(
+                "`{method_name}` takes {} arguments but {} were given",
+                in_args.len(),
+                args.len(),
+            ))
+            .into());
+        }
+
+        let args = args
+            .iter()
+            .zip(in_args)
+            .map(|(arg, signature)| coerce(arg.try_clone()?, signature))
+            .collect::<Result<Vec<_>>>()?;
+        let reply = if args.is_empty() {
+            self.proxy.call_method(method_name, &()).await?
+        } else {
+            let body = args
+                .into_iter()
+                .fold(StructureBuilder::new(), |builder, arg| {
+                    builder.append_field(arg)
+                })
+                .build()?;
+
+            self.proxy.call_method(method_name, &body).await?
+        };
+
+        decode(&reply.body(), &out_args)
+    }
+
+    /// Get the value of the `property_name` property.
+    pub async fn get_property(&self, property_name: &str) -> Result<OwnedValue> {
+        let property = self
+            .property(property_name)
+            .ok_or_else(|| unknown(property_name))?;
+        if !property.access().read() {
+            return Err(fdo::Error::AccessDenied(format!(
+                "Property `{property_name}` is not readable"
+            ))
+            .into());
+        }
+
+        self.proxy.get_property(property_name).await
+    }
+
+    /// Set the value of the `property_name` property.
+    ///
+    /// `value` is coerced to the type declared by the interface. It's an error if it can't be.
+    pub async fn set_property(&self, property_name: &str, value: Value<'_>) -> Result<()> {
+        let property = self
+            .property(property_name)
+            .ok_or_else(|| unknown(property_name))?;
+        if !property.access().write() {
+            return Err(fdo::Error::PropertyReadOnly(format!(
+                "Property `{property_name}` is not writable"
+            ))
+            .into());
+        }
+        let value = coerce(value, property.ty().inner())?;
+
+        self.proxy
+            .set_property(property_name, value)
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Create a stream receiving the `signal_name` signals.
+    ///
+    /// Use [`DynamicProxy::signal_args`] to decode the received signals. If the interface has no
+    /// such signal, an [`fdo::Error::UnknownMethod`] error is returned, as D-Bus doesn't define an
+    /// error specific to signals.
+    pub async fn receive_signal(&self, signal_name: &str) -> Result<SignalStream<'p>> {
+        if self.signal(signal_name).is_none() {
+            return Err(unknown_signal(signal_name));
+        }
+
+        let signal_name = MemberName::try_from(signal_name)?.to_owned();
+
+        self.proxy.receive_signal(signal_name).await
+    }
+
+    /// Decode the arguments of `signal`, one of the signals of the interface.
+    ///
+    /// As with [`DynamicProxy::receive_signal`], an [`fdo::Error::UnknownMethod`] error is returned
+    /// if `signal` is not one of the signals of the interface.
+    pub fn signal_args(&self, signal: &Message) -> Result<Vec<OwnedValue>> {
+        let header = signal.header();
+        let member = header.member().ok_or(Error::MissingField)?;
+        let signal_info = self
+            .signal(member.as_str())
+            .filter(|_| header.interface() == Some(self.proxy.interface()))
+            .ok_or_else(|| unknown_signal(member.as_str()))?;
+        let args: Vec<_> = signal_info
+            .args()
+            .iter()
+            .map(|a| a.ty().inner().clone())
+            .collect();
+
+        decode(&signal.body(), &args)
+    }
+}
+
+fn unknown(property_name: &str) -> Error {
+    fdo::Error::UnknownProperty(format!("Unknown property `{property_name}`")).into()
+}
+
+fn unknown_signal(signal_name: &str) -> Error {
+    fdo::Error::UnknownMethod(format!("Unknown signal `{signal_name}`")).into()
+}
+
+/// Coerce `value` to `signature`, if possible.
+fn coerce<'a>(value: Value<'a>, signature: &Signature) -> Result<Value<'a>> {
+    if value.value_signature() == signature {
+        return Ok(value);
+    }
+    let mismatch = |value: &Value<'_>| {
+        Error::Variant(zvariant::Error::SignatureMismatch(
+            value.value_signature().clone(),
+            format!("`{signature}`"),
+        ))
+    };
+
+    let coerced = match (value, signature) {
+        (value, Signature::Variant) => Value::Value(Box::new(value)),
+        // Dynamically-typed callers tend to wrap everything in variants.
+        (Value::Value(value), _) => return coerce(*value, signature),
+        (Value::Str(s), Signature::ObjectPath) => {
+            Value::ObjectPath(ObjectPath::try_from(s.as_str().to_string())?)
+        }
+        (Value::Str(s), Signature::Signature) => Value::Signature(
+            Signature::try_from(s.as_str()).map_err(zvariant::Error::SignatureParse)?,
+        ),
+        (Value::Array(array), Signature::Array(element)) => {
+            let mut coerced = Array::new(element.signature());
+            for e in array.iter() {
+                coerced.append(coerce(e.try_clone()?, element.signature())?)?;
+            }
+
+            Value::Array(coerced)
+        }
+        (Value::Dict(dict), Signature::Dict { key, value }) => {
+            let mut coerced = Dict::new(key.signature(), value.signature());
+            for (k, v) in dict.iter() {
+                coerced.append(
+                    coerce(k.try_clone()?, key.signature())?,
+                    coerce(v.try_clone()?, value.signature())?,
+                )?;
+            }
+
+            Value::Dict(coerced)
+        }
+        (Value::Structure(structure), Signature::Structure(fields))
+            if structure.fields().len() == fields.len() =>
+        {
+            let builder = structure
+                .into_fields()
+                .into_iter()
+                .zip(fields.iter())
+                .try_fold(StructureBuilder::new(), |builder, (field, signature)| {
+                    coerce(field, signature).map(|field| builder.append_field(field))
+                })?;
+
+            Value::Structure(builder.build()?)
+        }
+        (value, _) => coerce_number(&value, signature).ok_or_else(|| mismatch(&value))?,
+    };
+
+    Ok(coerced)
+}
+
+/// Convert a numeric `value` to the numeric type of `signature`, if it fits.
+fn coerce_number(value: &Value<'_>, signature: &Signature) -> Option<Value<'static>> {
+    let n = match *value {
+        Value::U8(n) => i128::from(n),
+        Value::I16(n) => i128::from(n),
+        Value::U16(n) => i128::from(n),
+        Value::I32(n) => i128::from(n),
+        Value::U32(n) => i128::from(n),
+        Value::I64(n) => i128::from(n),
+        Value::U64(n) => i128::from(n),
+        // Only doubles without a fractional part are converted to integers.
+        Value::F64(f) if f.fract() == 0.0 && f.abs() < 2f64.powi(64) => f as i128,
+        _ => return None,
+    };
+
+    let value = match signature {
+        Signature::U8 => Value::U8(n.try_into().ok()?),
+        Signature::I16 => Value::I16(n.try_into().ok()?),
+        Signature::U16 => Value::U16(n.try_into().ok()?),
+        Signature::I32 => Value::I32(n.try_into().ok()?),
+        Signature::U32 => Value::U32(n.try_into().ok()?),
+        Signature::I64 => Value::I64(n.try_into().ok()?),
+        Signature::U64 => Value::U64(n.try_into().ok()?),
+        Signature::F64 => Value::F64(n as f64),
+        _ => return None,
+    };
+
+    Some(value)
+}
+
+/// Decode `body` into values of the types in `signatures`.
+fn decode(body: &Body, signatures: &[Signature]) -> Result<Vec<OwnedValue>> {
+    let expected = match signatures {
+        [] => Signature::Unit,
+        [signature] => signature.clone(),
+        signatures => Signature::structure(signatures.to_vec()),
+    };
+    if *body.signature() != expected {
+        return Err(Error::Variant(zvariant::Error::SignatureMismatch(
+            body.signature().clone(),
+            format!("`{}`", expected.to_string_no_parens()),
+        )));
+    }
+    if signatures.is_empty() {
+        return Ok(vec![]);
+    }
+
+    // Deserializing as a structure of the expected types, ensures that a single structure
+    // argument isn't mistaken for several arguments.
+    let (structure, _) = body
+        .data()
+        .deserialize_for_dynamic_signature::<_, Structure<'_>>(Signature::structure(
+            signatures.to_vec(),
+        ))?;
+
+    structure
+        .into_fields()
+        .into_iter()
+        .map(|field| field.try_into_owned().map_err(Into::into))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::StreamExt;
+    use ntest::timeout;
+    use test_log::test;
+
+    use super::*;
+    use crate::{interface, object_server::SignalEmitter};
+
+    #[derive(Default)]
+    struct Store {
+        size: u16,
+    }
+
+    #[interface(name = "org.zbus.Store")]
+    impl Store {
+        fn put(&mut self, path: ObjectPath<'_>, data: Vec<u8>, meta: (u16, Value<'_>)) -> u64 {
+            assert_eq!(path.as_str(), "/a/b");
+            assert_eq!(meta.1, Value::from("meta"));
+            self.size += meta.0;
+
+            data.len() as u64
+        }
+
+        fn stat(&self) -> ((String, u16), bool) {
+            (("store".into(), self.size), true)
+        }
+
+        #[zbus(property)]
+        fn size(&self) -> u16 {
+            self.size
+        }
+
+        #[zbus(property)]
+        fn set_size(&mut self, size: u16) {
+            self.size = size;
+        }
+
+        #[zbus(property)]
+        fn name(&self) -> &str {
+            "store"
+        }
+
+        #[zbus(signal)]
+        async fn full(emitter: &SignalEmitter<'_>, size: u16, reason: &str) -> crate::Result<()>;
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn dynamic_proxy() {
+        crate::block_on(dynamic_proxy_async());
+    }
+
+    async fn dynamic_proxy_async() {
+        let service = crate::connection::Builder::session()
+            .unwrap()
+            .serve_at("/org/zbus/Store", Store::default())
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+        let client = Connection::session().await.unwrap();
+        let proxy = DynamicProxy::new(
+            &client,
+            service.unique_name().unwrap().to_owned(),
+            "/org/zbus/Store",
+            "org.zbus.Store",
+        )
+        .await
+        .unwrap();
+        assert!(proxy.method("Put").is_some());
+        assert!(proxy.property("Size").is_some());
+        assert!(proxy.signal("Full").is_some());
+
+        // The arguments are coerced to `o`, `ay` and `(qv)`.
+        let meta = StructureBuilder::new()
+            .add_field(7i64)
+            .add_field("meta")
+            .build()
+            .unwrap();
+        let data = Value::Array(vec![1i32, 2, 3].into());
+        let reply = proxy
+            .call("Put", &[Value::from("/a/b"), data, Value::from(meta)])
+            .await
+            .unwrap();
+        assert_eq!(reply.len(), 1);
+        assert_eq!(*reply[0], Value::U64(3));
+
+        // A single structure argument isn't confused with several arguments.
+        let reply = proxy.call("Stat", &[]).await.unwrap();
+        assert_eq!(reply.len(), 2);
+        assert_eq!(
+            *reply[0],
+            Value::from(
+                StructureBuilder::new()
+                    .add_field("store")
+                    .add_field(7u16)
+                    .build()
+                    .unwrap()
+            )
+        );
+        assert_eq!(*reply[1], Value::Bool(true));
+
+        // Invalid calls are refused before being sent.
+        match proxy.call("Put", &[Value::from("/a/b")]).await {
+            Err(Error::FDO(e)) => assert!(matches!(*e, fdo::Error::InvalidArgs(_))),
+            res => panic!("unexpected result: {res:?}"),
+        }
+        let data = Value::Array(vec![256i32].into());
+        let meta = Value::from(
+            StructureBuilder::new()
+                .add_field(1u16)
+                .add_field(1u8)
+                .build()
+                .unwrap(),
+        );
+        match proxy.call("Put", &[Value::from("/a/b"), data, meta]).await {
+            Err(Error::Variant(zvariant::Error::SignatureMismatch(..))) => (),
+            res => panic!("unexpected result: {res:?}"),
+        }
+        assert!(proxy.call("Nope", &[]).await.is_err());
+
+        // Properties.
+        assert_eq!(*proxy.get_property("Size").await.unwrap(), Value::U16(7));
+        proxy.set_property("Size", Value::U32(9)).await.unwrap();
+        assert!(proxy.set_property("Size", Value::I32(-1)).await.is_err());
+        assert!(proxy.set_property("Name", Value::from("x")).await.is_err());
+        let reply = proxy.call("Stat", &[]).await.unwrap();
+        let Value::Structure(stat) = &*reply[0] else {
+            panic!("unexpected reply: {reply:?}");
+        };
+        assert_eq!(stat.fields()[1], Value::U16(9));
+
+        // Signals.
+        let mut stream = proxy.receive_signal("Full").await.unwrap();
+        match proxy.receive_signal("Empty").await {
+            Err(Error::FDO(e)) => assert!(matches!(*e, fdo::Error::UnknownMethod(_))),
+            res => panic!("unexpected result: {res:?}"),
+        }
+        let emitter = SignalEmitter::new(&service, "/org/zbus/Store").unwrap();
+        Store::full(&emitter, 9, "no space").await.unwrap();
+        let signal = stream.next().await.unwrap();
+        let args = proxy.signal_args(&signal).unwrap();
+        assert_eq!(*args[0], Value::U16(9));
+        assert_eq!(*args[1], Value::from("no space"));
+    }
+}
@@ -0,0 +1,315 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+
+use async_broadcast::{broadcast, InactiveReceiver, Receiver, Sender};
+use futures_core::{ready, stream};
+use serde::{de::DeserializeOwned, Serialize};
+use zbus_names::{InterfaceName, MemberName};
+use zvariant::{DynamicDeserialize, DynamicType, ObjectPath, OwnedValue, Type, Value};
+
+use crate::{fdo, message::Message, Error, Result};
+
+// Signals emitted while the receivers are behind are dropped, as a bus would eventually do.
+const MAX_QUEUED_SIGNALS: usize = 64;
+
+type Handler = Arc<dyn Fn(&Message) -> Result<Message> + Send + Sync>;
+
+/// An in-memory stand-in for a remote object, for testing client code without a D-Bus bus.
+///
+/// Method calls made through a `Mock` are recorded and answered by the handlers programmed with
+/// [`Mock::on_call`]. Properties are stored in the mock itself, and signals are injected with
+/// [`Mock::emit_signal`]. The method calls and signals are encoded as actual D-Bus messages, so
+/// the arguments and replies are checked against the D-Bus types, just as they would be on a bus.
+///
+/// You typically don't use this type directly, but through the mocks generated by the
+/// [`macro@crate::proxy`] macro, with the `gen_mock` attribute. Cloning a `Mock` gives another
+/// handle to the same mock.
+#[derive(Clone)]
+pub struct Mock {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    interface: InterfaceName<'static>,
+    path: ObjectPath<'static>,
+    state: Mutex<State>,
+    signals: Sender<Message>,
+    _signals_receiver: InactiveReceiver<Message>,
+}
+
+#[derive(Default)]
+struct State {
+    handlers: HashMap<String, Handler>,
+    calls: Vec<Message>,
+    properties: HashMap<String, OwnedValue>,
+}
+
+impl Mock {
+    /// Create a mock of the `interface` interface of the object at `path`.
+    pub fn new(interface: InterfaceName<'static>, path: ObjectPath<'static>) -> Self {
+        let (mut signals, receiver) = broadcast(MAX_QUEUED_SIGNALS);
+        signals.set_overflow(true);
+        signals.set_await_active(false);
+
+        Self {
+            inner: Arc::new(Inner {
+                interface,
+                path,
+                state: Mutex::new(State::default()),
+                signals,
+                _signals_receiver: receiver.deactivate(),
+            }),
+        }
+    }
+
+    /// The interface being mocked.
+    pub fn interface(&self) -> &InterfaceName<'static> {
+        &self.inner.interface
+    }
+
+    /// The path of the mocked object.
+    pub fn path(&self) -> &ObjectPath<'static> {
+        &self.inner.path
+    }
+
+    /// Program the response to the calls of the `method_name` method.
+    ///
+    /// `handler` receives the arguments of the call, as a tuple, and returns the reply or an
+    /// error. It replaces any handler previously set for the method. Calls to methods without a
+    /// handler fail with an [`fdo::Error::UnknownMethod`] error.
+    ///
+    /// # Panics
+    ///
+    /// If `method_name` is not a valid member name.
+    pub fn on_call<A, R, F>(&self, method_name: &str, handler: F) -> &Self
+    where
+        A: DeserializeOwned + Type,
+        R: Serialize + DynamicType,
+        F: Fn(A) -> Result<R> + Send + Sync + 'static,
+    {
+        let method_name = MemberName::try_from(method_name).expect("invalid method name");
+        let handler: Handler = Arc::new(move |call: &Message| {
+            let args = call.body().deserialize()?;
+            let reply = handler(args)?;
+
+            Message::method_return(&call.header())?.build(&reply)
+        });
+        self.state()
+            .handlers
+            .insert(method_name.to_string(), handler);
+
+        self
+    }
+
+    /// Call the `method_name` method of the mock and return the reply.
+    ///
+    /// The call is recorded and passed to the handler set for the method.
+    pub fn call<B, R>(&self, method_name: &str, body: &B) -> Result<R>
+    where
+        B: Serialize + DynamicType,
+        R: for<'d> DynamicDeserialize<'d>,
+    {
+        let reply = self.call_method(method_name, body)?;
+
+        reply.body().deserialize()
+    }
+
+    /// Call the `method_name` method of the mock, without waiting for a reply.
+    ///
+    /// The call is recorded and passed to the handler set for the method, if any, but the outcome
+    /// is ignored.
+    pub fn call_noreply<B>(&self, method_name: &str, body: &B) -> Result<()>
+    where
+        B: Serialize + DynamicType,
+    {
+        let (call, handler) = self.record_call(method_name, body)?;
+        if let Some(handler) = handler {
+            let _ = handler(&call);
+        }
+
+        Ok(())
+    }
+
+    /// The method calls made so far, in order.
+    pub fn calls(&self) -> Vec<Message> {
+        self.state().calls.clone()
+    }
+
+    /// The calls of the `method_name` method made so far, in order.
+    pub fn calls_to(&self, method_name: &str) -> Vec<Message> {
+        self.state()
+            .calls
+            .iter()
+            .filter(|call| {
+                call.header()
+                    .member()
+                    .is_some_and(|m| m.as_str() == method_name)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Forget about the method calls made so far.
+    pub fn clear_calls(&self) {
+        self.state().calls.clear();
+    }
+
+    /// Get the value of the `property_name` property.
+    ///
+    /// Fails with an [`fdo::Error::UnknownProperty`] error if the property was never set.
+    pub fn get_property<T>(&self, property_name: &str) -> Result<T>
+    where
+        T: TryFrom<OwnedValue>,
+        T::Error: Into<Error>,
+    {
+        let value = self
+            .state()
+            .properties
+            .get(property_name)
+            .ok_or_else(|| {
+                fdo::Error::UnknownProperty(format!("Unknown property `{property_name}`"))
+            })?
+            .try_clone()?;
+
+        T::try_from(value).map_err(Into::into)
+    }
+
+    /// Set the value of the `property_name` property.
+    pub fn set_property<'t, T>(&self, property_name: &str, value: T) -> Result<()>
+    where
+        T: 't + Into<Value<'t>>,
+    {
+        let value = value.into().try_into_owned()?;
+        self.state()
+            .properties
+            .insert(property_name.to_string(), value);
+
+        Ok(())
+    }
+
+    /// Emit the `signal_name` signal, with `body` as its arguments.
+    ///
+    /// The signal is received by the streams created with [`Mock::receive_signal`] beforehand.
+    pub fn emit_signal<B>(&self, signal_name: &str, body: &B) -> Result<()>
+    where
+        B: Serialize + DynamicType,
+    {
+        let signal =
+            Message::signal(&self.inner.path, &self.inner.interface, signal_name)?.build(body)?;
+        // Errors only mean that there are no active receivers or that the oldest signal was
+        // dropped, both of which are fine.
+        let _ = self.inner.signals.try_broadcast(signal);
+
+        Ok(())
+    }
+
+    /// Create a stream receiving the `signal_name` signals emitted through
+    /// [`Mock::emit_signal`].
+    pub fn receive_signal(&self, signal_name: &str) -> MockSignalStream {
+        MockSignalStream {
+            receiver: self.inner.signals.new_receiver(),
+            signal_name: signal_name.to_string(),
+            convert: Some,
+        }
+    }
+
+    fn call_method<B>(&self, method_name: &str, body: &B) -> Result<Message>
+    where
+        B: Serialize + DynamicType,
+    {
+        let (call, handler) = self.record_call(method_name, body)?;
+
+        match handler {
+            Some(handler) => handler(&call),
+            None => Err(fdo::Error::UnknownMethod(format!(
+                "No response programmed for `{method_name}`"
+            ))
+            .into()),
+        }
+    }
+
+    /// Build and record a call, returning it along with the handler for it.
+    fn record_call<B>(&self, method_name: &str, body: &B) -> Result<(Message, Option<Handler>)>
+    where
+        B: Serialize + DynamicType,
+    {
+        let call = Message::method_call(&self.inner.path, method_name)?
+            .interface(&self.inner.interface)?
+            .build(body)?;
+        let mut state = self.state();
+        state.calls.push(call.clone());
+        let handler = state.handlers.get(method_name).cloned();
+
+        Ok((call, handler))
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.inner.state.lock().expect("lock poisoned")
+    }
+}
+
+impl fmt::Debug for Mock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Mock")
+            .field("interface", &self.inner.interface)
+            .field("path", &self.inner.path)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A [`stream::Stream`] of the signals emitted through a [`Mock`].
+///
+/// Use [`Mock::receive_signal`] to create an instance of this type.
+pub struct MockSignalStream<S = Message> {
+    receiver: Receiver<Message>,
+    signal_name: String,
+    convert: fn(Message) -> Option<S>,
+}
+
+impl<S> MockSignalStream<S> {
+    /// Convert the signals with `convert`, skipping those it returns `None` for.
+    ///
+    /// This is useful to turn the signals into the signal types generated by the
+    /// [`macro@crate::proxy`] macro, through their `from_message` method.
+    pub fn map_signals<T>(self, convert: fn(Message) -> Option<T>) -> MockSignalStream<T> {
+        MockSignalStream {
+            receiver: self.receiver,
+            signal_name: self.signal_name,
+            convert,
+        }
+    }
+}
+
+impl<S> stream::Stream for MockSignalStream<S> {
+    type Item = S;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<S>> {
+        let this = self.get_mut();
+
+        loop {
+            let Some(signal) = ready!(Pin::new(&mut this.receiver).poll_next(cx)) else {
+                return Poll::Ready(None);
+            };
+            let is_match = signal
+                .header()
+                .member()
+                .is_some_and(|m| m.as_str() == this.signal_name);
+            if let Some(signal) = is_match.then(|| (this.convert)(signal)).flatten() {
+                return Poll::Ready(Some(signal));
+            }
+        }
+    }
+}
+
+impl<S> fmt::Debug for MockSignalStream<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MockSignalStream")
+            .field("signal_name", &self.signal_name)
+            .finish_non_exhaustive()
+    }
+}
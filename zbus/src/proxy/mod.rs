@@ -12,6 +12,7 @@ use std::{
     pin::Pin,
     sync::{Arc, OnceLock, RwLock, RwLockReadGuard},
     task::{Context, Poll},
+    time::{Duration, Instant},
 };
 use tracing::{debug, info_span, instrument, trace, Instrument};
 
@@ -19,17 +20,32 @@ use zbus_names::{BusName, InterfaceName, MemberName, UniqueName};
 use zvariant::{ObjectPath, OwnedValue, Str, Value};
 
 use crate::{
+    connection::SignalHandler,
     fdo::{self, IntrospectableProxy, NameOwnerChanged, PropertiesChangedStream, PropertiesProxy},
     message::{Flags, Message, Sequence, Type},
-    AsyncDrop, Connection, Error, Executor, MatchRule, MessageStream, OwnedMatchRule, Result, Task,
+    timeout::sleep,
+    AsyncDrop, Connection, Error, Executor, MatchRule, MessageStream, OverflowPolicy,
+    OwnedMatchRule, Result, Task,
 };
 
 mod builder;
-pub use builder::{Builder, CacheProperties};
+use builder::CachePolicies;
+pub use builder::{Builder, CacheProperties, PropertyCachePolicy};
 
 mod defaults;
 pub use defaults::Defaults;
 
+mod mock;
+pub use mock::{Mock, MockSignalStream};
+
+mod retry;
+pub use retry::RetryPolicy;
+
+#[cfg(feature = "dynamic-proxy")]
+mod dynamic;
+#[cfg(feature = "dynamic-proxy")]
+pub use dynamic::DynamicProxy;
+
 /// A client-side interface proxy.
 ///
 /// A `Proxy` is a helper to interact with an interface on a remote object.
@@ -95,10 +111,14 @@ pub(crate) struct ProxyInner<'a> {
     pub(crate) interface: InterfaceName<'a>,
 
     /// Cache of property values.
-    property_cache: Option<OnceLock<(Arc<PropertiesCache>, Task<()>)>>,
+    property_cache: Option<OnceLock<PropertiesCacheAndTask>>,
     /// Set of properties which do not get cached, by name.
     /// This overrides proxy-level caching behavior.
     uncached_properties: HashSet<Str<'a>>,
+    /// The policy for retrying failed method calls, if any.
+    retry_policy: Option<RetryPolicy>,
+    /// The policies for keeping the cached properties up to date.
+    cache_policies: CachePolicies,
 }
 
 impl Drop for ProxyInnerStatic {
@@ -178,7 +198,7 @@ impl<T> PropertyChanged<'_, T> {
             values
                 .get_mut(self.name)
                 .expect("PropertyStream with no corresponding property")
-                .value = Some(value);
+                .set(Some(value));
         }
 
         Ok(Wrapper {
@@ -249,6 +269,9 @@ where
     }
 }
 
+/// The properties cache, along with the task keeping it updated (if any).
+type PropertiesCacheAndTask = (Arc<PropertiesCache>, Option<Task<()>>);
+
 #[derive(Debug)]
 pub(crate) struct PropertiesCache {
     values: RwLock<HashMap<String, PropertyValue>>,
@@ -268,6 +291,7 @@ impl PropertiesCache {
         interface: InterfaceName<'static>,
         executor: &Executor<'_>,
         uncached_properties: HashSet<zvariant::Str<'static>>,
+        cache_policies: CachePolicies,
     ) -> (Arc<Self>, Task<()>) {
         let cache = Arc::new(PropertiesCache {
             values: Default::default(),
@@ -280,7 +304,7 @@ impl PropertiesCache {
         let task_name = format!("{interface} proxy caching");
         let proxy_caching = async move {
             let result = cache_clone
-                .init(proxy, interface, uncached_properties)
+                .init(proxy.clone(), interface, uncached_properties)
                 .await;
             let (prop_changes, interface, uncached_properties) = {
                 let mut caching_result = cache_clone.caching_result.write().expect("lock poisoned");
@@ -306,10 +330,10 @@ impl PropertiesCache {
                 }
             };
 
-            if let Err(e) = cache_clone
-                .keep_updated(prop_changes, interface, uncached_properties)
-                .await
-            {
+            let keep_updated =
+                cache_clone.keep_updated(prop_changes, interface.clone(), uncached_properties);
+            let poll = cache_clone.poll(proxy, interface, cache_policies);
+            if let Err(e) = futures_lite::future::or(keep_updated, poll).await {
                 debug!("Error keeping properties cache updated: {e}");
             }
         }
@@ -319,6 +343,31 @@ impl PropertiesCache {
         (cache, task)
     }
 
+    /// Create a cache that is already populated with `values`.
+    ///
+    /// Unlike [`PropertiesCache::new`], no task is spawned to keep the cache updated. It's up to
+    /// the caller to do that, through [`PropertiesCache::update_cache`] and
+    /// [`PropertiesCache::reset`].
+    pub(crate) fn new_populated(values: HashMap<String, OwnedValue>) -> Arc<Self> {
+        let values = values
+            .into_iter()
+            .map(|(name, value)| {
+                let value = PropertyValue {
+                    value: Some(value),
+                    cached_at: Some(Instant::now()),
+                    event: Event::new(),
+                };
+
+                (name, value)
+            })
+            .collect();
+
+        Arc::new(PropertiesCache {
+            values: RwLock::new(values),
+            caching_result: RwLock::new(CachingResult::Cached { result: Ok(()) }),
+        })
+    }
+
     /// new() runs this in a task it spawns for initialization of properties cache.
     async fn init(
         &self,
@@ -397,7 +446,19 @@ impl PropertiesCache {
         use futures_lite::StreamExt;
 
         trace!("Listening for property changes on {interface}...");
+        let mut lagged = 0;
         while let Some(update) = prop_changes.next().await {
+            if prop_changes.inner().lagged() != lagged {
+                lagged = prop_changes.inner().lagged();
+                // Some changes were missed so none of the cached values can be trusted anymore.
+                debug!("Missed property changes on {interface}, invalidating the cache");
+                let names: Vec<String> = {
+                    let values = self.values.read().expect("lock poisoned");
+                    values.keys().cloned().collect()
+                };
+                let names: Vec<&str> = names.iter().map(String::as_str).collect();
+                self.update_cache(&uncached_properties, &HashMap::new(), &names, &interface);
+            }
             if let Ok(args) = update.args() {
                 if args.interface_name == interface {
                     self.update_cache(
@@ -413,7 +474,58 @@ impl PropertiesCache {
         Ok(())
     }
 
-    fn update_cache(
+    /// new() runs this in a task it spawns for fetching the polled properties periodically.
+    #[instrument(skip_all)]
+    async fn poll(
+        &self,
+        proxy: PropertiesProxy<'static>,
+        interface: InterfaceName<'static>,
+        cache_policies: CachePolicies,
+    ) -> Result<()> {
+        let Some(tick) = cache_policies.min_poll_interval() else {
+            // Nothing to poll.
+            return std::future::pending().await;
+        };
+        let mut last_polled: HashMap<String, Instant> = HashMap::new();
+
+        loop {
+            sleep(tick).await;
+
+            let mut due: Vec<(String, Duration)> = {
+                let values = self.values.read().expect("lock poisoned");
+                values
+                    .keys()
+                    .map(String::as_str)
+                    .chain(cache_policies.polled_properties())
+                    .filter_map(|name| {
+                        let interval = cache_policies.get(name).poll_interval()?;
+
+                        Some((name.to_string(), interval))
+                    })
+                    .collect()
+            };
+            due.sort_unstable();
+            due.dedup();
+
+            for (name, interval) in due {
+                if last_polled
+                    .get(&name)
+                    .is_some_and(|polled| polled.elapsed() < interval)
+                {
+                    continue;
+                }
+
+                trace!("Polling property `{interface}.{name}`");
+                match proxy.get(interface.as_ref(), &name).await {
+                    Ok(value) => self.store(&name, value),
+                    Err(e) => debug!("Failed to poll property `{interface}.{name}`: {e}"),
+                }
+                last_polled.insert(name, Instant::now());
+            }
+        }
+    }
+
+    pub(crate) fn update_cache(
         &self,
         uncached_properties: &HashSet<Str<'_>>,
         changed: &HashMap<&str, Value<'_>>,
@@ -433,7 +545,7 @@ impl PropertiesCache {
             trace!("Property `{interface}.{inval}` invalidated");
 
             if let Some(entry) = values.get_mut(*inval) {
-                entry.value = None;
+                entry.set(None);
                 entry.event.notify(usize::MAX);
             }
         }
@@ -459,9 +571,39 @@ impl PropertiesCache {
                     continue;
                 }
             };
-            entry.value = Some(value);
+            entry.set(Some(value));
+            entry.event.notify(usize::MAX);
+        }
+    }
+
+    /// Cache `value` as the value of `property_name`.
+    ///
+    /// The listeners are notified if the value differs from the previously cached one.
+    fn store(&self, property_name: &str, value: OwnedValue) {
+        let mut values = self.values.write().expect("lock poisoned");
+        let entry = values.entry(property_name.to_string()).or_default();
+        let changed = entry.value.as_ref() != Some(&value);
+
+        entry.set(Some(value));
+        if changed {
+            entry.event.notify(usize::MAX);
+        }
+    }
+
+    /// Replace all the cached values with `values`.
+    ///
+    /// Properties that are not in `values` are invalidated.
+    pub(crate) fn reset(&self, mut values: HashMap<String, OwnedValue>) {
+        let mut cached = self.values.write().expect("lock poisoned");
+
+        for (name, entry) in cached.iter_mut() {
+            entry.set(values.remove(name));
             entry.event.notify(usize::MAX);
         }
+        for (name, value) in values {
+            let entry = cached.entry(name).or_default();
+            entry.set(Some(value));
+        }
     }
 
     /// Wait for the cache to be populated and return any error encountered during population.
@@ -483,6 +625,7 @@ impl PropertiesCache {
 }
 
 impl<'a> ProxyInner<'a> {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         conn: Connection,
         destination: BusName<'a>,
@@ -490,10 +633,14 @@ impl<'a> ProxyInner<'a> {
         interface: InterfaceName<'a>,
         cache: CacheProperties,
         uncached_properties: HashSet<Str<'a>>,
+        populated_cache: Option<Arc<PropertiesCache>>,
+        retry_policy: Option<RetryPolicy>,
+        cache_policies: CachePolicies,
     ) -> Self {
-        let property_cache = match cache {
-            CacheProperties::Yes | CacheProperties::Lazily => Some(OnceLock::new()),
-            CacheProperties::No => None,
+        let property_cache = match (cache, populated_cache) {
+            (CacheProperties::No, _) => None,
+            (_, Some(populated)) => Some(OnceLock::from((populated, None))),
+            (CacheProperties::Yes | CacheProperties::Lazily, None) => Some(OnceLock::new()),
         };
         Self {
             inner_without_borrows: ProxyInnerStatic {
@@ -505,6 +652,8 @@ impl<'a> ProxyInner<'a> {
             interface,
             property_cache,
             uncached_properties,
+            retry_policy,
+            cache_policies,
         }
     }
 
@@ -634,6 +783,11 @@ impl<'a> Proxy<'a> {
         &self.inner.interface
     }
 
+    /// Get a reference to the policy for retrying failed method calls, if any.
+    pub fn retry_policy(&self) -> Option<&RetryPolicy> {
+        self.inner.retry_policy.as_ref()
+    }
+
     /// Introspect the associated object, and return the XML description.
     ///
     /// See the [xml](https://docs.rs/zbus_xml) crate for parsing the
@@ -698,7 +852,15 @@ impl<'a> Proxy<'a> {
                 .collect();
             let executor = self.connection().executor();
 
-            PropertiesCache::new(proxy, interface, executor, uncached_properties)
+            let (cache, task) = PropertiesCache::new(
+                proxy,
+                interface,
+                executor,
+                uncached_properties,
+                self.inner.cache_policies.clone(),
+            );
+
+            (cache, Some(task))
         });
 
         Some(cache)
@@ -737,10 +899,12 @@ impl<'a> Proxy<'a> {
             .map(|c| c.0.values.read().expect("lock poisoned"))
         {
             // ensure that the property is in the cache.
+            let ttl = self.inner.cache_policies.get(property_name).ttl();
             values
                 .get(property_name)
-                // if the property value has not yet been cached, this will return None.
-                .and_then(|e| e.value.as_ref())?;
+                // if the property value has not yet been cached or has expired, this will return
+                // None.
+                .filter(|e| e.is_fresh(ttl))?;
 
             struct Wrapper<'a> {
                 values: RwLockReadGuard<'a, HashMap<String, PropertyValue>>,
@@ -792,9 +956,89 @@ impl<'a> Proxy<'a> {
         }
 
         let value = self.get_proxy_property(property_name).await?;
+        if let Some(cache) = self.get_property_cache() {
+            let is_uncached = self
+                .inner
+                .uncached_properties
+                .contains(&Str::from(property_name));
+            if !is_uncached
+                && self
+                    .inner
+                    .cache_policies
+                    .get(property_name)
+                    .refreshes_on_read()
+            {
+                cache.store(property_name, value.try_clone()?);
+            }
+        }
+
         value.try_into().map_err(Into::into)
     }
 
+    /// Get all the properties, as a [`crate::Properties`] implementation.
+    ///
+    /// This calls the `GetAll` method of the `org.freedesktop.DBus.Properties` interface, fetching
+    /// all the properties in a single round trip, bypassing the cache.
+    pub async fn get_all_as<T>(&self) -> Result<T>
+    where
+        T: crate::Properties,
+    {
+        let values = self
+            .properties_proxy()
+            .get_all(self.inner.interface.as_ref())
+            .await?;
+
+        T::from_properties(values)
+    }
+
+    /// Fetch all the properties from the peer and update the cache with them.
+    ///
+    /// This calls the `GetAll` method of the `org.freedesktop.DBus.Properties` interface, the same
+    /// way the cache is populated initially. It's a no-op if caching is disabled for this proxy.
+    ///
+    /// If the initial population of the cache failed, a successful refresh makes the cache
+    /// [ready](PropertyCacheState::Ready) again.
+    pub async fn refresh_properties(&self) -> Result<()> {
+        let Some(cache) = self.get_property_cache() else {
+            return Ok(());
+        };
+        // Don't race with the initial population, whatever its outcome.
+        let _ = cache.ready().await;
+
+        let values = self
+            .properties_proxy()
+            .get_all(self.inner.interface.as_ref())
+            .await?;
+        for (name, value) in values {
+            if !self.inner.uncached_properties.contains(&Str::from(&*name)) {
+                cache.store(&name, value);
+            }
+        }
+        *cache.caching_result.write().expect("lock poisoned") =
+            CachingResult::Cached { result: Ok(()) };
+
+        Ok(())
+    }
+
+    /// The state of the properties cache.
+    ///
+    /// This notably allows to find out about the errors encountered while populating the cache,
+    /// in which case the property reads fail with the same error.
+    pub fn property_cache_state(&self) -> PropertyCacheState {
+        let Some(cache) = &self.inner.property_cache else {
+            return PropertyCacheState::Disabled;
+        };
+        let Some((cache, _)) = cache.get() else {
+            return PropertyCacheState::Pending;
+        };
+
+        match &*cache.caching_result.read().expect("lock poisoned") {
+            CachingResult::Caching { .. } => PropertyCacheState::Pending,
+            CachingResult::Cached { result: Ok(()) } => PropertyCacheState::Ready,
+            CachingResult::Cached { result: Err(e) } => PropertyCacheState::Failed(e.clone()),
+        }
+    }
+
     /// Set the property `property_name`.
     ///
     /// Effectively, call the `Set` method of the `org.freedesktop.DBus.Properties` interface.
@@ -820,17 +1064,26 @@ impl<'a> Proxy<'a> {
         M::Error: Into<Error>,
         B: serde::ser::Serialize + zvariant::DynamicType,
     {
-        self.inner
-            .inner_without_borrows
-            .conn
-            .call_method(
-                Some(&self.inner.destination),
-                self.inner.path.as_str(),
-                Some(&self.inner.interface),
-                method_name,
-                body,
-            )
-            .await
+        let method_name = method_name.try_into().map_err(Into::into)?;
+        let method_name = &method_name;
+        let attempt = || async move {
+            self.inner
+                .inner_without_borrows
+                .conn
+                .call_method(
+                    Some(&self.inner.destination),
+                    self.inner.path.as_str(),
+                    Some(&self.inner.interface),
+                    method_name,
+                    body,
+                )
+                .await
+        };
+
+        match self.retry_policy() {
+            Some(policy) => policy.run(self, attempt).await,
+            None => attempt().await,
+        }
     }
 
     /// Call a method and return the reply body.
@@ -871,23 +1124,56 @@ impl<'a> Proxy<'a> {
         B: serde::ser::Serialize + zvariant::DynamicType,
         R: for<'d> zvariant::DynamicDeserialize<'d>,
     {
-        let flags = flags.iter().map(Flags::from).collect::<BitFlags<_>>();
-        match self
-            .inner
-            .inner_without_borrows
-            .conn
-            .call_method_raw(
-                Some(self.destination()),
-                self.path(),
-                Some(self.interface()),
-                method_name,
-                flags,
-                body,
-            )
-            .await?
-        {
-            Some(reply) => reply.await?.body().deserialize().map(Some),
-            None => Ok(None),
+        self.call_with_retry_policy(method_name, flags, self.retry_policy(), body)
+            .await
+    }
+
+    /// Same as [`Proxy::call_with_flags`], but with `retry_policy` used instead of the policy the
+    /// proxy was built with.
+    ///
+    /// Pass `None` to make a single attempt, e.g. for methods that must not be called more than
+    /// once. Calls with the `NoReplyExpected` flag are never retried.
+    pub async fn call_with_retry_policy<'m, M, B, R>(
+        &self,
+        method_name: M,
+        flags: BitFlags<MethodFlags>,
+        retry_policy: Option<&RetryPolicy>,
+        body: &B,
+    ) -> Result<Option<R>>
+    where
+        M: TryInto<MemberName<'m>>,
+        M::Error: Into<Error>,
+        B: serde::ser::Serialize + zvariant::DynamicType,
+        R: for<'d> zvariant::DynamicDeserialize<'d>,
+    {
+        let method_name = method_name.try_into().map_err(Into::into)?;
+        let method_name = &method_name;
+        let raw_flags = flags.iter().map(Flags::from).collect::<BitFlags<_>>();
+        let attempt = || async move {
+            match self
+                .inner
+                .inner_without_borrows
+                .conn
+                .call_method_raw(
+                    Some(self.destination()),
+                    self.path(),
+                    Some(self.interface()),
+                    method_name,
+                    raw_flags,
+                    body,
+                )
+                .await?
+            {
+                Some(reply) => reply.await?.body().deserialize().map(Some),
+                None => Ok(None),
+            }
+        };
+
+        match retry_policy {
+            Some(policy) if !flags.contains(MethodFlags::NoReplyExpected) => {
+                policy.run(self, attempt).await
+            }
+            _ => attempt().await,
         }
     }
 
@@ -951,6 +1237,29 @@ impl<'a> Proxy<'a> {
         SignalStream::new(self.clone(), signal_name, args).await
     }
 
+    /// Call `handler` for each signal named `signal_name`.
+    ///
+    /// This is the callback-based counterpart of [`Proxy::receive_signal`]: the signals are
+    /// received from a task running on the executor of the connection, for as long as the returned
+    /// [`SignalHandler`] is alive. See [`Connection::on_signal`] for details.
+    pub async fn on_signal<'m, M, F>(&self, signal_name: M, handler: F) -> Result<SignalHandler>
+    where
+        M: TryInto<MemberName<'m>>,
+        M::Error: Into<Error>,
+        F: FnMut(Message) + Send + 'static,
+    {
+        let signal_name = signal_name.try_into().map_err(Into::into)?.into_owned();
+        let name = format!("{}.{signal_name}", self.interface());
+        let stream = self.receive_signals(Some(signal_name), &[]).await?;
+
+        Ok(SignalHandler::spawn(
+            self.connection(),
+            stream,
+            handler,
+            &name,
+        ))
+    }
+
     /// Create a stream for all signals emitted by this service.
     pub async fn receive_all_signals(&self) -> Result<SignalStream<'static>> {
         self.receive_signals(None, &[]).await
@@ -1025,9 +1334,45 @@ impl<'a> Proxy<'a> {
 #[derive(Debug, Default)]
 struct PropertyValue {
     value: Option<OwnedValue>,
+    /// When `value` was cached.
+    cached_at: Option<Instant>,
     event: Event,
 }
 
+impl PropertyValue {
+    fn set(&mut self, value: Option<OwnedValue>) {
+        self.cached_at = value.is_some().then(Instant::now);
+        self.value = value;
+    }
+
+    /// Whether the value is cached and not older than `ttl`.
+    fn is_fresh(&self, ttl: Option<Duration>) -> bool {
+        match (&self.value, self.cached_at, ttl) {
+            (None, _, _) => false,
+            (Some(_), Some(cached_at), Some(ttl)) => cached_at.elapsed() < ttl,
+            (Some(_), _, _) => true,
+        }
+    }
+}
+
+/// The state of the properties cache of a proxy.
+///
+/// Use [`Proxy::property_cache_state`] to get the state of a proxy.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum PropertyCacheState {
+    /// Caching is disabled.
+    Disabled,
+    /// The cache is not populated yet.
+    ///
+    /// With [`CacheProperties::Lazily`], population only starts with the first read.
+    Pending,
+    /// The cache is populated and kept up to date.
+    Ready,
+    /// Populating the cache failed.
+    Failed(Error),
+}
+
 /// Flags to use with [`Proxy::call_with_flags`].
 #[bitflags]
 #[repr(u8)]
@@ -1122,6 +1467,7 @@ pub struct SignalStream<'a> {
     stream: Join<MessageStream, Option<MessageStream>>,
     src_unique_name: Option<UniqueName<'static>>,
     signal_name: Option<MemberName<'a>>,
+    lagged: u64,
 }
 
 impl<'a> SignalStream<'a> {
@@ -1130,6 +1476,23 @@ impl<'a> SignalStream<'a> {
         self.signal_name.as_ref()
     }
 
+    /// Set the policy to apply when the queue of this stream is full.
+    ///
+    /// See [`MessageStream::set_overflow_policy`] for details. Since this stream only yields
+    /// signals, the missed ones are only reported through [`SignalStream::lagged`].
+    pub async fn set_overflow_policy(&mut self, policy: OverflowPolicy) {
+        Pin::new(&mut self.stream)
+            .stream_a()
+            .get_mut()
+            .set_overflow_policy(policy)
+            .await;
+    }
+
+    /// The number of messages this stream missed so far because its queue was full.
+    pub fn lagged(&self) -> u64 {
+        self.lagged
+    }
+
     async fn new(
         proxy: Proxy<'_>,
         signal_name: Option<MemberName<'a>>,
@@ -1260,6 +1623,7 @@ impl<'a> SignalStream<'a> {
             stream,
             src_unique_name,
             signal_name,
+            lagged: 0,
         })
     }
 
@@ -1304,8 +1668,8 @@ impl OrderedStream for SignalStream<'_> {
                 cx,
                 before
             )) {
-                PollResult::Item { data, ordering } => {
-                    if let Ok(msg) = data {
+                PollResult::Item { data, ordering } => match data {
+                    Ok(msg) => {
                         if let Ok(true) = this.filter(&msg) {
                             return Poll::Ready(PollResult::Item {
                                 data: msg,
@@ -1313,7 +1677,9 @@ impl OrderedStream for SignalStream<'_> {
                             });
                         }
                     }
-                }
+                    Err(Error::Lagged(n)) => this.lagged += n,
+                    Err(_) => (),
+                },
                 PollResult::Terminated => return Poll::Ready(PollResult::Terminated),
                 PollResult::NoneBefore => return Poll::Ready(PollResult::NoneBefore),
             }
@@ -1361,7 +1727,7 @@ where
     fn inner(&self) -> &Proxy<'c>;
 }
 
-enum Either<L, R> {
+pub(crate) enum Either<L, R> {
     Left(L),
     Right(R),
 }
@@ -1554,4 +1920,152 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    #[timeout(15000)]
+    fn cache_policies() {
+        block_on(test_cache_policies()).unwrap();
+    }
+
+    async fn test_cache_policies() -> Result<()> {
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        #[proxy(
+            gen_blocking = false,
+            default_path = "/org/zbus/CachePolicies",
+            interface = "org.zbus.CachePolicies"
+        )]
+        trait CachePolicies {
+            #[zbus(property(emits_changed_signal = "false"))]
+            fn level(&self) -> Result<u32>;
+        }
+
+        struct Levels(Arc<AtomicU32>);
+
+        #[interface(name = "org.zbus.CachePolicies")]
+        impl Levels {
+            #[zbus(property(emits_changed_signal = "false"))]
+            fn level(&self) -> u32 {
+                self.0.load(Ordering::SeqCst)
+            }
+        }
+
+        let level = Arc::new(AtomicU32::new(1));
+        let service = connection::Builder::session()?
+            .serve_at("/org/zbus/CachePolicies", Levels(level.clone()))?
+            .build()
+            .await?;
+        let dest = service.unique_name().unwrap().to_owned();
+        let conn = Connection::session().await?;
+
+        // Without a policy, the property isn't cached at all.
+        let proxy = CachePoliciesProxy::builder(&conn)
+            .destination(dest.clone())?
+            .cache_properties(CacheProperties::Yes)
+            .build()
+            .await?;
+        assert_eq!(
+            proxy.inner().property_cache_state(),
+            PropertyCacheState::Ready
+        );
+        assert_eq!(proxy.inner().cached_property::<u32>("Level")?, None);
+
+        // Cached values expire.
+        let ttl = CachePoliciesProxy::builder(&conn)
+            .destination(dest.clone())?
+            .cache_properties(CacheProperties::Yes)
+            .property_cache_policy(
+                "Level",
+                PropertyCachePolicy::Ttl(Duration::from_millis(200)),
+            )
+            .build()
+            .await?;
+        assert_eq!(ttl.inner().cached_property::<u32>("Level")?, Some(1));
+        level.store(2, Ordering::SeqCst);
+        assert_eq!(ttl.level().await?, 1);
+        sleep(Duration::from_millis(250)).await;
+        assert_eq!(ttl.inner().cached_property::<u32>("Level")?, None);
+        assert_eq!(ttl.level().await?, 2);
+        assert_eq!(ttl.inner().cached_property::<u32>("Level")?, Some(2));
+
+        // Polled values are kept up to date.
+        let polled = CachePoliciesProxy::builder(&conn)
+            .destination(dest.clone())?
+            .cache_properties(CacheProperties::Yes)
+            .property_cache_policy(
+                "Level",
+                PropertyCachePolicy::Poll(Duration::from_millis(20)),
+            )
+            .build()
+            .await?;
+        assert_eq!(polled.inner().cached_property::<u32>("Level")?, Some(2));
+        level.store(3, Ordering::SeqCst);
+        while polled.inner().cached_property::<u32>("Level")? != Some(3) {
+            sleep(Duration::from_millis(10)).await;
+        }
+
+        // Refreshing explicitly.
+        let refreshed = CachePoliciesProxy::builder(&conn)
+            .destination(dest.clone())?
+            .cache_properties(CacheProperties::Yes)
+            .property_cache_policy("Level", PropertyCachePolicy::Signals)
+            .build()
+            .await?;
+        assert_eq!(refreshed.inner().cached_property::<u32>("Level")?, Some(3));
+        level.store(4, Ordering::SeqCst);
+        assert_eq!(refreshed.inner().cached_property::<u32>("Level")?, Some(3));
+        refreshed.inner().refresh_properties().await?;
+        assert_eq!(refreshed.inner().cached_property::<u32>("Level")?, Some(4));
+
+        // The cache state tells about the population errors.
+        let uncached: Proxy<'_> = Builder::new(&conn)
+            .destination(dest.clone())?
+            .path("/org/zbus/CachePolicies")?
+            .interface("org.zbus.CachePolicies")?
+            .cache_properties(CacheProperties::No)
+            .build()
+            .await?;
+        assert_eq!(
+            uncached.property_cache_state(),
+            PropertyCacheState::Disabled
+        );
+        let unknown: Proxy<'_> = Builder::new(&conn)
+            .destination(dest.clone())?
+            .path("/org/zbus/CachePolicies")?
+            .interface("org.zbus.Unknown")?
+            .build()
+            .await?;
+        assert_eq!(unknown.property_cache_state(), PropertyCacheState::Pending);
+        assert!(unknown.get_property::<u32>("Level").await.is_err());
+        assert!(matches!(
+            unknown.property_cache_state(),
+            PropertyCacheState::Failed(_)
+        ));
+
+        // A successful refresh recovers from these.
+        let late = CachePoliciesProxy::builder(&conn)
+            .destination(dest.clone())?
+            .path("/org/zbus/CachePolicies/Late")?
+            .cache_properties(CacheProperties::Lazily)
+            .property_cache_policy("Level", PropertyCachePolicy::Signals)
+            .build()
+            .await?;
+        assert!(late.level().await.is_err());
+        assert!(matches!(
+            late.inner().property_cache_state(),
+            PropertyCacheState::Failed(_)
+        ));
+        service
+            .object_server()
+            .at("/org/zbus/CachePolicies/Late", Levels(level.clone()))
+            .await?;
+        late.inner().refresh_properties().await?;
+        assert_eq!(
+            late.inner().property_cache_state(),
+            PropertyCacheState::Ready
+        );
+        assert_eq!(late.level().await?, 4);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,242 @@
+use std::{
+    fmt,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    connection::{NameEvent, NameWatcher},
+    fdo,
+    timeout::{sleep, timeout},
+    Error, Result,
+};
+use futures_lite::{future::poll_once, StreamExt};
+use zbus_names::BusName;
+
+use super::Proxy;
+
+type Retryable = Arc<dyn Fn(&fdo::Error) -> bool + Send + Sync>;
+
+/// A policy for retrying method calls that failed with a transient error.
+///
+/// Set a policy on a proxy with [`Builder::retry_policy`] to have all of its method calls retried
+/// when they fail with one of the errors the policy considers retryable. By default, these are
+/// [`fdo::Error::ServiceUnknown`], [`fdo::Error::NoReply`] and [`fdo::Error::Disconnected`] (as
+/// returned by the peer), which typically happen while a service is starting up or restarting. A
+/// local [`Error::Disconnected`], meaning that the connection of the proxy is closed, is not
+/// retried by default since all further attempts would fail the same way.
+///
+/// Between attempts, the policy waits for an exponentially growing delay, starting at
+/// [`RetryPolicy::initial_delay`], and gives up once [`RetryPolicy::max_elapsed`] is reached,
+/// returning the last error. If the destination of the proxy is a well-known name without an
+/// owner, the call is retried as soon as the name is acquired instead (see
+/// [`RetryPolicy::wait_for_owner`]).
+///
+/// Calls sent with the [`MethodFlags::NoReplyExpected`] flag are never retried. Methods that must
+/// not be called more than once can opt out through [`Proxy::call_with_retry_policy`] or, with the
+/// [`macro@crate::proxy`] macro, the `no_retry` method attribute.
+///
+/// # Example
+///
+/// ```no_run
+/// # use std::time::Duration;
+/// use zbus::{fdo, proxy::RetryPolicy};
+///
+/// let policy = RetryPolicy::new()
+///     .retry_if(|e| matches!(e, fdo::Error::ServiceUnknown(_) | fdo::Error::TimedOut(_)))
+///     .initial_delay(Duration::from_millis(50))
+///     .max_elapsed(Duration::from_secs(5));
+/// # drop(policy);
+/// ```
+///
+/// [`Builder::retry_policy`]: crate::proxy::Builder::retry_policy
+/// [`MethodFlags::NoReplyExpected`]: crate::proxy::MethodFlags::NoReplyExpected
+#[derive(Clone)]
+pub struct RetryPolicy {
+    retryable: Retryable,
+    initial_delay: Duration,
+    max_delay: Duration,
+    multiplier: u32,
+    max_elapsed: Duration,
+    wait_for_owner: bool,
+}
+
+impl RetryPolicy {
+    /// Create a policy with the default settings.
+    pub fn new() -> Self {
+        Self {
+            retryable: Arc::new(|e| match e {
+                fdo::Error::ServiceUnknown(_)
+                | fdo::Error::NoReply(_)
+                | fdo::Error::Disconnected(_) => true,
+                // Our own connection is closed, there's no point in trying again.
+                fdo::Error::ZBus(Error::Disconnected(_)) => false,
+                _ => false,
+            }),
+            initial_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(5),
+            multiplier: 2,
+            max_elapsed: Duration::from_secs(30),
+            wait_for_owner: true,
+        }
+    }
+
+    /// Set the errors to retry on.
+    ///
+    /// `retryable` is called with each error a call fails with and returns whether the call should
+    /// be retried. Errors returned by the peer are converted to their [`fdo::Error`] variant, and
+    /// all others are given as [`fdo::Error::ZBus`].
+    #[must_use]
+    pub fn retry_if<F>(mut self, retryable: F) -> Self
+    where
+        F: Fn(&fdo::Error) -> bool + Send + Sync + 'static,
+    {
+        self.retryable = Arc::new(retryable);
+        self
+    }
+
+    /// Set the delay before the first retry (default: 100 ms).
+    #[must_use]
+    pub fn initial_delay(mut self, delay: Duration) -> Self {
+        self.initial_delay = delay;
+        self
+    }
+
+    /// Set the maximum delay between two attempts (default: 5 s).
+    #[must_use]
+    pub fn max_delay(mut self, delay: Duration) -> Self {
+        self.max_delay = delay;
+        self
+    }
+
+    /// Set the factor the delay is multiplied by after each retry (default: 2).
+    ///
+    /// Use `1` for a constant delay.
+    #[must_use]
+    pub fn multiplier(mut self, multiplier: u32) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+
+    /// Set the time after which to give up retrying, since the first attempt (default: 30 s).
+    #[must_use]
+    pub fn max_elapsed(mut self, max_elapsed: Duration) -> Self {
+        self.max_elapsed = max_elapsed;
+        self
+    }
+
+    /// Whether to wait for the destination name to be owned before retrying (default: `true`).
+    ///
+    /// When enabled and the destination is a well-known name without an owner, the call is retried
+    /// as soon as the name is acquired, rather than after the backoff delay.
+    #[must_use]
+    pub fn wait_for_owner(mut self, wait: bool) -> Self {
+        self.wait_for_owner = wait;
+        self
+    }
+
+    /// Whether a call failing with `error` should be retried.
+    pub fn is_retryable(&self, error: &Error) -> bool {
+        match error {
+            Error::FDO(e) => (self.retryable)(e),
+            e => (self.retryable)(&fdo::Error::from(e.clone())),
+        }
+    }
+
+    /// Run `attempt` until it succeeds, fails with a non-retryable error, or the policy gives up.
+    pub(crate) async fn run<F, Fut, T>(&self, proxy: &Proxy<'_>, mut attempt: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = Result<T>>,
+    {
+        let start = Instant::now();
+        let mut delay = self.initial_delay;
+        // Created on the first retry, if needed, and kept for the following ones.
+        let mut watcher = None;
+
+        loop {
+            let error = match attempt().await {
+                Err(e) if self.is_retryable(&e) => e,
+                res => return res,
+            };
+            let remaining = self.max_elapsed.saturating_sub(start.elapsed());
+            if remaining.is_zero() {
+                return Err(error);
+            }
+
+            if self.wait_for_owner
+                && self
+                    .wait_for_destination(proxy, &mut watcher, remaining)
+                    .await?
+            {
+                continue;
+            }
+            let remaining = self.max_elapsed.saturating_sub(start.elapsed());
+            if remaining.is_zero() {
+                return Err(error);
+            }
+            // The last attempt is made right at the deadline.
+            sleep(delay.min(remaining)).await;
+            delay = delay.saturating_mul(self.multiplier).min(self.max_delay);
+        }
+    }
+
+    /// Wait for the destination of `proxy` to be owned, if it's a well-known name without an owner.
+    ///
+    /// Returns whether the name was acquired in the meantime.
+    async fn wait_for_destination(
+        &self,
+        proxy: &Proxy<'_>,
+        watcher: &mut Option<NameWatcher>,
+        remaining: Duration,
+    ) -> Result<bool> {
+        let conn = proxy.connection();
+        if !conn.is_bus() || !matches!(proxy.destination(), BusName::WellKnown(_)) {
+            return Ok(false);
+        }
+
+        let watcher = match watcher {
+            Some(watcher) => watcher,
+            // Boxed since the watcher makes method calls itself, through proxies.
+            None => watcher.insert(Box::pin(conn.watch_name(proxy.destination())).await?),
+        };
+        // Catch up with the changes since the last attempt.
+        while let Some(Some(_)) = poll_once(watcher.next()).await {}
+        if watcher.owner().is_some() {
+            return Ok(false);
+        }
+        let appeared = timeout(
+            async {
+                while let Some(event) = watcher.next().await {
+                    if let NameEvent::Appeared(_) = event {
+                        return Ok(true);
+                    }
+                }
+
+                Ok(false)
+            },
+            remaining,
+        )
+        .await;
+
+        Ok(appeared.unwrap_or(false))
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for RetryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("initial_delay", &self.initial_delay)
+            .field("max_delay", &self.max_delay)
+            .field("multiplier", &self.multiplier)
+            .field("max_elapsed", &self.max_elapsed)
+            .field("wait_for_owner", &self.wait_for_owner)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,359 @@
+//! Integration with the [`tower`] ecosystem.
+//!
+//! This module provides:
+//!
+//! * [`MethodCallService`], a [`Service`] that sends method calls over a [`Connection`] and
+//!   resolves to their replies. It allows wrapping client calls in `tower` middleware.
+//! * [`Dispatch`], the [`Service`] that calls a method of an interface served by an
+//!   [`ObjectServer`](crate::ObjectServer). Incoming method calls can be passed through `tower`
+//!   middleware before they reach the interface, through
+//!   [`ObjectServer::set_dispatch_layer`](crate::ObjectServer::set_dispatch_layer).
+//!
+//! Requires the `tower` feature to be enabled.
+//!
+//! [`tower`]: https://docs.rs/tower
+
+use std::{
+    error::Error as StdError,
+    fmt,
+    future::{poll_fn, Future},
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+pub use tower_layer::Layer;
+pub use tower_service::Service;
+
+use crate::{
+    async_lock::RwLock,
+    fdo,
+    message::{Message, Type},
+    object_server::Interface,
+    timeout::timeout,
+    Connection, Error, Result,
+};
+
+type BoxFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
+
+/// A [`Service`] sending method calls over a [`Connection`].
+///
+/// The requests are method call messages and the responses are their replies. Error replies
+/// result in an [`Error::MethodError`]. The [method timeout](Connection::method_timeout) of the
+/// connection, if any, applies.
+///
+/// Since a reply is required, method calls with the `NoReplyExpected` flag are refused with an
+/// [`Error::Unsupported`] error.
+///
+/// # Example
+///
+/// ```no_run
+/// # use std::error::Error;
+/// use zbus::{
+///     message::Message,
+///     tower::{MethodCallService, Service},
+///     Connection,
+/// };
+///
+/// # async_io::block_on(async {
+/// let connection = Connection::session().await?;
+/// let mut service = MethodCallService::new(connection);
+///
+/// let call = Message::method_call("/org/freedesktop/DBus", "GetId")?
+///     .destination("org.freedesktop.DBus")?
+///     .interface("org.freedesktop.DBus")?
+///     .build(&())?;
+/// let reply = service.call(call).await?;
+/// let id: String = reply.body().deserialize()?;
+/// println!("Bus ID: {id}");
+/// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+/// # })?;
+/// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct MethodCallService {
+    conn: Connection,
+}
+
+impl MethodCallService {
+    /// Create a service sending method calls over `conn`.
+    pub fn new(conn: Connection) -> Self {
+        Self { conn }
+    }
+
+    /// The connection the method calls are sent over.
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+}
+
+impl Service<Message> for MethodCallService {
+    type Response = Message;
+    type Error = Error;
+    type Future = BoxFuture<Result<Message>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, msg: Message) -> Self::Future {
+        let conn = self.conn.clone();
+
+        Box::pin(async move {
+            if msg.message_type() != Type::MethodCall {
+                return Err(Error::InvalidField);
+            }
+            let method = conn
+                .send_method_call(&msg)
+                .await?
+                .ok_or(Error::Unsupported)?;
+
+            match conn.method_timeout() {
+                Some(tout) => timeout(method, tout).await,
+                None => method.await,
+            }
+        })
+    }
+}
+
+/// A method call to be dispatched to an interface, by the [`Dispatch`] service.
+pub struct DispatchRequest {
+    message: Message,
+    connection: Connection,
+    iface: Arc<RwLock<dyn Interface>>,
+}
+
+impl DispatchRequest {
+    pub(crate) fn new(
+        message: Message,
+        connection: Connection,
+        iface: Arc<RwLock<dyn Interface>>,
+    ) -> Self {
+        Self {
+            message,
+            connection,
+            iface,
+        }
+    }
+
+    /// The method call message.
+    pub fn message(&self) -> &Message {
+        &self.message
+    }
+
+    /// The connection the method call was received on.
+    pub fn connection(&self) -> &Connection {
+        &self.connection
+    }
+}
+
+impl fmt::Debug for DispatchRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DispatchRequest")
+            .field("message", &self.message)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The [`Service`] calling interface methods, at the bottom of the dispatch middleware stack.
+///
+/// The method replies are sent by the service itself. If the service fails, the error is sent as
+/// the reply instead.
+///
+/// See [`ObjectServer::set_dispatch_layer`](crate::ObjectServer::set_dispatch_layer) for details.
+#[derive(Debug, Clone, Default)]
+pub struct Dispatch {
+    _private: (),
+}
+
+impl Service<DispatchRequest> for Dispatch {
+    type Response = ();
+    type Error = fdo::Error;
+    type Future = BoxFuture<fdo::Result<()>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<fdo::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: DispatchRequest) -> Self::Future {
+        Box::pin(async move {
+            let DispatchRequest {
+                message,
+                connection,
+                iface,
+            } = req;
+            let server = connection.object_server();
+            let hdr = message.header();
+
+            server
+                .dispatch_call_to_iface_direct(iface, &connection, &message, &hdr)
+                .await
+        })
+    }
+}
+
+type DispatchFn = dyn Fn(DispatchRequest) -> BoxFuture<fdo::Result<()>> + Send + Sync;
+
+/// A type-erased dispatch middleware stack.
+#[derive(Clone)]
+pub(crate) struct DispatchStack(Arc<DispatchFn>);
+
+impl DispatchStack {
+    /// Wrap [`Dispatch`] in `layer`.
+    pub fn new<L>(layer: L) -> Self
+    where
+        L: Layer<Dispatch>,
+        L::Service: Service<DispatchRequest, Response = ()> + Clone + Send + Sync + 'static,
+        <L::Service as Service<DispatchRequest>>::Error: Into<Box<dyn StdError + Send + Sync>>,
+        <L::Service as Service<DispatchRequest>>::Future: Send + 'static,
+    {
+        let service = layer.layer(Dispatch::default());
+
+        Self(Arc::new(move |req| {
+            let mut service = service.clone();
+
+            Box::pin(async move {
+                poll_fn(|cx| service.poll_ready(cx))
+                    .await
+                    .map_err(into_fdo_error)?;
+
+                service.call(req).await.map_err(into_fdo_error)
+            })
+        }))
+    }
+
+    /// Pass `req` through the stack.
+    pub async fn dispatch(&self, req: DispatchRequest) -> fdo::Result<()> {
+        (self.0)(req).await
+    }
+}
+
+impl fmt::Debug for DispatchStack {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DispatchStack").finish_non_exhaustive()
+    }
+}
+
+fn into_fdo_error<E>(e: E) -> fdo::Error
+where
+    E: Into<Box<dyn StdError + Send + Sync>>,
+{
+    match e.into().downcast::<fdo::Error>() {
+        Ok(e) => *e,
+        Err(e) => fdo::Error::Failed(e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use ntest::timeout;
+    use test_log::test;
+
+    use super::*;
+    use crate::interface;
+
+    struct Greeter;
+
+    #[interface(name = "org.zbus.Greeter")]
+    impl Greeter {
+        fn greet(&self, name: &str) -> String {
+            format!("Hello {name}!")
+        }
+
+        fn forbidden(&self) {}
+    }
+
+    /// Counts the calls and refuses the ones to `Forbidden`.
+    #[derive(Clone)]
+    struct Guard<S> {
+        inner: S,
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl<S> Service<DispatchRequest> for Guard<S>
+    where
+        S: Service<DispatchRequest, Response = (), Error = fdo::Error>,
+        S::Future: Send + 'static,
+    {
+        type Response = ();
+        type Error = fdo::Error;
+        type Future = BoxFuture<fdo::Result<()>>;
+
+        fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<fdo::Result<()>> {
+            self.inner.poll_ready(cx)
+        }
+
+        fn call(&mut self, req: DispatchRequest) -> Self::Future {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if req.message().header().member().unwrap() == "Forbidden" {
+                return Box::pin(async { Err(fdo::Error::AccessDenied("Forbidden".into())) });
+            }
+
+            Box::pin(self.inner.call(req))
+        }
+    }
+
+    struct GuardLayer {
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl<S> Layer<S> for GuardLayer {
+        type Service = Guard<S>;
+
+        fn layer(&self, inner: S) -> Guard<S> {
+            Guard {
+                inner,
+                calls: self.calls.clone(),
+            }
+        }
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn dispatch_layer() {
+        crate::block_on(dispatch_layer_async());
+    }
+
+    async fn dispatch_layer_async() {
+        let service = crate::connection::Builder::session()
+            .unwrap()
+            .serve_at("/org/zbus/Greeter", Greeter)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+        let calls = Arc::new(AtomicUsize::new(0));
+        service.object_server().set_dispatch_layer(GuardLayer {
+            calls: calls.clone(),
+        });
+
+        let client = Connection::session().await.unwrap();
+        let mut client_service = MethodCallService::new(client);
+        let call = |method, body: &str| {
+            let builder = Message::method_call("/org/zbus/Greeter", method)
+                .unwrap()
+                .destination(service.unique_name().unwrap())
+                .unwrap()
+                .interface("org.zbus.Greeter")
+                .unwrap();
+            if body.is_empty() {
+                builder.build(&()).unwrap()
+            } else {
+                builder.build(&(body,)).unwrap()
+            }
+        };
+
+        let reply = client_service.call(call("Greet", "zbus")).await.unwrap();
+        assert_eq!(reply.body().deserialize::<String>().unwrap(), "Hello zbus!");
+
+        match client_service.call(call("Forbidden", "")).await {
+            Err(Error::MethodError(name, _, _)) => {
+                assert_eq!(name, "org.freedesktop.DBus.Error.AccessDenied")
+            }
+            res => panic!("unexpected result: {res:?}"),
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,140 @@
+use futures_util::StreamExt;
+use ntest::timeout;
+use test_log::test;
+use zbus::{block_on, fdo, FieldProperties, Proxy, Result};
+
+#[derive(FieldProperties)]
+struct Light {
+    #[zbus(property)]
+    model: String,
+    #[zbus(property(access = "readwrite"), validate = "check_brightness")]
+    brightness: u8,
+    #[zbus(property(access = "readwrite"), name = "RGB")]
+    color: (u8, u8, u8),
+    power_draw: u32,
+}
+
+fn check_brightness(brightness: &u8) -> fdo::Result<()> {
+    if *brightness > 100 {
+        return Err(fdo::Error::InvalidArgs("Brightness out of range".into()));
+    }
+
+    Ok(())
+}
+
+#[zbus::interface(name = "org.freedesktop.zbus.Light", field_properties)]
+impl Light {
+    #[zbus(property)]
+    fn power_draw(&self) -> u32 {
+        self.power_draw
+    }
+}
+
+#[test]
+#[timeout(15000)]
+fn field_properties() {
+    block_on(test_field_properties()).unwrap();
+}
+
+async fn test_field_properties() -> Result<()> {
+    let path = "/org/freedesktop/zbus/Light";
+    let light = Light {
+        model: "Lamp".into(),
+        brightness: 50,
+        color: (255, 255, 255),
+        power_draw: 7,
+    };
+    let service = zbus::connection::Builder::session()?
+        .serve_at(path, light)?
+        .build()
+        .await?;
+    let dest = service.unique_name().unwrap().to_owned();
+
+    let client_conn = zbus::Connection::session().await?;
+    let proxy = Proxy::new(&client_conn, &dest, path, "org.freedesktop.zbus.Light").await?;
+    assert_eq!(proxy.get_property::<String>("Model").await?, "Lamp");
+    assert_eq!(proxy.get_property::<u8>("Brightness").await?, 50);
+    assert_eq!(proxy.get_property::<u32>("PowerDraw").await?, 7);
+
+    let props = fdo::PropertiesProxy::builder(&client_conn)
+        .destination(&dest)?
+        .path(path)?
+        .build()
+        .await?;
+    let all = props
+        .get_all("org.freedesktop.zbus.Light".try_into()?)
+        .await?;
+    assert_eq!(all.len(), 4);
+    assert_eq!(u8::try_from(&all["Brightness"])?, 50);
+    assert!(all.contains_key("RGB"));
+
+    let xml = zbus::fdo::IntrospectableProxy::builder(&client_conn)
+        .destination(&dest)?
+        .path(path)?
+        .build()
+        .await?
+        .introspect()
+        .await?;
+    assert!(xml.contains(r#"<property name="Model" type="s" access="read"/>"#));
+    assert!(xml.contains(r#"<property name="RGB" type="(yyy)" access="readwrite"/>"#));
+    assert!(!xml.contains("power_draw"));
+
+    // Setting from the bus emits the change signal, and is subject to access and validation.
+    let mut changes = props.receive_properties_changed().await?;
+    proxy.set_property("Brightness", 80u8).await?;
+    let signal = changes.next().await.unwrap();
+    let args = signal.args()?;
+    assert_eq!(u8::try_from(&args.changed_properties()["Brightness"])?, 80);
+    match proxy.set_property("Brightness", 120u8).await {
+        Err(fdo::Error::InvalidArgs(_)) => (),
+        res => panic!("unexpected result: {res:?}"),
+    }
+    match proxy.set_property("Model", "Bulb").await {
+        Err(fdo::Error::PropertyReadOnly(_)) => (),
+        res => panic!("unexpected result: {res:?}"),
+    }
+    assert_eq!(proxy.get_property::<u8>("Brightness").await?, 80);
+
+    // And so does setting from the service side, including for read-only properties.
+    let iface = service.object_server().interface::<_, Light>(path).await?;
+    // Setting the current value is a no-op.
+    iface.set_model("Lamp".into()).await?;
+    iface.set_model("Bulb".into()).await?;
+    let signal = changes.next().await.unwrap();
+    let args = signal.args()?;
+    assert_eq!(
+        <&str>::try_from(&args.changed_properties()["Model"])?,
+        "Bulb"
+    );
+    assert!(iface.set_brightness(101).await.is_err());
+    iface.set_color((0, 0, 255)).await?;
+    let signal = changes.next().await.unwrap();
+    let args = signal.args()?;
+    assert!(args.changed_properties().contains_key("RGB"));
+    assert_eq!(iface.get().await.color, (0, 0, 255));
+
+    // Assigning the fields through the tracking guard signals all the changes at once.
+    let mut guard = iface.get_mut_tracked().await;
+    guard.brightness = 30;
+    guard.color = (255, 0, 0);
+    guard.power_draw = 9;
+    guard.commit().await?;
+    let signal = changes.next().await.unwrap();
+    let args = signal.args()?;
+    let changed = args.changed_properties();
+    assert_eq!(changed.len(), 2);
+    assert_eq!(u8::try_from(&changed["Brightness"])?, 30);
+    assert!(changed.contains_key("RGB"));
+
+    // Dropping the guard also does.
+    iface.get_mut_tracked().await.model = "Tube".into();
+    let signal = changes.next().await.unwrap();
+    let args = signal.args()?;
+    assert_eq!(
+        <&str>::try_from(&args.changed_properties()["Model"])?,
+        "Tube"
+    );
+    assert_eq!(iface.get().await.model, "Tube");
+
+    Ok(())
+}
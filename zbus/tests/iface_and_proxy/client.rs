@@ -5,6 +5,7 @@ use tracing::{debug, instrument};
 use zbus::{
     fdo::{ObjectManagerProxy, PropertiesProxy},
     message,
+    names::InterfaceName,
     proxy::CacheProperties,
     Connection, Error, Message, MessageStream,
 };
@@ -123,6 +124,30 @@ pub async fn my_iface_test(conn: Connection, event: Event) -> zbus::Result<u32>
     proxy.set_count2(1).await?;
     assert_eq!(proxy.count().await?, 1);
 
+    // Test that values of the wrong type are rejected before reaching the setters.
+    let iface_name = InterfaceName::from_static_str_unchecked("org.freedesktop.MyIface");
+    let err = props_proxy
+        .set(iface_name.clone(), "Count", Value::from("8888"))
+        .await
+        .unwrap_err();
+    match err {
+        zbus::fdo::Error::InvalidArgs(msg) => {
+            assert!(msg.contains("expected signature 'u', got 's'"), "{msg}")
+        }
+        e => panic!("unexpected error: {e}"),
+    }
+    assert_eq!(proxy.count().await?, 1);
+    let err = props_proxy
+        .set(iface_name, "OptionalProperty", Value::from(7u32))
+        .await
+        .unwrap_err();
+    match err {
+        zbus::fdo::Error::PropertyReadOnly(msg) => {
+            assert!(msg.contains("'OptionalProperty'"), "{msg}")
+        }
+        e => panic!("unexpected error: {e}"),
+    }
+
     proxy.test_header().await?;
     proxy
         .test_single_struct_arg(ArgStructTest {
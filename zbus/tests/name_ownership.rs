@@ -0,0 +1,66 @@
+use enumflags2::BitFlags;
+use futures_util::StreamExt;
+use ntest::timeout;
+use test_log::test;
+use zbus::{
+    block_on,
+    connection::{NameOwnership, NameOwnershipEvent},
+    fdo::RequestNameFlags,
+    Connection, Result,
+};
+
+const NAME: &str = "org.freedesktop.zbus.NameOwnership";
+
+async fn next(ownership: &mut NameOwnership) -> NameOwnershipEvent {
+    ownership.next().await.unwrap()
+}
+
+#[test]
+#[timeout(15000)]
+fn name_ownership() {
+    block_on(test_name_ownership()).unwrap();
+}
+
+async fn test_name_ownership() -> Result<()> {
+    let conn1 = Connection::session().await?;
+    let conn2 = Connection::session().await?;
+    let conn3 = Connection::session().await?;
+
+    let mut first = conn1
+        .own_name(NAME, RequestNameFlags::AllowReplacement.into())
+        .await?;
+    assert_eq!(next(&mut first).await, NameOwnershipEvent::Acquired);
+    assert!(first.is_owner());
+    assert_eq!(first.queue_position().await?, Some(0));
+
+    // Taking over the name puts the previous owner back in the queue.
+    let mut second = conn2
+        .own_name(NAME, RequestNameFlags::ReplaceExisting.into())
+        .await?;
+    assert_eq!(next(&mut second).await, NameOwnershipEvent::Acquired);
+    assert_eq!(next(&mut first).await, NameOwnershipEvent::Lost);
+    assert!(!first.is_owner());
+    assert_eq!(first.queue_position().await?, Some(1));
+
+    // And it gets the name back once released.
+    assert!(second.release().await?);
+    assert_eq!(next(&mut first).await, NameOwnershipEvent::Reacquired);
+
+    let mut third = conn3.own_name(NAME, BitFlags::empty()).await?;
+    assert_eq!(next(&mut third).await, NameOwnershipEvent::Queued);
+    assert_eq!(third.queue_position().await?, Some(1));
+    // Dropping the guard releases the name.
+    drop(first);
+    assert_eq!(next(&mut third).await, NameOwnershipEvent::Acquired);
+
+    // The name can't be queued for.
+    match conn1
+        .own_name(NAME, RequestNameFlags::DoNotQueue.into())
+        .await
+    {
+        Err(zbus::Error::NameTaken) => (),
+        res => panic!("unexpected result: {res:?}"),
+    }
+
+    Ok(())
+}
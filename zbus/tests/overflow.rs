@@ -0,0 +1,97 @@
+use futures_util::StreamExt;
+use ntest::timeout;
+use test_log::test;
+use zbus::{
+    block_on, message::Type, AsyncDrop, Connection, Error, MatchRule, MessageStream,
+    OverflowPolicy, Result,
+};
+
+const INTERFACE: &str = "org.freedesktop.zbus.Overflow";
+
+async fn emit(conn: &Connection, member: &str, n: u32) -> Result<()> {
+    conn.emit_signal(
+        None::<()>,
+        "/org/freedesktop/zbus/Overflow",
+        INTERFACE,
+        member,
+        &n,
+    )
+    .await
+}
+
+/// Emit `count` `Tick` signals, and return once `client` received them all.
+async fn flood(service: &Connection, client: &Connection, count: u32) -> Result<()> {
+    let rule = MatchRule::builder()
+        .msg_type(Type::Signal)
+        .interface(INTERFACE)?
+        .member("Done")?
+        .build();
+    let mut done = MessageStream::for_match_rule(rule, client, None).await?;
+    for n in 0..count {
+        emit(service, "Tick", n).await?;
+    }
+    // Signals from the same sender are delivered in order.
+    emit(service, "Done", 0).await?;
+    done.next().await.unwrap()?;
+
+    Ok(())
+}
+
+async fn ticks(client: &Connection, policy: OverflowPolicy) -> Result<MessageStream> {
+    let rule = MatchRule::builder()
+        .msg_type(Type::Signal)
+        .interface(INTERFACE)?
+        .member("Tick")?
+        .build();
+    let mut stream = MessageStream::for_match_rule(rule, client, Some(2)).await?;
+    assert_eq!(stream.overflow_policy(), OverflowPolicy::Block);
+    stream.set_overflow_policy(policy).await;
+    assert_eq!(stream.overflow_policy(), policy);
+
+    Ok(stream)
+}
+
+async fn next_tick(stream: &mut MessageStream) -> Result<u32> {
+    stream.next().await.unwrap()?.body().deserialize()
+}
+
+#[test]
+#[timeout(15000)]
+fn overflow_policy() {
+    block_on(test_overflow_policy()).unwrap();
+}
+
+async fn test_overflow_policy() -> Result<()> {
+    let service = Connection::session().await?;
+    let client = Connection::session().await?;
+
+    let mut stream = ticks(&client, OverflowPolicy::DropOldest).await?;
+    // The policy doesn't apply to the other streams for the same rule.
+    let rule = stream.match_rule().unwrap().to_owned();
+    let mut other = MessageStream::for_match_rule(rule, &client, Some(8)).await?;
+    assert_eq!(other.overflow_policy(), OverflowPolicy::Block);
+    flood(&service, &client, 5).await?;
+    assert!(matches!(stream.next().await, Some(Err(Error::Lagged(3)))));
+    assert_eq!(next_tick(&mut stream).await?, 3);
+    assert_eq!(next_tick(&mut stream).await?, 4);
+    assert_eq!(stream.lagged(), 3);
+    for n in 0..5 {
+        assert_eq!(next_tick(&mut other).await?, n);
+    }
+    assert_eq!(other.lagged(), 0);
+    // Ensure the queue shared by the streams of the same rule goes away with them.
+    other.async_drop().await;
+    stream.async_drop().await;
+
+    let mut stream = ticks(&client, OverflowPolicy::DropNewest).await?;
+    flood(&service, &client, 5).await?;
+    assert!(matches!(stream.next().await, Some(Err(Error::Lagged(3)))));
+    assert_eq!(next_tick(&mut stream).await?, 0);
+    assert_eq!(next_tick(&mut stream).await?, 1);
+    // The queue has room again.
+    flood(&service, &client, 1).await?;
+    assert_eq!(next_tick(&mut stream).await?, 0);
+    assert_eq!(stream.lagged(), 3);
+
+    Ok(())
+}
@@ -0,0 +1,121 @@
+use futures_util::StreamExt;
+use ntest::timeout;
+use test_log::test;
+use zbus::{block_on, fdo, Properties, Proxy, Result};
+
+struct Device {
+    count: u32,
+}
+
+#[zbus::interface(name = "org.freedesktop.zbus.Device")]
+impl Device {
+    #[zbus(property)]
+    fn version(&self) -> &str {
+        "1.0"
+    }
+
+    #[zbus(property)]
+    fn count(&self) -> u32 {
+        self.count
+    }
+
+    #[zbus(property)]
+    fn set_count(&mut self, count: u32) {
+        self.count = count;
+    }
+
+    #[zbus(property(emits_changed_signal = "const"), name = "HWAddress")]
+    fn hw_address(&self) -> Vec<u8> {
+        vec![0, 1, 2]
+    }
+}
+
+struct Battery {
+    level: u8,
+}
+
+#[zbus::interface(name = "org.freedesktop.zbus.Battery")]
+impl Battery {
+    #[zbus(property)]
+    fn level(&self) -> u8 {
+        self.level
+    }
+
+    #[zbus(property)]
+    fn set_level(&mut self, level: u8) {
+        self.level = level;
+    }
+}
+
+#[derive(Debug, PartialEq, Properties)]
+#[zbus(interface = "org.freedesktop.zbus.Device")]
+struct DeviceProperties {
+    version: String,
+    count: u32,
+    #[zbus(rename = "HWAddress")]
+    address: Vec<u8>,
+    #[zbus(default)]
+    label: String,
+}
+
+#[derive(Debug, Properties)]
+#[zbus(rename_all = "snake_case")]
+struct Unrelated {
+    #[allow(dead_code)]
+    no_such_property: u32,
+}
+
+#[test]
+#[timeout(15000)]
+fn get_all_as() {
+    block_on(test_get_all_as()).unwrap();
+}
+
+async fn test_get_all_as() -> Result<()> {
+    let path = "/org/freedesktop/zbus/Device";
+    let service = zbus::connection::Builder::session()?
+        .serve_at(path, Device { count: 1 })?
+        .serve_at(path, Battery { level: 50 })?
+        .build()
+        .await?;
+    let dest = service.unique_name().unwrap().to_owned();
+
+    let client_conn = zbus::Connection::session().await?;
+    let proxy = Proxy::new(&client_conn, &dest, path, "org.freedesktop.zbus.Device").await?;
+    let mut props: DeviceProperties = proxy.get_all_as().await?;
+    assert_eq!(
+        props,
+        DeviceProperties {
+            version: "1.0".into(),
+            count: 1,
+            address: vec![0, 1, 2],
+            label: String::new(),
+        }
+    );
+
+    match proxy.get_all_as::<Unrelated>().await {
+        Err(zbus::Error::FDO(e)) => assert!(matches!(*e, fdo::Error::UnknownProperty(_))),
+        res => panic!("unexpected result: {res:?}"),
+    }
+
+    // Keeping the snapshot up to date.
+    let mut changes = fdo::PropertiesProxy::builder(&client_conn)
+        .destination(&dest)?
+        .path(path)?
+        .build()
+        .await?
+        .receive_properties_changed()
+        .await?;
+    let battery = Proxy::new(&client_conn, &dest, path, "org.freedesktop.zbus.Battery").await?;
+    battery.set_property("Level", 40u8).await?;
+    proxy.set_property("Count", 7u32).await?;
+
+    let signal = changes.next().await.unwrap();
+    assert!(!props.update_from_signal(&signal)?);
+    assert_eq!(props.count, 1);
+    let signal = changes.next().await.unwrap();
+    assert!(props.update_from_signal(&signal)?);
+    assert_eq!(props.count, 7);
+
+    Ok(())
+}
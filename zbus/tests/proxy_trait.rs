@@ -0,0 +1,128 @@
+use futures_util::StreamExt;
+use ntest::timeout;
+use test_log::test;
+use zbus::{block_on, fdo, Result};
+
+#[zbus::proxy(
+    interface = "org.freedesktop.zbus.Thermostat",
+    default_path = "/org/freedesktop/zbus/Thermostat",
+    gen_server = true,
+    gen_mock = true
+)]
+trait Thermostat {
+    fn set_target(&self, celsius: f64) -> zbus::Result<bool>;
+
+    #[zbus(no_reply)]
+    fn reset(&self) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn temperature(&self) -> zbus::Result<f64>;
+
+    #[zbus(signal)]
+    fn overheated(&self, celsius: f64) -> zbus::Result<()>;
+}
+
+/// The logic under test, only depending on the interface.
+async fn cool_down(thermostat: &dyn Thermostat) -> zbus::Result<bool> {
+    let temperature = thermostat.temperature().await?;
+
+    thermostat.set_target(temperature - 1.0).await
+}
+
+#[test]
+#[timeout(15000)]
+fn mock() {
+    block_on(test_mock()).unwrap();
+}
+
+async fn test_mock() -> Result<()> {
+    let mock = ThermostatMock::new();
+    mock.inner()
+        .on_call("SetTarget", |(celsius,): (f64,)| Ok(celsius > 10.0))
+        .set_property("Temperature", 20.0)?;
+
+    assert!(cool_down(&mock).await?);
+    mock.inner().set_property("Temperature", 5.0)?;
+    assert!(!cool_down(&mock).await?);
+
+    let calls = mock.inner().calls_to("SetTarget");
+    assert_eq!(calls.len(), 2);
+    assert_eq!(calls[0].body().deserialize::<f64>()?, 19.0);
+    assert_eq!(calls[1].body().deserialize::<f64>()?, 4.0);
+
+    // Calls to methods without a programmed response fail, unless no reply is expected.
+    mock.reset().await?;
+    assert_eq!(mock.inner().calls().len(), 3);
+    mock.inner().clear_calls();
+    let unprogrammed = ThermostatMock::new();
+    match unprogrammed.set_target(1.0).await {
+        Err(zbus::Error::FDO(e)) => assert!(matches!(*e, fdo::Error::UnknownMethod(_))),
+        res => panic!("unexpected result: {res:?}"),
+    }
+    assert!(unprogrammed.temperature().await.is_err());
+
+    // Signals.
+    let mut overheated = mock.receive_overheated().await?;
+    mock.inner().emit_signal("Overheated", &(90.0,))?;
+    let signal = overheated.next().await.unwrap();
+    assert_eq!(signal.args()?.celsius, 90.0);
+
+    Ok(())
+}
+
+struct MyThermostat;
+
+impl ThermostatServer for MyThermostat {
+    async fn set_target(&self, celsius: f64) -> fdo::Result<bool> {
+        Ok(celsius > 10.0)
+    }
+
+    async fn reset(&self) -> fdo::Result<()> {
+        Ok(())
+    }
+
+    async fn temperature(&self) -> fdo::Result<f64> {
+        Ok(21.5)
+    }
+}
+
+#[test]
+#[timeout(15000)]
+fn proxies() {
+    block_on(test_proxies()).unwrap();
+}
+
+async fn test_proxies() -> Result<()> {
+    let service = zbus::connection::Builder::session()?
+        .serve_at(
+            "/org/freedesktop/zbus/Thermostat",
+            ThermostatInterface(MyThermostat),
+        )?
+        .build()
+        .await?;
+
+    let client_conn = zbus::Connection::session().await?;
+    let proxy =
+        ThermostatProxy::new(&client_conn, service.unique_name().unwrap().to_owned()).await?;
+    assert!(cool_down(&proxy).await?);
+
+    #[cfg(feature = "blocking-api")]
+    let blocking_proxy = ThermostatProxyBlocking::from(proxy.inner().clone());
+    let thermostats: Vec<Box<dyn Thermostat>> = vec![
+        Box::new(proxy),
+        #[cfg(feature = "blocking-api")]
+        Box::new(blocking_proxy),
+    ];
+    for thermostat in &thermostats {
+        let mut overheated = thermostat.receive_overheated().await?;
+        let emitter =
+            zbus::object_server::SignalEmitter::new(&service, "/org/freedesktop/zbus/Thermostat")?;
+        ThermostatInterface::<MyThermostat>::overheated(&emitter, 95.0).await?;
+        let signal = overheated.next().await.unwrap();
+        assert_eq!(signal.args()?.celsius, 95.0);
+
+        assert_eq!(thermostat.temperature().await?, 21.5);
+    }
+
+    Ok(())
+}
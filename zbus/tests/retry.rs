@@ -0,0 +1,122 @@
+use std::time::Duration;
+
+use futures_util::StreamExt;
+use ntest::timeout;
+use zbus::{
+    connection::{DisconnectReason, NameEvent},
+    proxy::RetryPolicy,
+    Result,
+};
+
+#[zbus::proxy(
+    interface = "org.freedesktop.zbus.Retry",
+    default_service = "org.freedesktop.zbus.RetryTest",
+    default_path = "/org/freedesktop/zbus/Retry"
+)]
+trait Retry {
+    fn ping(&self) -> zbus::Result<u32>;
+
+    #[zbus(no_retry)]
+    fn launch(&self) -> zbus::Result<u32>;
+
+    #[zbus(retry_policy = "impatient")]
+    fn poke(&self) -> zbus::Result<u32>;
+}
+
+fn impatient() -> RetryPolicy {
+    RetryPolicy::new().max_elapsed(Duration::ZERO)
+}
+
+struct Service;
+
+#[zbus::interface(name = "org.freedesktop.zbus.Retry")]
+impl Service {
+    fn ping(&self) -> u32 {
+        1
+    }
+
+    fn launch(&self) -> u32 {
+        2
+    }
+
+    fn poke(&self) -> u32 {
+        3
+    }
+}
+
+fn assert_service_unknown<T: std::fmt::Debug>(res: Result<T>) {
+    match res {
+        Err(zbus::Error::MethodError(name, _, _)) => {
+            assert_eq!(name.as_str(), "org.freedesktop.DBus.Error.ServiceUnknown")
+        }
+        res => panic!("unexpected result: {res:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[timeout(15000)]
+async fn retry_policy() -> Result<()> {
+    let client_conn = zbus::Connection::session().await?;
+    let proxy = RetryProxy::builder(&client_conn)
+        .retry_policy(
+            RetryPolicy::new()
+                .initial_delay(Duration::from_millis(10))
+                .max_elapsed(Duration::from_secs(10)),
+        )
+        .build()
+        .await?;
+    assert!(proxy.inner().retry_policy().is_some());
+
+    // Opted out methods fail right away.
+    assert_service_unknown(proxy.launch().await);
+    assert_service_unknown(proxy.poke().await);
+
+    // The call is retried until the service shows up.
+    let service = tokio::spawn(async {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        zbus::connection::Builder::session()?
+            .name("org.freedesktop.zbus.RetryTest")?
+            .serve_at("/org/freedesktop/zbus/Retry", Service)?
+            .build()
+            .await
+    });
+    assert_eq!(proxy.ping().await?, 1);
+    let service = service.await.unwrap()?;
+    assert_eq!(proxy.launch().await?, 2);
+    assert_eq!(proxy.poke().await?, 3);
+    let mut watcher = client_conn
+        .watch_name("org.freedesktop.zbus.RetryTest")
+        .await?;
+    drop(service);
+    while let Some(event) = watcher.next().await {
+        if event == NameEvent::Vanished {
+            break;
+        }
+    }
+
+    // Errors not deemed retryable are returned right away.
+    let proxy = RetryProxy::builder(&client_conn)
+        .retry_policy(RetryPolicy::new().retry_if(|_| false))
+        .build()
+        .await?;
+    assert_service_unknown(proxy.ping().await);
+
+    // And so are the errors after the maximum elapsed time.
+    let proxy = RetryProxy::builder(&client_conn)
+        .retry_policy(
+            RetryPolicy::new()
+                .initial_delay(Duration::from_millis(10))
+                .max_elapsed(Duration::from_millis(100)),
+        )
+        .build()
+        .await?;
+    assert_service_unknown(proxy.ping().await);
+
+    // A closed connection is not worth retrying on, unlike a disconnection reported by the peer.
+    let policy = RetryPolicy::new();
+    assert!(!policy.is_retryable(&zbus::Error::Disconnected(DisconnectReason::Closed)));
+    assert!(policy.is_retryable(&zbus::fdo::Error::Disconnected("gone".into()).into()));
+
+    Ok(())
+}
@@ -0,0 +1,117 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use futures_util::StreamExt;
+use ntest::timeout;
+use test_log::test;
+use zbus::{block_on, fdo, object_server::SignalEmitter, zvariant::OwnedObjectPath, Result};
+
+#[zbus::proxy(
+    interface = "org.freedesktop.zbus.Counter",
+    default_path = "/org/freedesktop/zbus/Counter",
+    gen_server = true
+)]
+trait Counter {
+    /// Add `n` to the counter and return the new value.
+    fn add(&self, n: u32) -> zbus::Result<u32>;
+
+    fn greet(&self, name: &str, tags: &[String]) -> zbus::Result<(String, u32)>;
+
+    #[zbus(name = "Self")]
+    fn self_path(&self) -> zbus::Result<OwnedObjectPath>;
+
+    #[zbus(property)]
+    fn value(&self) -> zbus::Result<u32>;
+
+    #[zbus(property)]
+    fn label(&self) -> zbus::Result<String>;
+
+    #[zbus(property)]
+    fn set_label(&self, label: &str) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    fn overflowed(&self, by: u32) -> zbus::Result<()>;
+}
+
+#[derive(Default)]
+struct MyCounter {
+    value: AtomicU32,
+    label: String,
+}
+
+impl CounterServer for MyCounter {
+    async fn add(&self, n: u32) -> fdo::Result<u32> {
+        Ok(self.value.fetch_add(n, Ordering::SeqCst) + n)
+    }
+
+    async fn greet(&self, name: String, tags: Vec<String>) -> fdo::Result<(String, u32)> {
+        Ok((format!("Hello {name}!"), tags.len() as u32))
+    }
+
+    async fn self_path(&self) -> fdo::Result<OwnedObjectPath> {
+        Ok(OwnedObjectPath::try_from("/org/freedesktop/zbus/Counter").unwrap())
+    }
+
+    async fn value(&self) -> fdo::Result<u32> {
+        Ok(self.value.load(Ordering::SeqCst))
+    }
+
+    async fn label(&self) -> fdo::Result<String> {
+        Ok(self.label.clone())
+    }
+
+    async fn set_label(&mut self, label: String) -> fdo::Result<()> {
+        if label.is_empty() {
+            return Err(fdo::Error::InvalidArgs("Empty label".into()));
+        }
+        self.label = label;
+
+        Ok(())
+    }
+}
+
+#[test]
+#[timeout(15000)]
+fn server_trait() {
+    block_on(test_server_trait()).unwrap();
+}
+
+async fn test_server_trait() -> Result<()> {
+    let service = zbus::connection::Builder::session()?
+        .serve_at(
+            "/org/freedesktop/zbus/Counter",
+            CounterInterface(MyCounter::default()),
+        )?
+        .build()
+        .await?;
+
+    let client_conn = zbus::Connection::session().await?;
+    let client = CounterProxy::builder(&client_conn)
+        .destination(service.unique_name().unwrap().to_owned())?
+        .cache_properties(zbus::proxy::CacheProperties::No)
+        .build()
+        .await?;
+    let mut overflows = client.receive_overflowed().await?;
+
+    assert_eq!(client.add(2).await?, 2);
+    assert_eq!(client.add(3).await?, 5);
+    assert_eq!(client.value().await?, 5);
+    assert_eq!(
+        client.greet("zbus", &["a".into(), "b".into()]).await?,
+        ("Hello zbus!".to_string(), 2)
+    );
+    assert_eq!(
+        client.self_path().await?.as_str(),
+        "/org/freedesktop/zbus/Counter"
+    );
+
+    client.set_label("counter").await?;
+    assert_eq!(client.label().await?, "counter");
+    assert!(client.set_label("").await.is_err());
+
+    let emitter = SignalEmitter::new(&service, "/org/freedesktop/zbus/Counter")?;
+    CounterInterface::<MyCounter>::overflowed(&emitter, 7).await?;
+    let signal = overflows.next().await.unwrap();
+    assert_eq!(signal.args()?.by, 7);
+
+    Ok(())
+}
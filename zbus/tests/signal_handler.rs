@@ -0,0 +1,95 @@
+use std::{sync::mpsc, time::Duration};
+
+use ntest::timeout;
+use zbus::{message::Type, Connection, MatchRule, Result};
+
+#[zbus::proxy(
+    interface = "org.freedesktop.zbus.Ticker",
+    default_path = "/org/freedesktop/zbus/Ticker"
+)]
+trait Ticker {
+    #[zbus(signal)]
+    fn tick(&self, n: u32) -> zbus::Result<()>;
+}
+
+async fn tick(conn: &Connection, n: u32) -> Result<()> {
+    conn.emit_signal(
+        None::<()>,
+        "/org/freedesktop/zbus/Ticker",
+        "org.freedesktop.zbus.Ticker",
+        "Tick",
+        &n,
+    )
+    .await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[timeout(15000)]
+async fn on_signal() -> Result<()> {
+    let service = Connection::session().await?;
+    let client = Connection::session().await?;
+    let proxy = TickerProxy::builder(&client)
+        .destination(service.unique_name().unwrap().to_owned())?
+        .build()
+        .await?;
+
+    let (proxy_tx, mut proxy_rx) = tokio::sync::mpsc::unbounded_channel();
+    let proxy_handler = proxy
+        .on_tick(move |signal| proxy_tx.send(signal.args().unwrap().n).unwrap())
+        .await?;
+    let rule = MatchRule::builder()
+        .msg_type(Type::Signal)
+        .interface("org.freedesktop.zbus.Ticker")?
+        .member("Tick")?
+        .build();
+    let (conn_tx, mut conn_rx) = tokio::sync::mpsc::unbounded_channel();
+    let _conn_handler = client
+        .on_signal(rule, move |msg| {
+            conn_tx
+                .send(msg.body().deserialize::<u32>().unwrap())
+                .unwrap()
+        })
+        .await?;
+
+    tick(&service, 1).await?;
+    assert_eq!(proxy_rx.recv().await, Some(1));
+    assert_eq!(conn_rx.recv().await, Some(1));
+
+    // Dropping the guard stops the handler, while the others keep running.
+    drop(proxy_handler);
+    tick(&service, 2).await?;
+    assert_eq!(conn_rx.recv().await, Some(2));
+    assert_eq!(proxy_rx.recv().await, None);
+
+    Ok(())
+}
+
+#[test]
+#[timeout(15000)]
+fn blocking_on_signal() -> Result<()> {
+    let service = Connection::from(zbus::blocking::Connection::session()?);
+    let client = zbus::blocking::Connection::session()?;
+    let proxy = TickerProxyBlocking::builder(&client)
+        .destination(service.unique_name().unwrap().to_owned())?
+        .build()?;
+
+    let (tx, rx) = mpsc::channel();
+    let handler = proxy.on_tick(move |signal| {
+        // The handler runs on its own thread, so it's fine to block here.
+        std::thread::sleep(Duration::from_millis(10));
+        tx.send(signal.args().unwrap().n).unwrap();
+    })?;
+
+    zbus::block_on(tick(&service, 3))?;
+    assert_eq!(rx.recv_timeout(Duration::from_secs(5)).unwrap(), 3);
+
+    // The thread exits, dropping the handler and its sender.
+    drop(handler);
+    assert!(rx.recv_timeout(Duration::from_secs(5)).is_err());
+    assert!(matches!(
+        rx.try_recv(),
+        Err(mpsc::TryRecvError::Disconnected)
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,262 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{spanned::Spanned, Data, DeriveInput, Error, ExprPath, Fields};
+use zvariant_utils::def_attrs;
+
+use crate::utils::{pascal_case, zbus_path};
+
+def_attrs! {
+    crate zbus;
+
+    pub FieldAttributes("field") {
+        property {
+            pub PropertyAttributes("property") {
+                access str
+            }
+        },
+        name str,
+        validate str
+    };
+}
+
+pub fn expand_derive(input: DeriveInput) -> Result<TokenStream, Error> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new(
+                    input.span(),
+                    "only structs with named fields supported",
+                ))
+            }
+        },
+        _ => return Err(Error::new(input.span(), "only structs supported")),
+    };
+    if !input.generics.params.is_empty() {
+        return Err(Error::new(
+            input.generics.span(),
+            "generic structs are not supported",
+        ));
+    }
+
+    let zbus = zbus_path();
+    let name = &input.ident;
+    let vis = &input.vis;
+    let mut names = vec![];
+    let mut get_dispatch = quote!();
+    let mut set_dispatch = quote!();
+    let mut signature_dispatch = quote!();
+    let mut introspect = quote!();
+    let mut setters_trait_methods = quote!();
+    let mut setters_impl_methods = quote!();
+    for field in fields {
+        let FieldAttributes {
+            property,
+            name: prop_name,
+            validate,
+        } = FieldAttributes::parse(&field.attrs)?;
+        let Some(property) = property else {
+            if prop_name.is_some() || validate.is_some() {
+                return Err(Error::new(
+                    field.span(),
+                    "`name` and `validate` attributes require the `property` attribute",
+                ));
+            }
+            continue;
+        };
+        let writable = match property.access.as_deref() {
+            None | Some("read") => false,
+            Some("readwrite") => true,
+            Some(other) => {
+                return Err(Error::new(
+                    field.span(),
+                    format!(
+                    "invalid `access` attribute value `{other}`, expected `read` or `readwrite`"
+                ),
+                ))
+            }
+        };
+        let ident = field.ident.as_ref().expect("named field");
+        let ty = &field.ty;
+        let prop_name = prop_name.unwrap_or_else(|| pascal_case(&ident.to_string()));
+        let validate = validate
+            .map(|path| {
+                let path = syn::parse_str::<ExprPath>(&path)?;
+
+                Ok::<_, Error>(quote!(#path(&value)?;))
+            })
+            .transpose()?;
+
+        get_dispatch.extend(quote! {
+            #prop_name => ::std::option::Option::Some(
+                <#zbus::zvariant::OwnedValue as ::std::convert::TryFrom<_>>::try_from(
+                    <#zbus::zvariant::Value as ::std::convert::From<_>>::from(
+                        ::std::clone::Clone::clone(&self.#ident),
+                    ),
+                )
+                .map_err(|e| #zbus::fdo::Error::Failed(e.to_string())),
+            ),
+        });
+        signature_dispatch.extend(quote! {
+            #prop_name => ::std::option::Option::Some((
+                ::std::clone::Clone::clone(<#ty as #zbus::zvariant::Type>::SIGNATURE),
+                #writable,
+            )),
+        });
+        set_dispatch.extend(quote! {
+            #prop_name => ::std::option::Option::Some((|| -> #zbus::fdo::Result<bool> {
+                #zbus::fdo::Properties::validate_set(
+                    #prop_name,
+                    <#ty as #zbus::zvariant::Type>::SIGNATURE,
+                    #writable,
+                    value,
+                )?;
+                let value = #zbus::zvariant::Value::try_to_owned(value)
+                    .map_err(|e| #zbus::Error::Variant(::std::convert::Into::into(e)))?;
+                let value = <#ty as ::std::convert::TryFrom<_>>::try_from(value)
+                    .map_err(|e| #zbus::Error::Variant(::std::convert::Into::into(e)))?;
+                #validate
+                if self.#ident == value {
+                    return ::std::result::Result::Ok(false);
+                }
+                self.#ident = value;
+
+                ::std::result::Result::Ok(true)
+            })()),
+        });
+        let access = if writable { "readwrite" } else { "read" };
+        let format_str = format!(
+            "{}<property name=\"{prop_name}\" type=\"{}\" access=\"{access}\"/>",
+            "{:indent$}", "{}",
+        );
+        introspect.extend(quote! {
+            ::std::writeln!(
+                writer,
+                #format_str,
+                "",
+                <#ty as #zbus::zvariant::Type>::SIGNATURE,
+                indent = level,
+            )
+            .unwrap();
+        });
+
+        let setter = format_ident!("set_{ident}");
+        let setter_doc = format!(
+            "Set the `{prop_name}` property and emit the “PropertiesChanged” signal if its value \
+             changed."
+        );
+        setters_trait_methods.extend(quote! {
+            #[doc = #setter_doc]
+            async fn #setter(&self, value: #ty) -> #zbus::Result<()>;
+        });
+        setters_impl_methods.extend(quote! {
+            async fn #setter(&self, value: #ty) -> #zbus::Result<()> {
+                #validate
+                {
+                    let mut iface = self.get_mut().await;
+                    if iface.#ident == value {
+                        return ::std::result::Result::Ok(());
+                    }
+                    iface.#ident = ::std::clone::Clone::clone(&value);
+                }
+                let mut changed = ::std::collections::HashMap::new();
+                changed.insert(
+                    #prop_name,
+                    <#zbus::zvariant::Value as ::std::convert::From<_>>::from(value),
+                );
+                #zbus::fdo::Properties::notify_properties_changed(
+                    self.signal_emitter(),
+                    <#name as #zbus::object_server::Interface>::name(),
+                    changed,
+                    ::std::borrow::Cow::Borrowed(&[]),
+                )
+                .await
+            }
+        });
+
+        names.push(prop_name);
+    }
+
+    let has_field_property = if names.is_empty() {
+        quote!({
+            let _ = name;
+            false
+        })
+    } else {
+        quote!(::std::matches!(name, #(#names)|*))
+    };
+    let setters_trait_name = format_ident!("{name}FieldProperties");
+    let setters_trait_doc = format!(
+        "Trait providing setters for the field-backed properties of `{name}`, emitting the change \
+         signals."
+    );
+
+    Ok(quote! {
+        impl #zbus::object_server::FieldProperties for #name {
+            fn has_field_property(name: &str) -> bool {
+                #has_field_property
+            }
+
+            fn get_field_property(
+                &self,
+                name: &str,
+            ) -> ::std::option::Option<#zbus::fdo::Result<#zbus::zvariant::OwnedValue>> {
+                match name {
+                    #get_dispatch
+                    _ => ::std::option::Option::None,
+                }
+            }
+
+            fn get_all_field_properties(
+                &self,
+            ) -> #zbus::fdo::Result<::std::collections::HashMap<
+                ::std::string::String,
+                #zbus::zvariant::OwnedValue,
+            >> {
+                let mut props = ::std::collections::HashMap::new();
+                #(
+                    if let ::std::option::Option::Some(value) = self.get_field_property(#names) {
+                        props.insert(::std::string::ToString::to_string(#names), value?);
+                    }
+                )*
+
+                ::std::result::Result::Ok(props)
+            }
+
+            fn field_property_signature(
+                name: &str,
+            ) -> ::std::option::Option<(#zbus::zvariant::Signature, bool)> {
+                match name {
+                    #signature_dispatch
+                    _ => ::std::option::Option::None,
+                }
+            }
+
+            fn set_field_property(
+                &mut self,
+                name: &str,
+                value: &#zbus::zvariant::Value<'_>,
+            ) -> ::std::option::Option<#zbus::fdo::Result<bool>> {
+                match name {
+                    #set_dispatch
+                    _ => ::std::option::Option::None,
+                }
+            }
+
+            fn introspect_field_properties(writer: &mut dyn ::std::fmt::Write, level: usize) {
+                #introspect
+            }
+        }
+
+        #[doc = #setters_trait_doc]
+        #[#zbus::export::async_trait::async_trait]
+        #vis trait #setters_trait_name {
+            #setters_trait_methods
+        }
+
+        #[#zbus::export::async_trait::async_trait]
+        impl #setters_trait_name for #zbus::object_server::InterfaceRef<#name> {
+            #setters_impl_methods
+        }
+    })
+}
@@ -1,4 +1,4 @@
-use proc_macro2::TokenStream;
+use proc_macro2::{Literal, Span, TokenStream};
 use quote::{format_ident, quote};
 use std::collections::BTreeMap;
 use syn::{
@@ -24,7 +24,23 @@ def_attrs! {
         interface str,
         name str,
         spawn bool,
+        blocking none,
+        limits {
+            pub LimitsAttributes("limits") {
+                max_concurrent_calls int,
+                max_calls_per_second int,
+                max_queued_calls int
+            }
+        },
         introspection_docs bool,
+        annotation [{
+            pub ImplAnnotationAttributes("annotation") {
+                name str,
+                value str
+            }
+        }],
+        deprecated none,
+        field_properties none,
         proxy {
             // Keep this in sync with proxy's method attributes.
             // TODO: Find a way to share code with proxy module.
@@ -50,6 +66,14 @@ def_attrs! {
             }
         },
         out_args [str],
+        blocking none,
+        annotation [{
+            pub MethodAnnotationAttributes("annotation") {
+                name str,
+                value str
+            }
+        }],
+        deprecated none,
         proxy {
             // Keep this in sync with proxy's method attributes.
             // TODO: Find a way to share code with proxy module.
@@ -59,7 +83,9 @@ def_attrs! {
                 blocking_object str,
                 no_reply none,
                 no_autostart none,
-                allow_interactive_auth none
+                allow_interactive_auth none,
+                no_retry none,
+                retry_policy str
             }
         }
     };
@@ -69,10 +95,99 @@ def_attrs! {
         connection none,
         header none,
         signal_context none,
-        signal_emitter none
+        signal_emitter none,
+        extract none,
+        credentials none,
+        annotation [{
+            pub ArgAnnotationAttributes("annotation") {
+                name str,
+                value str
+            }
+        }],
+        deprecated none
     };
 }
 
+/// An introspection annotation.
+#[derive(Debug, Clone)]
+struct Annotation {
+    name: String,
+    value: String,
+}
+
+impl Annotation {
+    /// Create the list of annotations from the `annotation` and `deprecated` attributes.
+    fn from_attrs<'a, I>(annotations: I, deprecated: bool, span: Span) -> syn::Result<Vec<Self>>
+    where
+        I: IntoIterator<Item = (&'a Option<String>, &'a Option<String>)>,
+    {
+        let mut parsed = Vec::new();
+        if deprecated {
+            parsed.push(Annotation {
+                name: "org.freedesktop.DBus.Deprecated".to_string(),
+                value: "true".to_string(),
+            });
+        }
+        for (name, value) in annotations {
+            match (name, value) {
+                (Some(name), Some(value)) => parsed.push(Annotation {
+                    name: name.clone(),
+                    value: value.clone(),
+                }),
+                _ => {
+                    return Err(Error::new(
+                        span,
+                        "`annotation` requires both `name` and `value` attributes",
+                    ))
+                }
+            }
+        }
+
+        Ok(parsed)
+    }
+
+    fn is_deprecation(&self) -> bool {
+        self.name == "org.freedesktop.DBus.Deprecated"
+    }
+}
+
+/// The `deprecated` and `annotation` attributes of the proxy, for the given annotations.
+fn proxy_annotations(annotations: &[Annotation]) -> TokenStream {
+    let attrs = annotations.iter().map(|annotation| {
+        if annotation.is_deprecation() {
+            quote!(deprecated,)
+        } else {
+            let Annotation { name, value } = annotation;
+
+            quote!(annotation(name = #name, value = #value),)
+        }
+    });
+
+    quote!(#(#attrs)*)
+}
+
+fn introspect_annotations(annotations: &[Annotation]) -> TokenStream {
+    let annotations = annotations.iter().map(|Annotation { name, value }| {
+        let (name, value) = (xml_escape(name), xml_escape(value));
+        quote!(
+            ::std::writeln!(
+                writer,
+                "{:indent$}<annotation name=\"{}\" value=\"{}\"/>",
+                "", #name, #value, indent = level,
+            ).unwrap();
+        )
+    });
+
+    quote!(#(#annotations)*)
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
 #[derive(Debug, Default)]
 struct Property {
     read: bool,
@@ -80,6 +195,8 @@ struct Property {
     emits_changed_signal: PropertyEmitsChangedSignal,
     ty: Option<Type>,
     doc_comments: TokenStream,
+    annotations: Vec<Annotation>,
+    getter_cfg_attrs: Vec<Attribute>,
 }
 
 #[derive(Debug, PartialEq, Copy, Clone)]
@@ -135,6 +252,8 @@ struct MethodInfo {
     cfg_attrs: Vec<Attribute>,
     /// The doc attributes of the method.
     doc_attrs: Vec<Attribute>,
+    /// The introspection annotations of the method.
+    annotations: Vec<Annotation>,
 }
 
 impl MethodInfo {
@@ -230,7 +349,12 @@ impl MethodInfo {
         };
 
         let mut intro_args = quote!();
-        intro_args.extend(introspect_input_args(&typed_inputs, is_signal, cfg_attrs));
+        intro_args.extend(introspect_input_args(
+            &typed_inputs,
+            is_signal,
+            is_property,
+            cfg_attrs,
+        )?);
         let is_result_output = introspect_add_output_args(
             &mut intro_args,
             output,
@@ -280,6 +404,11 @@ impl MethodInfo {
             output: output.clone(),
             cfg_attrs: cfg_attrs.iter().cloned().cloned().collect(),
             doc_attrs: doc_attrs.iter().cloned().cloned().collect(),
+            annotations: Annotation::from_attrs(
+                attrs.annotation.iter().map(|a| (&a.name, &a.value)),
+                attrs.deprecated,
+                method.span(),
+            )?,
         })
     }
 }
@@ -295,6 +424,7 @@ pub fn expand(args: Punctuated<Meta, Token![,]>, mut input: ItemImpl) -> syn::Re
     let mut get_all = quote!();
     let mut call_dispatch = quote!();
     let mut call_mut_dispatch = quote!();
+    let mut blocking_methods = quote!();
     let mut introspect = quote!();
     let mut generated_signals = quote!();
     let mut signals_trait_methods = quote!();
@@ -330,10 +460,39 @@ pub fn expand(args: Punctuated<Meta, Token![,]>, mut input: ItemImpl) -> syn::Re
         }
     };
     let with_spawn = impl_attrs.spawn.unwrap_or(true);
+    let all_blocking = impl_attrs.blocking;
+    let sender_limits = impl_attrs.limits.map(|limits| {
+        let setters = [
+            ("max_concurrent_calls", limits.max_concurrent_calls),
+            ("max_calls_per_second", limits.max_calls_per_second),
+            ("max_queued_calls", limits.max_queued_calls),
+        ]
+        .into_iter()
+        .filter_map(|(setter, max)| {
+            let setter = Ident::new(setter, Span::call_site());
+            let max = Literal::u64_unsuffixed(max?);
+
+            Some(quote!(.#setter(#max)))
+        });
+
+        quote! {
+            fn sender_limits(&self) -> ::std::option::Option<#zbus::object_server::SenderLimits> {
+                ::std::option::Option::Some(
+                    #zbus::object_server::SenderLimits::new() #(#setters)*
+                )
+            }
+        }
+    });
+    let introspect_docs = impl_attrs.introspection_docs.unwrap_or(true);
+    let iface_annotations = Annotation::from_attrs(
+        impl_attrs.annotation.iter().map(|a| (&a.name, &a.value)),
+        impl_attrs.deprecated,
+        input.span(),
+    )?;
     let mut proxy = impl_attrs
         .proxy
-        .map(|p| Proxy::new(ty, &iface_name, p, &zbus));
-    let introspect_docs = impl_attrs.introspection_docs.unwrap_or(true);
+        .map(|p| Proxy::new(ty, &iface_name, p, iface_annotations.clone(), &zbus));
+    let iface_annotations = introspect_annotations(&iface_annotations);
 
     // Store parsed information about each method
     let mut methods = vec![];
@@ -391,11 +550,39 @@ pub fn expand(args: Punctuated<Meta, Token![,]>, mut input: ItemImpl) -> syn::Re
             &doc_attrs,
             introspect_docs,
         )?;
+        if method_attrs.blocking
+            && (method_info.is_async || method_info.method_type != MethodType::Other)
+        {
+            return Err(Error::new_spanned(
+                method,
+                "`blocking` can only be used on methods that are neither async, nor properties or \
+                 signals",
+            ));
+        }
+        if (method_attrs.blocking || all_blocking)
+            && !method_info.is_async
+            && method_info.method_type == MethodType::Other
+        {
+            let cfg_attrs = &method_info.cfg_attrs;
+            let member_name = &method_info.member_name;
+            blocking_methods.extend(quote! {
+                #(#cfg_attrs)*
+                #member_name => true,
+            });
+        }
         let attr_property = method_attrs.property;
         if let Some(prop_attrs) = &attr_property {
             let property: &mut Property = properties
                 .entry(method_info.member_name.to_string())
                 .or_default();
+            for annotation in &method_info.annotations {
+                // Both the getter and the setter may be marked as deprecated.
+                if !annotation.is_deprecation()
+                    || !property.annotations.iter().any(Annotation::is_deprecation)
+                {
+                    property.annotations.push(annotation.clone());
+                }
+            }
             if method_info.method_type == MethodType::Property(PropertyType::Getter) {
                 let emits_changed_signal = if let Some(s) = &prop_attrs.emits_changed_signal {
                     PropertyEmitsChangedSignal::parse(s, method.span())?
@@ -404,6 +591,7 @@ pub fn expand(args: Punctuated<Meta, Token![,]>, mut input: ItemImpl) -> syn::Re
                 };
                 property.read = true;
                 property.emits_changed_signal = emits_changed_signal;
+                property.getter_cfg_attrs = method_info.cfg_attrs.clone();
             } else {
                 property.write = true;
                 if prop_attrs.emits_changed_signal.is_some() {
@@ -435,6 +623,7 @@ pub fn expand(args: Punctuated<Meta, Token![,]>, mut input: ItemImpl) -> syn::Re
             reply,
             member_name,
             cfg_attrs,
+            annotations,
             ..
         } = method_info;
 
@@ -451,7 +640,7 @@ pub fn expand(args: Punctuated<Meta, Token![,]>, mut input: ItemImpl) -> syn::Re
         match method_type {
             MethodType::Signal => {
                 introspect.extend(doc_comments);
-                introspect.extend(introspect_signal(&member_name, &intro_args));
+                introspect.extend(introspect_signal(&member_name, &intro_args, &annotations));
                 let signal_emitter = signal_emitter_arg.unwrap().pat;
 
                 method.block = parse_quote!({
@@ -543,6 +732,7 @@ pub fn expand(args: Punctuated<Meta, Token![,]>, mut input: ItemImpl) -> syn::Re
                                 && !a.header
                                 && !a.signal_context
                                 && !a.signal_emitter
+                                && !a.credentials
                         })
                         .ok_or_else(|| Error::new_spanned(inputs, "Expected a value argument"))?;
 
@@ -735,7 +925,7 @@ pub fn expand(args: Punctuated<Meta, Token![,]>, mut input: ItemImpl) -> syn::Re
                                 let mut changed = ::std::collections::HashMap::new();
                                 let value = <#zbus::zvariant::Value as ::std::convert::From<_>>::from(#prop_value_handled);
                                 changed.insert(#member_name, value);
-                                #zbus::fdo::Properties::properties_changed(
+                                #zbus::fdo::Properties::notify_properties_changed(
                                     __zbus__signal_emitter,
                                     #zbus::names::InterfaceName::from_static_str_unchecked(#iface_name),
                                     changed,
@@ -761,7 +951,7 @@ pub fn expand(args: Punctuated<Meta, Token![,]>, mut input: ItemImpl) -> syn::Re
                                 &self,
                                 __zbus__signal_emitter: &#zbus::object_server::SignalEmitter<'_>,
                             ) -> #zbus::Result<()> {
-                                #zbus::fdo::Properties::properties_changed(
+                                #zbus::fdo::Properties::notify_properties_changed(
                                     __zbus__signal_emitter,
                                     #zbus::names::InterfaceName::from_static_str_unchecked(#iface_name),
                                     ::std::collections::HashMap::new(),
@@ -776,7 +966,7 @@ pub fn expand(args: Punctuated<Meta, Token![,]>, mut input: ItemImpl) -> syn::Re
             }
             MethodType::Other => {
                 introspect.extend(doc_comments);
-                introspect.extend(introspect_method(&member_name, &intro_args));
+                introspect.extend(introspect_method(&member_name, &intro_args, &annotations));
 
                 let m = quote! {
                     #(#cfg_attrs)*
@@ -814,8 +1004,61 @@ pub fn expand(args: Punctuated<Meta, Token![,]>, mut input: ItemImpl) -> syn::Re
         }
     }
 
+    let mut property_signatures = quote!();
+    for (name, p) in &properties {
+        let ty = p.ty.as_ref().unwrap();
+        let cfg_attrs = &p.getter_cfg_attrs;
+        let writable = p.write;
+        property_signatures.extend(quote!(
+            #(#cfg_attrs)*
+            #name => ::std::option::Option::Some((
+                ::std::clone::Clone::clone(<#ty as #zbus::zvariant::Type>::SIGNATURE),
+                #writable,
+            )),
+        ));
+    }
+
     introspect_properties(&mut introspect, properties)?;
 
+    let mut get_fallback = quote!(::std::option::Option::None);
+    let mut set_mut_fallback = get_fallback.clone();
+    let mut property_signature_fallback = get_fallback.clone();
+    if impl_attrs.field_properties {
+        let field_properties = quote!(<Self as #zbus::object_server::FieldProperties>);
+        get_fallback = quote! {
+            #field_properties::get_field_property(self, __zbus__property_name)
+        };
+        property_signature_fallback = quote! {
+            #field_properties::field_property_signature(__zbus__property_name)
+        };
+        get_all.extend(quote! {
+            props.extend(#field_properties::get_all_field_properties(self)?);
+        });
+        set_dispatch.extend(quote! {
+            _ if #field_properties::has_field_property(__zbus__property_name) => {
+                #zbus::object_server::DispatchResult::RequiresMut
+            }
+        });
+        set_mut_fallback = quote! {
+            match #field_properties::set_field_property(self, __zbus__property_name, value) {
+                ::std::option::Option::Some(::std::result::Result::Ok(true)) => {
+                    ::std::option::Option::Some(
+                        #zbus::object_server::notify_field_property_changed::<Self>(
+                            __zbus__signal_emitter,
+                            __zbus__property_name,
+                            value,
+                        )
+                        .await,
+                    )
+                }
+                res => res.map(|res| res.map(|_| ())),
+            }
+        };
+        introspect.extend(quote! {
+            #field_properties::introspect_field_properties(writer, level);
+        });
+    }
+
     let generics = &input.generics;
     let where_clause = &generics.where_clause;
 
@@ -880,6 +1123,15 @@ pub fn expand(args: Punctuated<Meta, Token![,]>, mut input: ItemImpl) -> syn::Re
                 #with_spawn
             }
 
+            #sender_limits
+
+            fn is_blocking_method(&self, name: &#zbus::names::MemberName<'_>) -> bool {
+                match name.as_str() {
+                    #blocking_methods
+                    _ => false,
+                }
+            }
+
             async fn get(
                 &self,
                 __zbus__property_name: &str,
@@ -890,7 +1142,7 @@ pub fn expand(args: Punctuated<Meta, Token![,]>, mut input: ItemImpl) -> syn::Re
             ) -> ::std::option::Option<#zbus::fdo::Result<#zbus::zvariant::OwnedValue>> {
                 match __zbus__property_name {
                     #get_dispatch
-                    _ => ::std::option::Option::None,
+                    _ => #get_fallback,
                 }
             }
 
@@ -912,6 +1164,16 @@ pub fn expand(args: Punctuated<Meta, Token![,]>, mut input: ItemImpl) -> syn::Re
                 Ok(props)
             }
 
+            fn property_signature(
+                &self,
+                __zbus__property_name: &str,
+            ) -> ::std::option::Option<(#zbus::zvariant::Signature, bool)> {
+                match __zbus__property_name {
+                    #property_signatures
+                    _ => #property_signature_fallback,
+                }
+            }
+
             fn set<'call>(
                 &'call self,
                 __zbus__property_name: &'call str,
@@ -938,7 +1200,7 @@ pub fn expand(args: Punctuated<Meta, Token![,]>, mut input: ItemImpl) -> syn::Re
             ) -> ::std::option::Option<#zbus::fdo::Result<()>> {
                 match __zbus__property_name {
                     #set_mut_dispatch
-                    _ => ::std::option::Option::None,
+                    _ => #set_mut_fallback,
                 }
             }
 
@@ -979,6 +1241,7 @@ pub fn expand(args: Punctuated<Meta, Token![,]>, mut input: ItemImpl) -> syn::Re
                     use #zbus::zvariant::Type;
 
                     let level = level + 2;
+                    #iface_annotations
                     #introspect
                 }
                 ::std::writeln!(writer, r#"{:indent$}</interface>"#, "", indent = level).unwrap();
@@ -1001,6 +1264,8 @@ fn get_args_from_inputs(
         let mut conn_arg_decl = None;
         let mut header_arg_decl = None;
         let mut signal_emitter_arg_decl = None;
+        let mut extracted_args_decl = Vec::new();
+        let mut credentials_arg_decl = None;
         let mut args_names = Vec::new();
         let mut tys = Vec::new();
 
@@ -1011,8 +1276,22 @@ fn get_args_from_inputs(
                 header,
                 signal_emitter,
                 signal_context,
+                extract,
+                credentials,
+                annotation,
+                deprecated,
             } = ArgAttributes::parse(&input.attrs)?;
 
+            if matches!(method_type, MethodType::Property(_))
+                && (!annotation.is_empty() || deprecated)
+            {
+                return Err(Error::new_spanned(
+                    input,
+                    "annotations are not supported on property arguments, annotate the property \
+                     method instead",
+                ));
+            }
+
             if object_server {
                 if server_arg_decl.is_some() {
                     return Err(Error::new_spanned(
@@ -1078,6 +1357,65 @@ fn get_args_from_inputs(
                         };
                     }),
                 };
+            } else if extract {
+                if method_type != MethodType::Other {
+                    return Err(Error::new_spanned(
+                        input,
+                        "`extract` arguments are only supported on methods",
+                    ));
+                }
+
+                let extracted_arg = &input.pat;
+                let ty = &input.ty;
+                extracted_args_decl.push(quote! {
+                    let #extracted_arg: #ty =
+                        match <#ty as #zbus::object_server::FromMethodCall>::from_method_call(
+                            &#zbus::object_server::CallContext::new(
+                                __zbus__message,
+                                __zbus__connection,
+                                __zbus__object_server,
+                            ),
+                        )
+                        .await
+                        {
+                            ::std::result::Result::Ok(arg) => arg,
+                            ::std::result::Result::Err(e) => {
+                                return __zbus__connection.reply_dbus_error(&hdr, e).await;
+                            }
+                        };
+                });
+            } else if credentials {
+                if method_type != MethodType::Other {
+                    return Err(Error::new_spanned(
+                        input,
+                        "`credentials` arguments are only supported on methods",
+                    ));
+                }
+                if credentials_arg_decl.is_some() {
+                    return Err(Error::new_spanned(
+                        input,
+                        "There can only be one credentials argument",
+                    ));
+                }
+
+                let credentials_arg = &input.pat;
+                credentials_arg_decl = Some(quote! {
+                    let __zbus__credentials =
+                        match #zbus::object_server::CallContext::new(
+                            __zbus__message,
+                            __zbus__connection,
+                            __zbus__object_server,
+                        )
+                        .sender_credentials()
+                        .await
+                        {
+                            ::std::result::Result::Ok(credentials) => credentials,
+                            ::std::result::Result::Err(e) => {
+                                return __zbus__connection.reply_dbus_error(&hdr, e).await;
+                            }
+                        };
+                    let #credentials_arg: &#zbus::fdo::ConnectionCredentials = &__zbus__credentials;
+                });
             } else {
                 args_names.push(pat_ident(input).unwrap());
                 tys.push(&input.ty);
@@ -1120,6 +1458,10 @@ fn get_args_from_inputs(
 
             #signal_emitter_arg_decl
 
+            #credentials_arg_decl
+
+            #(#extracted_args_decl)*
+
             #args_decl
         };
 
@@ -1139,54 +1481,90 @@ fn clear_input_arg_attrs(inputs: &mut Punctuated<FnArg, Token![,]>) {
     }
 }
 
-fn introspect_signal(name: &str, args: &TokenStream) -> TokenStream {
+fn introspect_signal(name: &str, args: &TokenStream, annotations: &[Annotation]) -> TokenStream {
     let format_str = format!("{}<signal name=\"{name}\">", "{:indent$}");
+    let annotations = introspect_annotations(annotations);
     quote!(
         ::std::writeln!(writer, #format_str, "", indent = level).unwrap();
         {
             let level = level + 2;
             #args
+            #annotations
         }
         ::std::writeln!(writer, "{:indent$}</signal>", "", indent = level).unwrap();
     )
 }
 
-fn introspect_method(name: &str, args: &TokenStream) -> TokenStream {
+fn introspect_method(name: &str, args: &TokenStream, annotations: &[Annotation]) -> TokenStream {
     let format_str = format!("{}<method name=\"{name}\">", "{:indent$}");
+    let annotations = introspect_annotations(annotations);
     quote!(
         ::std::writeln!(writer, #format_str, "", indent = level).unwrap();
         {
             let level = level + 2;
             #args
+            #annotations
         }
         ::std::writeln!(writer, "{:indent$}</method>", "", indent = level).unwrap();
     )
 }
 
-fn introspect_input_args<'i>(
-    inputs: &'i [PatType],
+fn introspect_input_args(
+    inputs: &[PatType],
     is_signal: bool,
-    cfg_attrs: &'i [&'i syn::Attribute],
-) -> impl Iterator<Item = TokenStream> + 'i {
-    inputs
-        .iter()
-        .filter_map(move |pat_type @ PatType { ty, attrs, .. }| {
-            if is_special_arg(attrs) {
-                return None;
-            }
+    is_property: bool,
+    cfg_attrs: &[&syn::Attribute],
+) -> syn::Result<TokenStream> {
+    let mut args = quote!();
+    for pat_type @ PatType { ty, attrs, .. } in inputs {
+        if is_special_arg(attrs) {
+            continue;
+        }
 
-            let ident = pat_ident(pat_type).unwrap();
-            let arg_name = quote!(#ident).to_string();
-            let dir = if is_signal { "" } else { " direction=\"in\"" };
+        let ident = pat_ident(pat_type).unwrap();
+        let arg_name = quote!(#ident).to_string();
+        let dir = if is_signal { "" } else { " direction=\"in\"" };
+        let arg_attrs = ArgAttributes::parse(attrs)?;
+        let annotations = if is_property {
+            // Reported as an error, when parsing the arguments.
+            vec![]
+        } else {
+            Annotation::from_attrs(
+                arg_attrs.annotation.iter().map(|a| (&a.name, &a.value)),
+                arg_attrs.deprecated,
+                pat_type.span(),
+            )?
+        };
+        if annotations.is_empty() {
             let format_str = format!(
                 "{}<arg name=\"{arg_name}\" type=\"{}\"{dir}/>",
                 "{:indent$}", "{}",
             );
-            Some(quote!(
+            args.extend(quote!(
                 #(#cfg_attrs)*
                 ::std::writeln!(writer, #format_str, "", <#ty>::SIGNATURE, indent = level).unwrap();
-            ))
-        })
+            ));
+        } else {
+            let format_str = format!(
+                "{}<arg name=\"{arg_name}\" type=\"{}\"{dir}>",
+                "{:indent$}", "{}",
+            );
+            let annotations = introspect_annotations(&annotations);
+            args.extend(quote!(
+                #(#cfg_attrs)*
+                {
+                    ::std::writeln!(writer, #format_str, "", <#ty>::SIGNATURE, indent = level).unwrap();
+                    {
+                        let level = level + 2;
+                        #annotations
+                    }
+                    ::std::writeln!(writer, "{:indent$}</arg>", "", indent = level).unwrap();
+                }
+            ));
+        }
+    }
+
+    Ok(args)
 }
 
 fn count_regular_args(inputs: &[PatType]) -> usize {
@@ -1218,7 +1596,9 @@ fn is_special_arg(attrs: &[Attribute]) -> bool {
                     path.is_ident("connection") ||
                     path.is_ident("header") ||
                     path.is_ident("signal_context") ||
-                    path.is_ident("signal_emitter")
+                    path.is_ident("signal_emitter") ||
+                    path.is_ident("extract") ||
+                    path.is_ident("credentials")
             )
         });
 
@@ -1345,7 +1725,15 @@ fn introspect_properties(
         let ty = prop.ty.unwrap();
 
         let doc_comments = prop.doc_comments;
-        if prop.emits_changed_signal == PropertyEmitsChangedSignal::True {
+        let mut annotations = Vec::new();
+        if prop.emits_changed_signal != PropertyEmitsChangedSignal::True {
+            annotations.push(Annotation {
+                name: "org.freedesktop.DBus.Property.EmitsChangedSignal".to_string(),
+                value: prop.emits_changed_signal.to_string(),
+            });
+        }
+        annotations.extend(prop.annotations);
+        if annotations.is_empty() {
             let format_str = format!(
                 "{}<property name=\"{name}\" type=\"{}\" access=\"{access}\"/>",
                 "{:indent$}", "{}",
@@ -1355,21 +1743,19 @@ fn introspect_properties(
                 ::std::writeln!(writer, #format_str, "", <#ty>::SIGNATURE, indent = level).unwrap();
             ));
         } else {
-            let emits_changed_signal = prop.emits_changed_signal.to_string();
-            let annot_name = "org.freedesktop.DBus.Property.EmitsChangedSignal";
             let format_str = format!(
-                "{}<property name=\"{name}\" type=\"{}\" access=\"{access}\">\n\
-                    {}<annotation name=\"{annot_name}\" value=\"{emits_changed_signal}\"/>\n\
-                {}</property>",
-                "{:indent$}", "{}", "{:annot_indent$}", "{:indent$}",
+                "{}<property name=\"{name}\" type=\"{}\" access=\"{access}\">",
+                "{:indent$}", "{}",
             );
+            let annotations = introspect_annotations(&annotations);
             introspection.extend(quote!(
                 #doc_comments
-                ::std::writeln!(
-                    writer,
-                    #format_str,
-                    "", <#ty>::SIGNATURE, "", "", indent = level, annot_indent = level + 2,
-                ).unwrap();
+                ::std::writeln!(writer, #format_str, "", <#ty>::SIGNATURE, indent = level).unwrap();
+                {
+                    let level = level + 2;
+                    #annotations
+                }
+                ::std::writeln!(writer, "{:indent$}</property>", "", indent = level).unwrap();
             ));
         }
     }
@@ -1438,18 +1824,26 @@ struct Proxy {
 
     // Input
     attrs: ProxyAttributes,
+    annotations: Vec<Annotation>,
 
     // Output
     methods: TokenStream,
 }
 
 impl Proxy {
-    fn new(ty: &Ident, iface_name: &str, attrs: ProxyAttributes, zbus: &TokenStream) -> Self {
+    fn new(
+        ty: &Ident,
+        iface_name: &str,
+        attrs: ProxyAttributes,
+        annotations: Vec<Annotation>,
+        zbus: &TokenStream,
+    ) -> Self {
         Self {
             iface_name: iface_name.to_string(),
             ty: ty.clone(),
             zbus: zbus.clone(),
             attrs,
+            annotations,
             methods: quote!(),
         }
     }
@@ -1469,7 +1863,10 @@ impl Proxy {
                     && !a.header
                     && !a.signal_context
                     && !a.signal_emitter
+                    && !a.extract
+                    && !a.credentials
             })
+            // The remaining `zbus` attributes are the annotations, that the proxy accepts too.
             .cloned()
             .collect();
         let zbus = &self.zbus;
@@ -1507,6 +1904,25 @@ impl Proxy {
         let ident = &method_info.ident;
         let member_name = method_info.member_name;
         let mut proxy_method_attrs = quote! { name = #member_name, };
+        let annotations = match method_info.method_type {
+            MethodType::Property(ty) => {
+                let property = properties.get(&member_name).unwrap();
+                // Both the getter and the setter are deprecated, but the other annotations of the
+                // property are only given once, so they're not duplicated on the way back.
+                if ty == PropertyType::Getter || !property.read {
+                    property.annotations.clone()
+                } else {
+                    property
+                        .annotations
+                        .iter()
+                        .filter(|a| a.is_deprecation())
+                        .cloned()
+                        .collect()
+                }
+            }
+            _ => method_info.annotations.clone(),
+        };
+        proxy_method_attrs.extend(proxy_annotations(&annotations));
         proxy_method_attrs.extend(match method_info.method_type {
             MethodType::Signal => quote!(signal),
             MethodType::Property(_) => {
@@ -1540,6 +1956,12 @@ impl Proxy {
             if attrs.allow_interactive_auth {
                 proxy_method_attrs.extend(quote! { allow_interactive_auth, });
             }
+            if attrs.no_retry {
+                proxy_method_attrs.extend(quote! { no_retry, });
+            }
+            if let Some(retry_policy) = attrs.retry_policy {
+                proxy_method_attrs.extend(quote! { retry_policy = #retry_policy, });
+            }
         }
         let cfg_attrs = method_info.cfg_attrs;
         let doc_attrs = method_info.doc_attrs;
@@ -1599,10 +2021,12 @@ impl Proxy {
         };
         let zbus = &self.zbus;
         let proxy_doc = format!("Proxy for the `{iface_name}` interface.");
+        let annotations = proxy_annotations(&self.annotations);
         Ok(quote! {
             #[doc = #proxy_doc]
             #[#zbus::proxy(
                 name = #iface_name,
+                #annotations
                 #assume_defaults
                 #default_path
                 #default_service
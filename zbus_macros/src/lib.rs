@@ -17,7 +17,9 @@ use syn::{
 };
 
 mod error;
+mod field_properties;
 mod iface;
+mod properties;
 mod proxy;
 mod utils;
 
@@ -53,6 +55,23 @@ mod utils;
 ///   attribute nor one of the default values are specified. Please make sure to explicitly set
 ///   either this attribute or the default values, according to your needs.
 ///
+/// * `gen_server` - Whether or not to also generate the server side of the interface (default:
+///   `false`). Read the [Server](#server) section below for details.
+///
+/// * `gen_trait` - Whether or not to also generate an async trait for the interface, implemented by
+///   the proxies (default: `false`). Read the [Trait and mock](#trait-and-mock) section below for
+///   details.
+///
+/// * `gen_mock` - Whether or not to also generate an in-memory mock implementing the trait
+///   generated by `gen_trait` (default: `false`). This implies `gen_trait`.
+///
+/// * `deprecated` - mark the generated proxy types with the `#[deprecated]` attribute, and the
+///   interface generated by `gen_server` as deprecated.
+///
+/// * `annotation` - add an annotation to the introspection data of the interface generated by
+///   `gen_server`, e.g `annotation(name = "org.example.Stability", value = "unstable")`. Can be
+///   specified multiple times.
+///
 /// Each trait method will be expanded to call to the associated D-Bus remote interface.
 ///
 /// Trait methods accept `proxy` attributes:
@@ -87,6 +106,18 @@ mod utils;
 /// * `allow_interactive_auth` - declare a method call that is allowed to trigger an interactive
 ///   prompt for authorization or confirmation from the receiver.
 ///
+/// * `no_retry` - declare a method call that is never retried, even if the proxy was built with a
+///   [`RetryPolicy`]. Use this for methods that must not be called more than once.
+///
+/// * `retry_policy` - the path of a function returning the [`RetryPolicy`] to use for calls to this
+///   method, instead of the one the proxy was built with (if any).
+///
+/// * `deprecated` - mark all the methods generated for this method, property or signal with the
+///   `#[deprecated]` attribute.
+///
+/// * `annotation` - add an annotation to the introspection data of the corresponding member of the
+///   interface generated by `gen_server`. Can be specified multiple times.
+///
 /// * `object` - methods that returns an [`ObjectPath`] can be annotated with the `object` attribute
 ///   to specify the proxy object to be constructed from the returned [`ObjectPath`].
 ///
@@ -98,6 +129,10 @@ mod utils;
 ///
 ///   NB: Any doc comments provided shall be appended to the ones added by the macro.
 ///
+/// The arguments of the trait methods accept the `annotation` and `deprecated` attributes, with
+/// the same meaning as for the arguments of [`macro@interface`] methods. Like the other
+/// annotations, these only affect the interface generated by `gen_server`.
+///
 /// # Signals
 ///
 /// For each signal method declared, this macro will provide a method, named `receive_<method_name>`
@@ -107,10 +142,114 @@ mod utils;
 /// access to the signal arguments. It also implements `Deref<Target = Message>` to allow easy
 /// access to the underlying [`zbus::message::Message`].
 ///
+/// An `on_<method_name>` method is also provided, to have a closure called with each
+/// `<SignalName>` instead. The closure runs until the returned [`zbus::connection::SignalHandler`]
+/// ([`zbus::blocking::connection::SignalHandler`] for the blocking proxy) guard is dropped.
+///
 /// For each property with `emits_changed_signal` set to `"true"` (default) or `"invalidates"`,
 /// this macro will provide a method named `receive_<property_name>_changed` that creates a
 /// [`zbus::proxy::PropertyStream`] for the property.
 ///
+/// # Server
+///
+/// With `gen_server = true`, the trait also serves as the single definition of the interface for
+/// the service side, so that the client and the service can't drift apart. Two more items are
+/// generated:
+///
+/// * A `TraitNameServer` trait, declaring an async method for each method and property of the
+///   interface, with the same arguments and returning a [`zbus::fdo::Result`] of the same output
+///   type. Reference arguments are passed owned: `&str` as `String`, `&[T]` as `Vec<T>` and `&T` as
+///   `T`. Methods with an `object` attribute return an [`OwnedObjectPath`]. Property setters take
+///   `&mut self`.
+/// * A `TraitNameInterface<T>` wrapper, implementing the interface (as if with [`macro@interface`])
+///   for any `T` implementing `TraitNameServer`, by calling into it. Its associated functions emit
+///   the signals of the interface.
+///
+/// Generic methods are not supported with this attribute.
+///
+/// ```
+/// use zbus::{fdo, object_server::SignalEmitter, proxy};
+///
+/// #[proxy(interface = "org.test.Counter", default_path = "/org/test/Counter", gen_server = true)]
+/// trait Counter {
+///     fn add(&self, n: u32) -> zbus::Result<u32>;
+///
+///     #[zbus(property)]
+///     fn label(&self) -> zbus::Result<String>;
+///
+///     #[zbus(signal)]
+///     fn overflowed(&self, by: u32) -> zbus::Result<()>;
+/// }
+///
+/// struct MyCounter(std::sync::atomic::AtomicU32);
+///
+/// impl CounterServer for MyCounter {
+///     async fn add(&self, n: u32) -> fdo::Result<u32> {
+///         Ok(self.0.fetch_add(n, std::sync::atomic::Ordering::SeqCst) + n)
+///     }
+///
+///     async fn label(&self) -> fdo::Result<String> {
+///         Ok("My counter".to_string())
+///     }
+/// }
+///
+/// # async fn serve(emitter: SignalEmitter<'_>) -> zbus::Result<()> {
+/// let _conn = zbus::connection::Builder::session()?
+///     .serve_at("/org/test/Counter", CounterInterface(MyCounter(Default::default())))?
+///     .build()
+///     .await?;
+/// // ...
+/// CounterInterface::<MyCounter>::overflowed(&emitter, 2).await?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Trait and mock
+///
+/// With `gen_trait = true`, an object-safe async trait, with the same name as the annotated trait,
+/// is generated. It has an async method for each method and property of the interface, with the
+/// same signature, and a `receive_<signal_name>` method for each signal, returning a boxed stream
+/// of the signals. Methods with an `object` attribute return an [`OwnedObjectPath`] instead of a
+/// proxy. The trait is implemented by both the asynchronous and blocking proxies, so code written
+/// against `&dyn TraitName` works with either of them. Note that the implementation for the
+/// blocking proxy makes asynchronous calls, through an asynchronous proxy sharing its connection.
+///
+/// With `gen_mock = true`, a `TraitNameMock` type implementing the trait without any D-Bus
+/// connection is also generated. It wraps a [`zbus::proxy::Mock`], through which the replies to
+/// method calls are programmed, the recorded calls are inspected, the properties are set and the
+/// signals are emitted. Generic methods are not supported with these attributes.
+///
+/// ```
+/// use zbus::proxy;
+///
+/// #[proxy(interface = "org.test.Thermostat", default_path = "/org/test/Thermostat", gen_mock = true)]
+/// trait Thermostat {
+///     fn set_target(&self, celsius: f64) -> zbus::Result<bool>;
+///
+///     #[zbus(property)]
+///     fn temperature(&self) -> zbus::Result<f64>;
+/// }
+///
+/// // The code to test only depends on the trait.
+/// async fn cool_down(thermostat: &dyn Thermostat) -> zbus::Result<bool> {
+///     let temperature = thermostat.temperature().await?;
+///
+///     thermostat.set_target(temperature - 1.0).await
+/// }
+///
+/// # zbus::block_on(async {
+/// let mock = ThermostatMock::new();
+/// mock.inner()
+///     .on_call("SetTarget", |(celsius,): (f64,)| Ok(celsius > 10.0))
+///     .set_property("Temperature", 20.0)?;
+///
+/// assert!(cool_down(&mock).await?);
+/// let calls = mock.inner().calls_to("SetTarget");
+/// assert_eq!(calls[0].body().deserialize::<f64>()?, 19.0);
+/// # Ok::<_, zbus::Error>(())
+/// # }).unwrap();
+/// ```
+///
 /// # Example
 ///
 /// ```no_run
@@ -196,7 +335,13 @@ mod utils;
 /// [`zbus::blocking::Proxy`]: https://docs.rs/zbus/latest/zbus/blocking/proxy/struct.Proxy.html
 /// [`zbus::SignalStream`]: https://docs.rs/zbus/latest/zbus/proxy/struct.SignalStream.html
 /// [`zbus::blocking::SignalIterator`]: https://docs.rs/zbus/latest/zbus/blocking/proxy/struct.SignalIterator.html
+/// [`zbus::connection::SignalHandler`]: https://docs.rs/zbus/latest/zbus/connection/struct.SignalHandler.html
+/// [`zbus::blocking::connection::SignalHandler`]: https://docs.rs/zbus/latest/zbus/blocking/connection/struct.SignalHandler.html
 /// [`ObjectPath`]: https://docs.rs/zvariant/latest/zvariant/struct.ObjectPath.html
+/// [`OwnedObjectPath`]: https://docs.rs/zvariant/latest/zvariant/struct.OwnedObjectPath.html
+/// [`zbus::fdo::Result`]: https://docs.rs/zbus/latest/zbus/fdo/type.Result.html
+/// [`zbus::proxy::Mock`]: https://docs.rs/zbus/latest/zbus/proxy/struct.Mock.html
+/// [`RetryPolicy`]: https://docs.rs/zbus/latest/zbus/proxy/struct.RetryPolicy.html
 /// [dbus_emits_changed_signal]: https://dbus.freedesktop.org/doc/dbus-specification.html#introspection-format
 #[proc_macro_attribute]
 pub fn proxy(attr: TokenStream, item: TokenStream) -> TokenStream {
@@ -231,6 +376,15 @@ pub fn proxy(attr: TokenStream, item: TokenStream) -> TokenStream {
 ///     received, which is crucial for interfaces requiring sequential processing of method calls.
 ///     However, care must be taken to avoid making D-Bus method calls from within your interface
 ///     methods when this setting is false, as it may lead to deadlocks under certain conditions.
+///     The `zbus::object_server::DispatchPolicy` of the object server still applies to these calls.
+///
+/// * `limits` - override the limits on the method calls of each sender for this interface, e.g
+///   `limits(max_concurrent_calls = 4, max_calls_per_second = 100, max_queued_calls = 16)`. All the
+///   sub-attributes are optional. Calls exceeding the limits are refused with a `LimitsExceeded`
+///   error. See `zbus::object_server::SenderLimits` for details.
+///
+/// * `blocking` - mark all the non-async methods of the interface as blocking. See the method
+///   attribute of the same name for details. Properties and signals are not affected.
 ///
 /// * `proxy` - If specified, a proxy type will also be generated for the interface. This attribute
 ///   supports all the [`macro@proxy`]-specific sub-attributes (e.g `gen_async`). The common
@@ -240,6 +394,17 @@ pub fn proxy(attr: TokenStream, item: TokenStream) -> TokenStream {
 ///   (Default: `true`). If your interface is well-known or well-documented, you may want to set
 ///   this to `false` to reduce the the size of your binary and D-Bus traffic.
 ///
+/// * `annotation` - add an annotation to the introspection data of the interface, e.g
+///   `annotation(name = "org.example.Stability", value = "unstable")`. Can be specified multiple
+///   times.
+///
+/// * `deprecated` - mark the interface as deprecated in the introspection data, through the
+///   `org.freedesktop.DBus.Deprecated` annotation.
+///
+/// * `field_properties` - also expose the fields of `T` marked as properties, through the
+///   implementation of `zbus::object_server::FieldProperties` generated by the
+///   [`FieldProperties`](derive.FieldProperties.html) derive macro.
+///
 /// The methods accepts the `interface` attributes:
 ///
 /// * `name` - override the D-Bus name (pascal case form of the method by default)
@@ -258,6 +423,10 @@ pub fn proxy(attr: TokenStream, item: TokenStream) -> TokenStream {
 ///     * `"false"` - the change signal is not emitted if the property changes. If a property is
 ///       write-only, the change signal will not be emitted in this interface.
 ///
+///   Before calling the setter, the signature of the value is checked against the type of the
+///   property and an `org.freedesktop.DBus.Error.InvalidArgs` error is returned on mismatch.
+///   Setting a property without a setter results in a `PropertyReadOnly` error.
+///
 /// * `signal` - the method is a "signal". It must be a method declaration (without body). Its code
 ///   block will be expanded to emit the signal from the object path associated with the interface
 ///   instance. Moreover, `interface` will also generate a trait named `<Interface>Signals` that
@@ -280,6 +449,20 @@ pub fn proxy(attr: TokenStream, item: TokenStream) -> TokenStream {
 ///   In such case, your method must return a tuple containing
 ///   your out arguments, in the same order as passed to `out_args`.
 ///
+/// * `blocking` - the method is blocking (e.g does synchronous I/O) and must not be run on the
+///   async runtime. Calls to it are dispatched from a thread pool instead: the `blocking` crate's
+///   pool, or tokio's blocking pool if the `tokio` feature is enabled. The number of blocking
+///   methods running concurrently on a connection is limited, see
+///   `zbus::connection::Builder::blocking_pool_size`. Only non-async methods can be blocking.
+///
+/// * `annotation` - add an annotation to the introspection data of the method, signal or property.
+///   Can be specified multiple times. For properties, the annotations of the getter and the setter
+///   are merged.
+///
+/// * `deprecated` - mark the method, signal or property as deprecated in the introspection data,
+///   through the `org.freedesktop.DBus.Deprecated` annotation. The corresponding methods of the
+///   generated proxy (if any) are marked with `#[deprecated]`.
+///
 /// The `struct_return` attribute (from zbus 1.x) is no longer supported. If you want to return a
 /// single structure from a method, declare it to return a tuple containing either a named structure
 /// or a nested tuple.
@@ -307,6 +490,18 @@ pub fn proxy(attr: TokenStream, item: TokenStream) -> TokenStream {
 ///   external property access.
 /// * `signal_emitter` - This marks the method argument to receive a [`SignalEmitter`] instance,
 ///   which is needed for emitting signals the easy way.
+/// * `extract` - This marks the method argument to be derived from the context of the call, through
+///   the `zbus::object_server::FromMethodCall` trait implementation of its type. If the extraction
+///   fails, the call is refused with the returned error and the method is not called. Not supported
+///   on properties.
+/// * `credentials` - This marks the method argument to receive a
+///   `&zbus::fdo::ConnectionCredentials` of the caller. The credentials are cached by the
+///   connection, so only the first call from a given peer costs a query to the bus. If the query
+///   fails, the call is refused with the error and the method is not called. Not supported on
+///   properties.
+/// * `annotation` - add an annotation to the introspection data of the argument. Can be specified
+///   multiple times. Not supported on property setter arguments.
+/// * `deprecated` - mark the argument as deprecated in the introspection data.
 ///
 /// # Example
 ///
@@ -426,3 +621,145 @@ pub fn derive_dbus_error(input: TokenStream) -> TokenStream {
         .unwrap_or_else(|err| err.to_compile_error())
         .into()
 }
+
+/// Derive macro for implementing [`zbus::Properties`], a typed snapshot of the properties of an
+/// interface.
+///
+/// The macro must be applied on a struct with named fields. Each field maps to the D-Bus property
+/// of the same name, converted to `PascalCase`, and its type must be convertible from a
+/// [`zvariant::OwnedValue`]. The struct can then be fetched with [`zbus::Proxy::get_all_as`] and
+/// updated from the `PropertiesChanged` signal.
+///
+/// The following attributes are supported on the struct:
+///
+/// * `interface` - the name of the interface the properties belong to. When set, the signals for
+///   other interfaces are ignored by `Properties::update_from_signal`.
+///
+/// * `rename_all` - the case convention of the property names: `"PascalCase"` (default),
+///   `"camelCase"`, `"snake_case"`, `"kebab-case"`, `"lowercase"` or `"UPPERCASE"`.
+///
+/// The following attributes are supported on the fields:
+///
+/// * `rename` - the name of the D-Bus property, if it doesn't follow the case convention.
+///
+/// * `default` - use the `Default` value of the field if the property is missing, instead of
+///   failing.
+///
+/// # Example
+///
+/// ```
+/// use zbus::Properties;
+///
+/// #[derive(Debug, Properties)]
+/// #[zbus(interface = "org.freedesktop.NetworkManager")]
+/// struct NetworkManager {
+///     version: String,
+///     networking_enabled: bool,
+///     #[zbus(rename = "WirelessHardwareEnabled")]
+///     wifi_switch: bool,
+///     #[zbus(default)]
+///     capabilities: Vec<u32>,
+/// }
+/// ```
+///
+/// [`zbus::Properties`]: https://docs.rs/zbus/latest/zbus/trait.Properties.html
+/// [`zbus::Proxy::get_all_as`]: https://docs.rs/zbus/latest/zbus/proxy/struct.Proxy.html#method.get_all_as
+/// [`zvariant::OwnedValue`]: https://docs.rs/zvariant/latest/zvariant/struct.OwnedValue.html
+#[proc_macro_derive(Properties, attributes(zbus))]
+pub fn derive_properties(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    properties::expand_derive(input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+/// Derive macro for exposing the fields of a struct as D-Bus properties.
+///
+/// The macro implements [`zbus::object_server::FieldProperties`] for a struct with named fields,
+/// so that the [`macro@interface`] macro, given the `field_properties` attribute, serves the fields
+/// marked with the `property` attribute as properties of the interface, without any getter or
+/// setter methods. The field types must implement `Clone`, `PartialEq` and [`zvariant::Type`], and
+/// be convertible to a [`zvariant::Value`] and from a [`zvariant::OwnedValue`].
+///
+/// The following attributes are supported on the fields:
+///
+/// * `property` - expose the field as a property, named after the field in `PascalCase`. The
+///   `access` sub-attribute sets whether it can be set by peers: `"read"` (default) or
+///   `"readwrite"`. Setting a property to a different value through the
+///   `org.freedesktop.DBus.Properties` interface emits the `PropertiesChanged` signal.
+///
+/// * `name` - override the D-Bus name of the property.
+///
+/// * `validate` - the path of a function validating the new values of the property, with the
+///   signature `fn(&T) -> zbus::fdo::Result<()>`. Values it rejects are not set and the error is
+///   returned to the peer.
+///
+/// In addition, a trait named `<Struct>FieldProperties` is generated and implemented for
+/// `zbus::object_server::InterfaceRef<Struct>`, with a `set_<field>` method for each of the
+/// properties (including the read-only ones). These methods update the field and emit the
+/// `PropertiesChanged` signal if its value changed, which makes them the way to change
+/// field-backed properties from outside of the interface methods.
+///
+/// Alternatively, `InterfaceRef::get_mut_tracked` returns a guard through which the fields can be
+/// assigned directly. Validation is skipped in that case but, once the guard is dropped or
+/// committed, the `PropertiesChanged` signal is emitted for all the properties whose value
+/// changed.
+///
+/// # Example
+///
+/// ```no_run
+/// use zbus::{fdo, interface, FieldProperties};
+///
+/// #[derive(FieldProperties)]
+/// struct Light {
+///     #[zbus(property)]
+///     model: String,
+///     #[zbus(property(access = "readwrite"), validate = "check_brightness")]
+///     brightness: u8,
+///     // Not exposed on the bus.
+///     power_draw: f64,
+/// }
+///
+/// fn check_brightness(brightness: &u8) -> fdo::Result<()> {
+///     if *brightness > 100 {
+///         return Err(fdo::Error::InvalidArgs("Brightness must be at most 100".into()));
+///     }
+///
+///     Ok(())
+/// }
+///
+/// #[interface(name = "org.example.Light", field_properties)]
+/// impl Light {
+///     fn power_draw(&self) -> f64 {
+///         self.power_draw
+///     }
+/// }
+///
+/// # async fn example(conn: &zbus::Connection) -> zbus::Result<()> {
+/// // From outside of the interface, through the generated `LightFieldProperties` trait:
+/// let light = conn
+///     .object_server()
+///     .interface::<_, Light>("/org/example/Light")
+///     .await?;
+/// light.set_brightness(40).await?;
+///
+/// // Or by assigning the fields through a change-tracking guard:
+/// let mut guard = light.get_mut_tracked().await;
+/// guard.brightness = 60;
+/// guard.model = "Lamp".into();
+/// guard.commit().await?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`zbus::object_server::FieldProperties`]: https://docs.rs/zbus/latest/zbus/object_server/trait.FieldProperties.html
+/// [`zvariant::Type`]: https://docs.rs/zvariant/latest/zvariant/trait.Type.html
+/// [`zvariant::Value`]: https://docs.rs/zvariant/latest/zvariant/enum.Value.html
+/// [`zvariant::OwnedValue`]: https://docs.rs/zvariant/latest/zvariant/struct.OwnedValue.html
+#[proc_macro_derive(FieldProperties, attributes(zbus))]
+pub fn derive_field_properties(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    field_properties::expand_derive(input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
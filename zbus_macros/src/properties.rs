@@ -0,0 +1,137 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{spanned::Spanned, Data, DeriveInput, Error, Fields};
+use zvariant_utils::{case, def_attrs};
+
+use crate::utils::zbus_path;
+
+def_attrs! {
+    crate zbus;
+
+    pub StructAttributes("struct") {
+        interface str,
+        rename_all str
+    };
+
+    pub FieldAttributes("field") {
+        rename str,
+        default none
+    };
+}
+
+pub fn expand_derive(input: DeriveInput) -> Result<TokenStream, Error> {
+    let StructAttributes {
+        interface,
+        rename_all,
+    } = StructAttributes::parse(&input.attrs)?;
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new(
+                    input.span(),
+                    "only structs with named fields supported",
+                ))
+            }
+        },
+        _ => return Err(Error::new(input.span(), "only structs supported")),
+    };
+
+    let zbus = zbus_path();
+    let mut field_inits = quote! {};
+    let mut field_updates = quote! {};
+    for field in fields {
+        let FieldAttributes { rename, default } = FieldAttributes::parse(&field.attrs)?;
+        let ident = field.ident.as_ref().expect("named field");
+        let ty = &field.ty;
+        let name = match rename {
+            Some(name) => name,
+            None => property_name(&ident.to_string(), rename_all.as_deref(), field.span())?,
+        };
+
+        let missing = if default {
+            quote! { ::std::default::Default::default() }
+        } else {
+            quote! {
+                return ::std::result::Result::Err(#zbus::Error::from(
+                    #zbus::fdo::Error::UnknownProperty(
+                        ::std::format!("Missing property `{}`", #name),
+                    ),
+                ))
+            }
+        };
+        field_inits.extend(quote! {
+            #ident: match properties.remove(#name) {
+                ::std::option::Option::Some(value) => {
+                    <#ty as ::std::convert::TryFrom<#zbus::zvariant::OwnedValue>>::try_from(value)
+                        .map_err(::std::convert::Into::<#zbus::Error>::into)?
+                }
+                ::std::option::Option::None => #missing,
+            },
+        });
+        field_updates.extend(quote! {
+            #name => {
+                let value = #zbus::zvariant::OwnedValue::try_from(value)?;
+                self.#ident =
+                    <#ty as ::std::convert::TryFrom<#zbus::zvariant::OwnedValue>>::try_from(value)
+                        .map_err(::std::convert::Into::<#zbus::Error>::into)?;
+            }
+        });
+    }
+
+    let interface = match interface {
+        Some(interface) => quote! { ::std::option::Option::Some(#interface) },
+        None => quote! { ::std::option::Option::None },
+    };
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #zbus::Properties for #name #ty_generics #where_clause {
+            const INTERFACE: ::std::option::Option<&'static str> = #interface;
+
+            fn from_properties(
+                mut properties: ::std::collections::HashMap<
+                    ::std::string::String,
+                    #zbus::zvariant::OwnedValue,
+                >,
+            ) -> #zbus::Result<Self> {
+                ::std::result::Result::Ok(Self { #field_inits })
+            }
+
+            fn update_properties(
+                &mut self,
+                changed: &::std::collections::HashMap<&str, #zbus::zvariant::Value<'_>>,
+            ) -> #zbus::Result<()> {
+                for (name, value) in changed {
+                    match *name {
+                        #field_updates
+                        _ => (),
+                    }
+                }
+
+                ::std::result::Result::Ok(())
+            }
+        }
+    })
+}
+
+/// The D-Bus property name of the field `ident`, `PascalCase` by default.
+fn property_name(
+    ident: &str,
+    rename_all: Option<&str>,
+    span: proc_macro2::Span,
+) -> Result<String, Error> {
+    match rename_all.unwrap_or("PascalCase") {
+        "PascalCase" => Ok(case::pascal_or_camel_case(ident, true)),
+        "camelCase" => Ok(case::pascal_or_camel_case(ident, false)),
+        "snake_case" => Ok(case::snake_or_kebab_case(ident, true)),
+        "kebab-case" => Ok(case::snake_or_kebab_case(ident, false)),
+        "lowercase" => Ok(ident.to_ascii_lowercase()),
+        "UPPERCASE" => Ok(ident.to_ascii_uppercase()),
+        other => Err(Error::new(
+            span,
+            format!("invalid `rename_all` attribute value {other}"),
+        )),
+    }
+}
@@ -1,9 +1,10 @@
-use crate::utils::{pat_ident, typed_arg, zbus_path, PropertyEmitsChangedSignal};
+use crate::utils::{get_doc_attrs, pat_ident, typed_arg, zbus_path, PropertyEmitsChangedSignal};
 use proc_macro2::{Literal, Span, TokenStream};
 use quote::{format_ident, quote, quote_spanned, ToTokens};
 use syn::{
     fold::Fold, parse_quote, parse_str, punctuated::Punctuated, spanned::Spanned, Error, FnArg,
-    Ident, ItemTrait, Meta, Path, ReturnType, Token, TraitItemFn, Visibility,
+    GenericArgument, Ident, ItemTrait, Meta, Path, PathArguments, ReturnType, Token, TraitItemFn,
+    Type, Visibility,
 };
 use zvariant_utils::{case, def_attrs};
 
@@ -20,7 +21,17 @@ def_attrs! {
         async_name str,
         blocking_name str,
         gen_async bool,
-        gen_blocking bool
+        gen_blocking bool,
+        gen_server bool,
+        gen_trait bool,
+        gen_mock bool,
+        annotation [{
+            pub TraitAnnotationAttributes("annotation") {
+                name str,
+                value str
+            }
+        }],
+        deprecated none
     };
 
     // Keep this in sync with interface's proxy method attributes.
@@ -37,7 +48,27 @@ def_attrs! {
         blocking_object str,
         no_reply none,
         no_autostart none,
-        allow_interactive_auth none
+        allow_interactive_auth none,
+        no_retry none,
+        retry_policy str,
+        annotation [{
+            pub MethodAnnotationAttributes("annotation") {
+                name str,
+                value str
+            }
+        }],
+        deprecated none
+    };
+
+    // Keep this in sync with interface's argument attributes.
+    pub ArgAttributes("argument") {
+        annotation [{
+            pub ArgAnnotationAttributes("annotation") {
+                name str,
+                value str
+            }
+        }],
+        deprecated none
     };
 }
 
@@ -64,6 +95,9 @@ impl AsyncOpts {
 
 pub fn expand(args: Punctuated<Meta, Token![,]>, input: ItemTrait) -> Result<TokenStream, Error> {
     let attrs = TraitAttributes::parse_nested_metas(args)?;
+    // The annotations only matter to the server side, the client side is generated without them.
+    let server_input = input.clone();
+    let input = client_input(input, attrs.deprecated)?;
 
     let iface_name = match (attrs.interface, attrs.name) {
         (Some(name), None) | (None, Some(name)) => Ok(Some(name)),
@@ -93,22 +127,30 @@ pub fn expand(args: Punctuated<Meta, Token![,]>, input: ItemTrait) -> Result<Tok
         "Can't set asynchronous proxy's name if you disabled it. 😸",
     );
 
-    let blocking_proxy = if gen_blocking {
-        let proxy_name = attrs.blocking_name.unwrap_or_else(|| {
+    let blocking_name = gen_blocking.then(|| {
+        attrs.blocking_name.unwrap_or_else(|| {
             if gen_async {
                 format!("{}ProxyBlocking", input.ident)
             } else {
                 // When only generating blocking proxy, there is no need for a suffix.
                 format!("{}Proxy", input.ident)
             }
-        });
+        })
+    });
+    let async_name = gen_async.then(|| {
+        attrs
+            .async_name
+            .unwrap_or_else(|| format!("{}Proxy", input.ident))
+    });
+
+    let blocking_proxy = if let Some(proxy_name) = &blocking_name {
         create_proxy(
             &input,
             iface_name.as_deref(),
             attrs.assume_defaults,
             attrs.default_path.as_deref(),
             attrs.default_service.as_deref(),
-            &proxy_name,
+            proxy_name,
             true,
             // Signal args structs are shared between the two proxies so always generate it for
             // async proxy only unless async proxy generation is disabled.
@@ -117,17 +159,14 @@ pub fn expand(args: Punctuated<Meta, Token![,]>, input: ItemTrait) -> Result<Tok
     } else {
         quote! {}
     };
-    let async_proxy = if gen_async {
-        let proxy_name = attrs
-            .async_name
-            .unwrap_or_else(|| format!("{}Proxy", input.ident));
+    let async_proxy = if let Some(proxy_name) = &async_name {
         create_proxy(
             &input,
             iface_name.as_deref(),
             attrs.assume_defaults,
             attrs.default_path.as_deref(),
             attrs.default_service.as_deref(),
-            &proxy_name,
+            proxy_name,
             false,
             true,
         )?
@@ -135,10 +174,49 @@ pub fn expand(args: Punctuated<Meta, Token![,]>, input: ItemTrait) -> Result<Tok
         quote! {}
     };
 
+    let server = if attrs.gen_server.unwrap_or(false) {
+        create_server(
+            &server_input,
+            iface_name.as_deref(),
+            attrs.deprecated,
+            &attrs.annotation,
+        )?
+    } else {
+        quote! {}
+    };
+
+    let gen_mock = attrs.gen_mock.unwrap_or(false);
+    let proxy_trait = if attrs.gen_trait.unwrap_or(false) || gen_mock {
+        let async_name = async_name.as_deref().ok_or_else(|| {
+            Error::new(
+                input.span(),
+                "`gen_trait` and `gen_mock` require the asynchronous proxy",
+            )
+        })?;
+        let mock_path = match (&attrs.default_path, attrs.assume_defaults) {
+            (Some(path), _) => path.clone(),
+            (None, Some(true)) => format!("/org/freedesktop/{}", input.ident),
+            (None, _) => "/".to_string(),
+        };
+        create_trait(
+            &input,
+            iface_name.as_deref(),
+            async_name,
+            blocking_name.as_deref(),
+            gen_mock.then_some(mock_path.as_str()),
+        )?
+    } else {
+        quote! {}
+    };
+
     Ok(quote! {
         #blocking_proxy
 
         #async_proxy
+
+        #server
+
+        #proxy_trait
     })
 }
 
@@ -160,17 +238,11 @@ pub fn create_proxy(
         .iter()
         .filter(|a| !a.path().is_ident("zbus"))
         .collect();
+    // The generated code itself shouldn't warn about the proxy being deprecated.
+    let allow_deprecated = is_deprecated(input).then(|| quote!(#[allow(deprecated)]));
     let proxy_name = Ident::new(proxy_name, Span::call_site());
     let ident = input.ident.to_string();
-    let iface_name = iface_name
-        .map(|iface| {
-            // Ensure the interface name is valid.
-            zbus_names::InterfaceName::try_from(iface)
-                .map_err(|e| Error::new(input.span(), format!("{e}")))
-                .map(|i| i.to_string())
-        })
-        .transpose()?
-        .unwrap_or(format!("org.freedesktop.{ident}"));
+    let iface_name = interface_name(input, iface_name)?;
     let assume_defaults = assume_defaults.unwrap_or(false);
     let default_path = default_path
         .map(|path| {
@@ -207,6 +279,16 @@ pub fn create_proxy(
         if let syn::TraitItem::Fn(m) = i {
             let method_attrs = MethodAttributes::parse(&m.attrs)?;
             let property = method_attrs.property.as_ref();
+            let deprecated_m;
+            let m = if method_attrs.deprecated {
+                // Mark all the generated methods as deprecated.
+                let mut method = m.clone();
+                method.attrs.push(parse_quote!(#[deprecated]));
+                deprecated_m = method;
+                &deprecated_m
+            } else {
+                m
+            };
 
             let method_name = m.sig.ident.to_string();
 
@@ -367,6 +449,7 @@ pub fn create_proxy(
     };
 
     Ok(quote! {
+        #allow_deprecated
         impl<'a> #zbus::proxy::Defaults for #proxy_name<'a> {
             const INTERFACE: &'static Option<#zbus::names::InterfaceName<'static>> =
                 &Some(#zbus::names::InterfaceName::from_static_str_unchecked(#iface_name));
@@ -378,6 +461,7 @@ pub fn create_proxy(
         #[derive(Clone, Debug)]
         #visibility struct #proxy_name<'p>(#proxy_struct<'p>);
 
+        #allow_deprecated
         impl<'p> #proxy_name<'p> {
             #proxy_method_new
 
@@ -411,6 +495,7 @@ pub fn create_proxy(
             #methods
         }
 
+        #allow_deprecated
         impl<'p> #proxy_trait<'p> for #proxy_name<'p> {
             fn builder(conn: &#connection) -> #builder<'p, Self> {
                 Self::builder(conn)
@@ -425,29 +510,34 @@ pub fn create_proxy(
             }
         }
 
+        #allow_deprecated
         impl<'p> ::std::convert::From<#zbus::Proxy<'p>> for #proxy_name<'p> {
             fn from(proxy: #zbus::Proxy<'p>) -> Self {
                 #proxy_name(::std::convert::Into::into(proxy))
             }
         }
 
+        #allow_deprecated
         impl<'p> ::std::convert::AsRef<#proxy_struct<'p>> for #proxy_name<'p> {
             fn as_ref(&self) -> &#proxy_struct<'p> {
                 self.inner()
             }
         }
 
+        #allow_deprecated
         impl<'p> ::std::convert::AsMut<#proxy_struct<'p>> for #proxy_name<'p> {
             fn as_mut(&mut self) -> &mut #proxy_struct<'p> {
                 self.inner_mut()
             }
         }
 
+        #allow_deprecated
         impl<'p> #zbus::zvariant::Type for #proxy_name<'p> {
             const SIGNATURE: &'static #zbus::zvariant::Signature =
                 &#zbus::zvariant::Signature::ObjectPath;
         }
 
+        #allow_deprecated
         impl<'p> #zbus::export::serde::ser::Serialize for #proxy_name<'p> {
             fn serialize<S>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error>
             where
@@ -464,6 +554,490 @@ pub fn create_proxy(
     })
 }
 
+/// The D-Bus interface name of the trait, `org.freedesktop.<TraitName>` by default.
+fn interface_name(input: &ItemTrait, iface_name: Option<&str>) -> Result<String, Error> {
+    match iface_name {
+        // Ensure the interface name is valid.
+        Some(iface) => zbus_names::InterfaceName::try_from(iface)
+            .map_err(|e| Error::new(input.span(), format!("{e}")))
+            .map(|i| i.to_string()),
+        None => Ok(format!("org.freedesktop.{}", input.ident)),
+    }
+}
+
+/// Whether the trait is marked as deprecated.
+fn is_deprecated(input: &ItemTrait) -> bool {
+    input.attrs.iter().any(|a| a.path().is_ident("deprecated"))
+}
+
+/// The trait to generate the client side from: without the argument attributes, and marked as
+/// deprecated if the interface is.
+fn client_input(mut input: ItemTrait, deprecated: bool) -> Result<ItemTrait, Error> {
+    for item in input.items.iter_mut() {
+        let syn::TraitItem::Fn(m) = item else {
+            continue;
+        };
+        for arg in m.sig.inputs.iter_mut() {
+            if let FnArg::Typed(arg) = arg {
+                ArgAttributes::parse(&arg.attrs)?;
+                arg.attrs.retain(|attr| !attr.path().is_ident("zbus"));
+            }
+        }
+    }
+    if deprecated {
+        input.attrs.push(parse_quote!(#[deprecated]));
+    }
+
+    Ok(input)
+}
+
+/// The `annotation` attributes to forward to the generated interface.
+fn forward_annotations<'a, I>(annotations: I, span: Span) -> Result<TokenStream, Error>
+where
+    I: IntoIterator<Item = (&'a Option<String>, &'a Option<String>)>,
+{
+    annotations
+        .into_iter()
+        .map(|(name, value)| match (name, value) {
+            (Some(name), Some(value)) => Ok(quote!(annotation(name = #name, value = #value),)),
+            _ => Err(Error::new(
+                span,
+                "`annotation` requires both `name` and `value` attributes",
+            )),
+        })
+        .collect()
+}
+
+/// Generate the server side of the interface: a `<TraitName>Server` trait to be implemented by the
+/// service and a `<TraitName>Interface` wrapper serving any implementation of it.
+fn create_server(
+    input: &ItemTrait,
+    iface_name: Option<&str>,
+    deprecated: bool,
+    annotations: &[TraitAnnotationAttributes],
+) -> Result<TokenStream, Error> {
+    let zbus = zbus_path();
+    let visibility = &input.vis;
+    let ident = &input.ident;
+    let iface_name = interface_name(input, iface_name)?;
+    let server_name = format_ident!("{ident}Server");
+    let iface_struct_name = format_ident!("{ident}Interface");
+    let mut server_methods = TokenStream::new();
+    let mut iface_methods = TokenStream::new();
+
+    for i in input.items.iter() {
+        let syn::TraitItem::Fn(m) = i else {
+            continue;
+        };
+        let method_attrs = MethodAttributes::parse(&m.attrs)?;
+        if !m.sig.generics.params.is_empty() {
+            return Err(Error::new_spanned(
+                &m.sig.generics,
+                "generic methods are not supported with `gen_server`",
+            ));
+        }
+        let doc_attrs = get_doc_attrs(&m.attrs);
+        let method = &m.sig.ident;
+        let is_setter = method_attrs.property.is_some() && m.sig.inputs.len() > 1;
+        let member_name = member_name(m, &method_attrs);
+        let args = m
+            .sig
+            .inputs
+            .iter()
+            .filter_map(typed_arg)
+            .map(|arg| {
+                pat_ident(arg)
+                    .map(|name| (name, &*arg.ty))
+                    .ok_or_else(|| Error::new_spanned(arg, "unsupported argument pattern"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        // The annotations of the arguments, to be forwarded to the interface.
+        let arg_attrs: Vec<Vec<_>> = m
+            .sig
+            .inputs
+            .iter()
+            .filter_map(typed_arg)
+            .map(|arg| {
+                arg.attrs
+                    .iter()
+                    .filter(|a| a.path().is_ident("zbus"))
+                    .collect()
+            })
+            .collect();
+        let deprecated = method_attrs.deprecated.then(|| quote!(deprecated,));
+        let annotations = forward_annotations(
+            method_attrs.annotation.iter().map(|a| (&a.name, &a.value)),
+            m.span(),
+        )?;
+
+        if method_attrs.signal {
+            // Signals are only emitted, so the arguments don't need to be owned.
+            let (arg_names, arg_types): (Vec<_>, Vec<_>) = args.into_iter().unzip();
+            iface_methods.extend(quote! {
+                #(#doc_attrs)*
+                #[zbus(signal, #deprecated #annotations name = #member_name)]
+                pub async fn #method(
+                    emitter: &#zbus::object_server::SignalEmitter<'_>,
+                    #(#(#arg_attrs)* #arg_names: #arg_types),*
+                ) -> #zbus::Result<()>;
+            });
+
+            continue;
+        }
+
+        let arg_names: Vec<_> = args.iter().map(|(name, _)| name).collect();
+        let arg_types: Vec<_> = args.iter().map(|(_, ty)| owned_type(ty)).collect();
+        let output = if method_attrs.object.is_some() {
+            quote!(#zbus::zvariant::OwnedObjectPath)
+        } else {
+            result_inner_type(&m.sig.output)?.to_token_stream()
+        };
+        let receiver = if is_setter {
+            quote!(&mut self)
+        } else {
+            quote!(&self)
+        };
+        let kind = match &method_attrs.property {
+            Some(PropertyAttributes {
+                emits_changed_signal: Some(emits),
+            }) if !is_setter => quote!(property(emits_changed_signal = #emits),),
+            Some(_) => quote!(property,),
+            None => quote!(),
+        };
+
+        server_methods.extend(quote! {
+            #(#doc_attrs)*
+            fn #method(
+                #receiver,
+                #(#arg_names: #arg_types),*
+            ) -> impl ::std::future::Future<Output = #zbus::fdo::Result<#output>>
+                   + ::std::marker::Send;
+        });
+        iface_methods.extend(quote! {
+            #(#doc_attrs)*
+            #[zbus(#kind #deprecated #annotations name = #member_name)]
+            async fn #method(
+                #receiver,
+                #(#(#arg_attrs)* #arg_names: #arg_types),*
+            ) -> #zbus::fdo::Result<#output> {
+                self.0.#method(#(#arg_names),*).await
+            }
+        });
+    }
+
+    let server_doc = format!(
+        " The server side of the `{iface_name}` interface.\n\n \
+         Serve an implementation of this trait with [`{iface_struct_name}`].",
+    );
+    let iface_struct_doc =
+        format!(" Serves an implementation of [`{server_name}`] as the `{iface_name}` interface.",);
+    let iface_deprecated = deprecated.then(|| quote!(deprecated,));
+    let iface_annotations = forward_annotations(
+        annotations.iter().map(|a| (&a.name, &a.value)),
+        input.span(),
+    )?;
+
+    Ok(quote! {
+        #[doc = #server_doc]
+        #visibility trait #server_name:
+            ::std::marker::Send + ::std::marker::Sync + 'static
+        {
+            #server_methods
+        }
+
+        #[doc = #iface_struct_doc]
+        #[derive(Debug)]
+        #visibility struct #iface_struct_name<T>(pub T);
+
+        #[#zbus::interface(name = #iface_name, #iface_deprecated #iface_annotations)]
+        impl<T> #iface_struct_name<T>
+        where
+            T: #server_name,
+        {
+            #iface_methods
+        }
+    })
+}
+
+/// Generate the `<TraitName>` async trait implemented by the proxies and, if `mock_path` is set,
+/// a `<TraitName>Mock` implementation of it mocking the object at that path.
+fn create_trait(
+    input: &ItemTrait,
+    iface_name: Option<&str>,
+    async_name: &str,
+    blocking_name: Option<&str>,
+    mock_path: Option<&str>,
+) -> Result<TokenStream, Error> {
+    let zbus = zbus_path();
+    let visibility = &input.vis;
+    let trait_name = &input.ident;
+    let iface_name = interface_name(input, iface_name)?;
+    let async_name = Ident::new(async_name, Span::call_site());
+    let mut trait_methods = TokenStream::new();
+    let mut async_methods = TokenStream::new();
+    let mut blocking_methods = TokenStream::new();
+    let mut mock_methods = TokenStream::new();
+
+    for i in input.items.iter() {
+        let syn::TraitItem::Fn(m) = i else {
+            continue;
+        };
+        let method_attrs = MethodAttributes::parse(&m.attrs)?;
+        if !m.sig.generics.params.is_empty() {
+            return Err(Error::new_spanned(
+                &m.sig.generics,
+                "generic methods are not supported with `gen_trait`",
+            ));
+        }
+        let other_attrs: Vec<_> = m
+            .attrs
+            .iter()
+            .filter(|a| !a.path().is_ident("zbus"))
+            .collect();
+        let member_name = member_name(m, &method_attrs);
+        let args: Vec<_> = m
+            .sig
+            .inputs
+            .iter()
+            .filter_map(typed_arg)
+            .filter_map(pat_ident)
+            .collect();
+        let body = if args.len() == 1 {
+            let arg = &args[0];
+            quote!(&#zbus::zvariant::DynamicTuple((#arg,)))
+        } else {
+            quote!(&#zbus::zvariant::DynamicTuple((#(#args),*)))
+        };
+
+        let mut sig = m.sig.clone();
+        sig.asyncness = Some(Default::default());
+        let method = &sig.ident;
+        let (async_call, mock_call) = if method_attrs.signal {
+            let signal_type = format_ident!("{member_name}");
+            sig.ident = format_ident!("receive_{}", m.sig.ident);
+            sig.inputs = parse_quote!(&self);
+            sig.output = parse_quote! {
+                -> #zbus::Result<
+                    #zbus::export::futures_core::stream::BoxStream<'static, #signal_type>
+                >
+            };
+            let method = &sig.ident;
+
+            (
+                quote! {
+                    let stream = #async_name::#method(self).await?;
+                    ::std::result::Result::Ok(::std::boxed::Box::pin(stream))
+                },
+                quote! {
+                    let stream = self
+                        .0
+                        .receive_signal(#member_name)
+                        .map_signals(#signal_type::from_message);
+                    ::std::result::Result::Ok(::std::boxed::Box::pin(stream))
+                },
+            )
+        } else if method_attrs.property.is_some() {
+            let mock_call = match args.first() {
+                Some(value) => quote!(self.0.set_property(#member_name, #value)),
+                None => quote!(self.0.get_property(#member_name)),
+            };
+
+            (
+                quote!(#async_name::#method(self, #(#args),*).await),
+                quote!(#mock_call.map_err(::std::convert::Into::into)),
+            )
+        } else if method_attrs.object.is_some() {
+            // The trait can't return the object's proxy, since it differs between the async and
+            // blocking proxies, so it returns the path of the object instead.
+            sig.output = parse_quote!(-> #zbus::Result<#zbus::zvariant::OwnedObjectPath>);
+
+            (
+                quote!(self.inner().call(#member_name, #body).await),
+                quote!(self.0.call(#member_name, #body)),
+            )
+        } else {
+            let mock_call = match (&m.sig.output, method_attrs.no_reply) {
+                (ReturnType::Default, _) => quote! {
+                    let _ = self.0.call_noreply(#member_name, #body);
+                },
+                (_, true) => quote! {
+                    self.0
+                        .call_noreply(#member_name, #body)
+                        .map_err(::std::convert::Into::into)
+                },
+                (_, false) => quote! {
+                    self.0
+                        .call(#member_name, #body)
+                        .map_err(::std::convert::Into::into)
+                },
+            };
+
+            (
+                quote!(#async_name::#method(self, #(#args),*).await),
+                mock_call,
+            )
+        };
+        let method = &sig.ident;
+        // Signals are received, so their arguments aren't passed along.
+        let call_args = if method_attrs.signal {
+            &[][..]
+        } else {
+            &args[..]
+        };
+
+        trait_methods.extend(quote! {
+            #(#other_attrs)*
+            #sig;
+        });
+        async_methods.extend(quote! {
+            #sig {
+                #async_call
+            }
+        });
+        blocking_methods.extend(quote! {
+            #sig {
+                let proxy = #async_name::from(::std::clone::Clone::clone(self.inner().inner()));
+                <#async_name<'_> as #trait_name>::#method(&proxy, #(#call_args),*).await
+            }
+        });
+        mock_methods.extend(quote! {
+            #sig {
+                #mock_call
+            }
+        });
+    }
+
+    let trait_doc = format!(
+        " The `{iface_name}` interface.\n\n \
+         This is implemented by the proxies of the interface, and allows to use them as trait \
+         objects, e.g to substitute them with a mock in tests."
+    );
+    let blocking_impl = blocking_name.map(|name| {
+        let blocking_name = Ident::new(name, Span::call_site());
+
+        quote! {
+            #[#zbus::export::async_trait::async_trait]
+            #[allow(deprecated)]
+            impl<'p> #trait_name for #blocking_name<'p> {
+                #blocking_methods
+            }
+        }
+    });
+    let mock = mock_path.map(|path| {
+        let mock_name = format_ident!("{trait_name}Mock");
+        let mock_doc = format!(
+            " An in-memory mock of the `{iface_name}` interface, implementing [`{trait_name}`].\n\n \
+             See [`zbus::proxy::Mock`] for how to program it.",
+        );
+
+        quote! {
+            #[doc = #mock_doc]
+            #[derive(Clone, Debug)]
+            #visibility struct #mock_name(#zbus::proxy::Mock);
+
+            impl #mock_name {
+                /// Create a new mock.
+                pub fn new() -> Self {
+                    Self(#zbus::proxy::Mock::new(
+                        #zbus::names::InterfaceName::from_static_str_unchecked(#iface_name),
+                        #zbus::zvariant::ObjectPath::from_static_str_unchecked(#path),
+                    ))
+                }
+
+                /// The reference to the underlying `zbus::proxy::Mock`.
+                pub fn inner(&self) -> &#zbus::proxy::Mock {
+                    &self.0
+                }
+            }
+
+            impl ::std::default::Default for #mock_name {
+                fn default() -> Self {
+                    Self::new()
+                }
+            }
+
+            #[#zbus::export::async_trait::async_trait]
+            #[allow(deprecated)]
+            impl #trait_name for #mock_name {
+                #mock_methods
+            }
+        }
+    });
+
+    Ok(quote! {
+        #[doc = #trait_doc]
+        #[#zbus::export::async_trait::async_trait]
+        #visibility trait #trait_name:
+            ::std::marker::Send + ::std::marker::Sync
+        {
+            #trait_methods
+        }
+
+        #[#zbus::export::async_trait::async_trait]
+        #[allow(deprecated)]
+        impl<'p> #trait_name for #async_name<'p> {
+            #async_methods
+        }
+
+        #blocking_impl
+
+        #mock
+    })
+}
+
+/// The D-Bus name of the member declared by `m`.
+fn member_name(m: &TraitItemFn, method_attrs: &MethodAttributes) -> String {
+    method_attrs.name.clone().unwrap_or_else(|| {
+        let method_name = m.sig.ident.to_string();
+        let is_setter = method_attrs.property.is_some() && m.sig.inputs.len() > 1;
+
+        case::pascal_or_camel_case(
+            if is_setter {
+                &method_name["set_".len()..]
+            } else {
+                &method_name
+            },
+            true,
+        )
+    })
+}
+
+/// The owned counterpart of a proxy argument type, for the server side.
+fn owned_type(ty: &Type) -> Type {
+    let Type::Reference(r) = ty else {
+        return ty.clone();
+    };
+
+    match &*r.elem {
+        Type::Path(p) if p.path.is_ident("str") => parse_quote!(::std::string::String),
+        Type::Slice(s) => {
+            let elem = &s.elem;
+            parse_quote!(::std::vec::Vec<#elem>)
+        }
+        elem => elem.clone(),
+    }
+}
+
+/// The `T` of a `Result<T>` return type.
+fn result_inner_type(output: &ReturnType) -> Result<&Type, Error> {
+    if let ReturnType::Type(_, ty) = output {
+        if let Type::Path(p) = &**ty {
+            if let Some(segment) = p.path.segments.last().filter(|s| s.ident == "Result") {
+                if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                    if let Some(GenericArgument::Type(ty)) = args.args.first() {
+                        return Ok(ty);
+                    }
+                }
+            }
+        }
+    }
+
+    Err(Error::new_spanned(
+        output,
+        "methods must return a `Result` with `gen_server`",
+    ))
+}
+
 fn gen_proxy_method_call(
     method_name: &str,
     snake_case_name: &str,
@@ -542,6 +1116,28 @@ fn gen_proxy_method_call(
         _ => None,
     };
 
+    let retry_policy = match (method_attrs.no_retry, &method_attrs.retry_policy) {
+        (false, None) => None,
+        _ if method_attrs.no_reply => {
+            return Err(Error::new_spanned(
+                &m.sig,
+                "`no_reply` methods are never retried",
+            ));
+        }
+        (true, None) => Some(quote!(::std::option::Option::None)),
+        (false, Some(policy)) => {
+            let policy = parse_str::<Path>(policy)?;
+
+            Some(quote!(::std::option::Option::Some(&#policy())))
+        }
+        (true, Some(_)) => {
+            return Err(Error::new_spanned(
+                &m.sig,
+                "`no_retry` and `retry_policy` attributes are mutually exclusive",
+            ));
+        }
+    };
+
     let method = Ident::new(snake_case_name, Span::call_site());
     let inputs = &m.sig.inputs;
     let mut generics = m.sig.generics.clone();
@@ -583,6 +1179,22 @@ fn gen_proxy_method_call(
 
     if let Some(proxy_path) = proxy_object {
         let proxy_path = parse_str::<Path>(&proxy_path)?;
+        let body = quote!(&#zbus::zvariant::DynamicTuple((#(#args,)*)));
+        let call = match retry_policy {
+            Some(retry_policy) => quote! {
+                self.0
+                    .call_with_retry_policy(
+                        #method_name,
+                        ::std::default::Default::default(),
+                        #retry_policy,
+                        #body,
+                    )
+                    #wait?
+                    // `NoReplyExpected` is not set here, so there is always a reply.
+                    .expect("no reply without NoReplyExpected")
+            },
+            None => quote!(self.0.call(#method_name, #body)#wait?),
+        };
         let signature = quote! {
             fn #method #ty_generics(#inputs) -> #zbus::Result<#proxy_path<'p>>
             #where_clause
@@ -591,12 +1203,7 @@ fn gen_proxy_method_call(
         Ok(quote! {
             #(#other_attrs)*
             pub #usage #signature {
-                let object_path: #zbus::zvariant::OwnedObjectPath =
-                    self.0.call(
-                        #method_name,
-                        &#zbus::zvariant::DynamicTuple((#(#args,)*)),
-                    )
-                    #wait?;
+                let object_path: #zbus::zvariant::OwnedObjectPath = #call;
                 #proxy_path::builder(&self.0.connection())
                     .path(object_path)?
                     .build()
@@ -623,7 +1230,27 @@ fn gen_proxy_method_call(
             #where_clause
         };
 
-        if let Some(method_flags) = method_flags {
+        if let Some(retry_policy) = retry_policy {
+            let method_flags =
+                method_flags.unwrap_or_else(|| quote!(::std::default::Default::default()));
+
+            Ok(quote! {
+                #(#other_attrs)*
+                pub #usage #signature {
+                    let reply = self.0.call_with_retry_policy(
+                        #method_name,
+                        #method_flags,
+                        #retry_policy,
+                        #body,
+                    )#wait?;
+
+                    // `NoReplyExpected` is not set here, so there is always a reply.
+                    ::std::result::Result::Ok(
+                        reply.expect("no reply without NoReplyExpected"),
+                    )
+                }
+            })
+        } else if let Some(method_flags) = method_flags {
             if method_attrs.no_reply {
                 Ok(quote! {
                     #(#other_attrs)*
@@ -860,26 +1487,32 @@ fn gen_proxy_signal(
         proxy_path,
         receive_signal_link,
         receive_signal_with_args_link,
+        on_signal_link,
         trait_name,
         trait_link,
         signal_type,
+        handler_type,
     ) = if *blocking {
         (
             "zbus::blocking::Proxy",
             "https://docs.rs/zbus/latest/zbus/blocking/proxy/struct.Proxy.html#method.receive_signal",
             "https://docs.rs/zbus/latest/zbus/blocking/proxy/struct.Proxy.html#method.receive_signal_with_args",
+            "https://docs.rs/zbus/latest/zbus/blocking/proxy/struct.Proxy.html#method.on_signal",
             "Iterator",
             "https://doc.rust-lang.org/std/iter/trait.Iterator.html",
             quote! { blocking::proxy::SignalIterator },
+            quote! { blocking::connection::SignalHandler },
         )
     } else {
         (
             "zbus::Proxy",
             "https://docs.rs/zbus/latest/zbus/proxy/struct.Proxy.html#method.receive_signal",
             "https://docs.rs/zbus/latest/zbus/proxy/struct.Proxy.html#method.receive_signal_with_args",
+            "https://docs.rs/zbus/latest/zbus/proxy/struct.Proxy.html#method.on_signal",
             "Stream",
             "https://docs.rs/futures/0.3.15/futures/stream/trait.Stream.html",
             quote! { proxy::SignalStream },
+            quote! { connection::SignalHandler },
         )
     };
     let receiver_name = format_ident!("receive_{snake_case_name}");
@@ -910,6 +1543,12 @@ fn gen_proxy_signal(
             }
         }
     };
+    let on_signal_name = format_ident!("on_{snake_case_name}");
+    let on_signal_gen_doc = format!(
+        "Call `handler` for each `{signal_name}` signal, for as long as the returned guard is alive.\n\
+            \n\
+            This a convenient wrapper around [`{proxy_path}::on_signal`]({on_signal_link}).",
+    );
     let receive_signal = quote! {
         #[doc = #receive_gen_doc]
         #(#other_attrs)*
@@ -919,6 +1558,18 @@ fn gen_proxy_signal(
         }
 
         #receive_signal_with_args
+
+        #[doc = #on_signal_gen_doc]
+        #(#other_attrs)*
+        pub #usage fn #on_signal_name<F>(&self, mut handler: F) -> #zbus::Result<#zbus::#handler_type>
+        where
+            F: ::std::ops::FnMut(#signal_name_ident) + ::std::marker::Send + 'static,
+        {
+            self.0
+                .on_signal(#signal_name, move |msg: #zbus::message::Message| {
+                    handler(#signal_name_ident(msg.body()))
+                })#wait
+        }
     };
 
     let stream_gen_doc = format!(
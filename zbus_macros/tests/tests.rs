@@ -312,6 +312,164 @@ fn test_interface() {
     }
 }
 
+#[test]
+fn test_interface_annotations() {
+    use zbus::object_server::Interface;
+
+    struct Annotated;
+
+    #[interface(
+        name = "org.freedesktop.zbus.Annotated",
+        annotation(name = "org.freedesktop.zbus.Stable", value = "<yes & no>"),
+        proxy
+    )]
+    impl Annotated {
+        #[zbus(
+            deprecated,
+            annotation(name = "org.freedesktop.DBus.Method.NoReply", value = "true")
+        )]
+        fn old_method(
+            &self,
+            #[zbus(annotation(name = "org.freedesktop.zbus.Unit", value = "seconds"))] timeout: u32,
+        ) {
+            let _ = timeout;
+        }
+
+        #[zbus(deprecated, property)]
+        fn old_prop(&self) -> u32 {
+            0
+        }
+
+        #[zbus(
+            property,
+            annotation(name = "org.freedesktop.zbus.Unit", value = "bytes")
+        )]
+        fn set_old_prop(&self, _val: u32) {}
+
+        #[zbus(
+            signal,
+            annotation(name = "org.freedesktop.zbus.First", value = "1"),
+            annotation(name = "org.freedesktop.zbus.Second", value = "2")
+        )]
+        async fn annotated_signal(emitter: &SignalEmitter<'_>) -> zbus::Result<()>;
+    }
+
+    const EXPECTED_XML: &str = r#"<interface name="org.freedesktop.zbus.Annotated">
+  <annotation name="org.freedesktop.zbus.Stable" value="&lt;yes &amp; no&gt;"/>
+  <method name="OldMethod">
+    <arg name="timeout" type="u" direction="in">
+      <annotation name="org.freedesktop.zbus.Unit" value="seconds"/>
+    </arg>
+    <annotation name="org.freedesktop.DBus.Deprecated" value="true"/>
+    <annotation name="org.freedesktop.DBus.Method.NoReply" value="true"/>
+  </method>
+  <signal name="AnnotatedSignal">
+    <annotation name="org.freedesktop.zbus.First" value="1"/>
+    <annotation name="org.freedesktop.zbus.Second" value="2"/>
+  </signal>
+  <property name="OldProp" type="u" access="readwrite">
+    <annotation name="org.freedesktop.DBus.Deprecated" value="true"/>
+    <annotation name="org.freedesktop.zbus.Unit" value="bytes"/>
+  </property>
+</interface>
+"#;
+    let mut xml = String::new();
+    Annotated.introspect_to_writer(&mut xml, 0);
+    assert_eq!(xml, EXPECTED_XML);
+
+    if false {
+        block_on(async {
+            // check compilation of the deprecated proxy methods.
+            let c = zbus::Connection::session().await.unwrap();
+            let proxy = AnnotatedProxy::new(&c, "org.freedesktop.zbus", "/org/freedesktop/zbus")
+                .await
+                .unwrap();
+            #[allow(deprecated)]
+            {
+                proxy.old_method(1).await.unwrap();
+                proxy.old_prop().await.unwrap();
+                proxy.set_old_prop(2).await.unwrap();
+            }
+            proxy.receive_annotated_signal().await.unwrap();
+        });
+    }
+}
+
+#[test]
+fn test_annotations_round_trip() {
+    use zbus::object_server::Interface;
+
+    struct Deprecated;
+
+    // The annotations of the interface are carried over to the proxy.
+    #[interface(
+        name = "org.freedesktop.zbus.Deprecated",
+        deprecated,
+        annotation(name = "org.freedesktop.zbus.Stable", value = "no"),
+        proxy(default_path = "/org/freedesktop/zbus/Deprecated")
+    )]
+    impl Deprecated {
+        #[zbus(annotation(name = "org.freedesktop.DBus.Method.NoReply", value = "true"))]
+        fn method(
+            &self,
+            #[zbus(annotation(name = "org.freedesktop.zbus.Unit", value = "seconds"))] timeout: u32,
+        ) {
+            let _ = timeout;
+        }
+    }
+
+    // And from the proxy to the interface generated from it.
+    #[proxy(
+        interface = "org.freedesktop.zbus.Deprecated",
+        default_path = "/org/freedesktop/zbus/Deprecated",
+        gen_server = true,
+        deprecated,
+        annotation(name = "org.freedesktop.zbus.Stable", value = "no")
+    )]
+    trait RoundTrip {
+        #[zbus(annotation(name = "org.freedesktop.DBus.Method.NoReply", value = "true"))]
+        fn method(
+            &self,
+            #[zbus(annotation(name = "org.freedesktop.zbus.Unit", value = "seconds"))] timeout: u32,
+        ) -> zbus::Result<()>;
+    }
+
+    struct RoundTripImpl;
+
+    impl RoundTripServer for RoundTripImpl {
+        async fn method(&self, _timeout: u32) -> fdo::Result<()> {
+            Ok(())
+        }
+    }
+
+    let mut expected = String::new();
+    Deprecated.introspect_to_writer(&mut expected, 0);
+    assert!(
+        expected.contains(r#"<annotation name="org.freedesktop.DBus.Deprecated" value="true"/>"#)
+    );
+    let mut xml = String::new();
+    RoundTripInterface(RoundTripImpl).introspect_to_writer(&mut xml, 0);
+    assert_eq!(xml, expected);
+
+    if false {
+        block_on(async {
+            // check compilation of the deprecated proxies.
+            let c = zbus::Connection::session().await.unwrap();
+            #[allow(deprecated)]
+            {
+                let proxy = DeprecatedProxy::new(&c, "org.freedesktop.zbus")
+                    .await
+                    .unwrap();
+                proxy.method(1).await.unwrap();
+                let proxy = RoundTripProxy::new(&c, "org.freedesktop.zbus")
+                    .await
+                    .unwrap();
+                proxy.method(1).await.unwrap();
+            }
+        });
+    }
+}
+
 mod signal_from_message {
     use super::*;
     use zbus::message::Message;
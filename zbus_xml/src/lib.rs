@@ -236,7 +236,7 @@ impl<'a> Interface<'a> {
     }
 
     /// Returns the interface properties.
-    pub fn properties(&self) -> &[Property<'_>] {
+    pub fn properties(&self) -> &[Property<'a>] {
         &self.properties
     }
 
@@ -59,7 +59,7 @@ fn dict_value() {
     assert_eq!(map[&2], "456");
     // Use iterator
     let mut dict = Dict::from(map);
-    let expect = vec![
+    let expect = [
         (Value::from(1i64), Value::from("123")),
         (Value::from(2i64), Value::from("456")),
     ];
@@ -2,8 +2,8 @@ use zvariant::{BE, LE};
 
 #[test]
 fn i64_value() {
-    let encoded = basic_type_test!(BE, DBus, -0xABBA_ABBA_ABBA_AB0_i64, 8, i64, 8, I64, 16);
+    let encoded = basic_type_test!(BE, DBus, -0xABB_AABB_AABB_AAB0_i64, 8, i64, 8, I64, 16);
     assert_eq!(LE.read_i64(&encoded), 0x5055_4455_4455_44F5_i64);
     #[cfg(feature = "gvariant")]
-    basic_type_test!(BE, GVariant, -0xABBA_ABBA_ABBA_AB0_i64, 8, i64, 8, I64, 10);
+    basic_type_test!(BE, GVariant, -0xABB_AABB_AABB_AAB0_i64, 8, i64, 8, I64, 10);
 }
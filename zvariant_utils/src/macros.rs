@@ -90,6 +90,28 @@ pub fn match_attribute_with_bool_value<'a>(
     }
 }
 
+/// Compares `ident` and `attr` and in case they match ensures `value` is `Some` and contains a
+/// [`struct@syn::LitInt`] that fits in a `u64`. Returns the parsed value in case `ident` and `attr`
+/// match, otherwise `None`.
+///
+/// # Errors
+///
+/// Returns an error in case `ident` and `attr` match but the value is not `Some` or is not a
+/// [`struct@syn::LitInt`] that fits in a `u64`.
+pub fn match_attribute_with_int_value(meta: &Meta, attr: &str) -> Result<Option<u64>> {
+    if !meta.path().is_ident(attr) {
+        return Ok(None);
+    }
+
+    match get_meta_value(meta, attr)? {
+        Lit::Int(value) => value.base10_parse().map(Some),
+        other => Err(syn::Error::new(
+            other.span(),
+            format!("value of the `{attr}` attribute must be an integer literal"),
+        )),
+    }
+}
+
 pub fn match_attribute_with_str_list_value(meta: &Meta, attr: &str) -> Result<Option<Vec<String>>> {
     if meta.path().is_ident(attr) {
         let list = meta.require_list()?;
@@ -184,6 +206,7 @@ pub fn iter_meta_lists(
 ///
 /// * `str` - string literals;
 /// * `bool` - boolean literals;
+/// * `int` - integer literals, stored as `u64`;
 /// * `[str]` - lists of string literals (`#[macro_name(foo("bar", "baz"))]`);
 /// * `none` - no literal at all, the attribute is specified alone.
 ///
@@ -216,6 +239,24 @@ pub fn iter_meta_lists(
 /// The syntax for inner attributes is the same as for the outer attributes, but you can specify
 /// only one inner attribute per outer attribute.
 ///
+/// If the nested list is wrapped in brackets, the attribute can be specified multiple times and
+/// the generated field is a `Vec` of all the occurrences:
+///
+/// ```
+/// # use zvariant_utils::def_attrs;
+/// def_attrs! {
+///     crate zvariant;
+///
+///     pub OuterAttributes("outer") {
+///         repeated_attr [{
+///             pub RepeatedAttributes("repeated") {
+///                 inner_attr str
+///             }
+///         }]
+///     };
+/// }
+/// ```
+///
 /// # Using attribute names for attribute lists
 ///
 /// It is possible to use multiple different "crate" names as follows:
@@ -256,8 +297,15 @@ pub fn iter_meta_lists(
 macro_rules! def_attrs {
     (@attr_ty str) => {::std::option::Option<::std::string::String>};
     (@attr_ty bool) => {::std::option::Option<bool>};
+    (@attr_ty int) => {::std::option::Option<u64>};
     (@attr_ty [str]) => {::std::option::Option<::std::vec::Vec<::std::string::String>>};
     (@attr_ty none) => {bool};
+    (@attr_ty [{
+        $(#[$m:meta])*
+        $vis:vis $name:ident($what:literal) {
+            $($attr_name:ident $kind:tt),+
+        }
+    }]) => {::std::vec::Vec<$name>};
     (@attr_ty {
         $(#[$m:meta])*
         $vis:vis $name:ident($what:literal) {
@@ -301,6 +349,22 @@ macro_rules! def_attrs {
             )
         )
     };
+    (@match_attr int $attr_name:ident, $meta:ident, $self:ident) => {
+        if let ::std::option::Option::Some(value) = $crate::macros::match_attribute_with_int_value(
+            $meta,
+            ::std::stringify!($attr_name),
+        )? {
+            if $self.$attr_name.is_some() {
+                return ::std::result::Result::Err(::syn::Error::new(
+                    $meta.span(),
+                    ::std::concat!("duplicate `", ::std::stringify!($attr_name), "` attribute")
+                ));
+            }
+
+            $self.$attr_name = ::std::option::Option::Some(value);
+            return Ok(());
+        }
+    };
     (@match_attr [str] $attr_name:ident, $meta:ident, $self:ident) => {
         if let Some(list) = $crate::macros::match_attribute_with_str_list_value(
             $meta,
@@ -333,6 +397,27 @@ macro_rules! def_attrs {
             return Ok(());
         }
     };
+    (@match_attr [{
+        $(#[$m:meta])*
+        $vis:vis $name:ident($what:literal) $body:tt
+    }] $attr_name:ident, $meta:expr, $self:ident) => {
+        if $meta.path().is_ident(::std::stringify!($attr_name)) {
+            return match $meta {
+                ::syn::Meta::List(meta) => {
+                    $self.$attr_name.push($name::parse_nested_metas(
+                        meta.parse_args_with(::syn::punctuated::Punctuated::<::syn::Meta, ::syn::Token![,]>::parse_terminated)?
+                    )?);
+                    ::std::result::Result::Ok(())
+                }
+                _ => Err(::syn::Error::new(
+                    $meta.span(),
+                    ::std::format!(::std::concat!(
+                        "attribute `", ::std::stringify!($attr_name), "` must be a list"
+                    )),
+                ))
+            };
+        }
+    };
     (@match_attr {
         $(#[$m:meta])*
         $vis:vis $name:ident($what:literal) $body:tt
@@ -368,8 +453,12 @@ macro_rules! def_attrs {
     };
     (@def_ty str) => {};
     (@def_ty bool) => {};
+    (@def_ty int) => {};
     (@def_ty [str]) => {};
     (@def_ty none) => {};
+    (@def_ty [$nested:tt]) => {
+        $crate::def_attrs!(@def_ty $nested);
+    };
     (
         @def_ty {
             $(#[$m:meta])*